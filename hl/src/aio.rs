@@ -0,0 +1,381 @@
+//! Asynchronous W5500 socket traits.
+//!
+//! These mirror the [`Tcp`](crate::Tcp) and [`Udp`](crate::Udp) traits for
+//! the asynchronous [`Registers`] trait provided in [`w5500-ll`].
+//!
+//! [`w5500-ll`]: https://crates.io/crates/w5500-ll
+use crate::{port_is_free, Error, UdpHeader};
+use core::cmp::min;
+use w5500_ll::{
+    aio::Registers, net::SocketAddrV4, Protocol, Sn, SocketCommand, SocketMode, SocketStatus,
+    SOCKETS,
+};
+
+async fn port_is_unique<T, E>(w5500: &mut T, socket: Sn, port: u16) -> Result<bool, E>
+where
+    T: Registers<Error = E> + ?Sized,
+{
+    for socket in SOCKETS.iter().filter(|s| s != &&socket) {
+        if w5500.sn_port(*socket).await? == port && !port_is_free(w5500.sn_sr(*socket).await?) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// An asynchronous W5500 TCP trait.
+///
+/// This is the asynchronous version of [`crate::Tcp`].
+pub trait Tcp: Registers {
+    /// Starts the 3-way TCP handshake with the remote host.
+    ///
+    /// See [`crate::Tcp::tcp_connect`].
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The port must not be in use by any other socket on the W5500.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn example() -> Result<(), embedded_hal::spi::ErrorKind> {
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{
+    ///     aio::Tcp,
+    ///     ll::Sn,
+    ///     net::{Ipv4Addr, SocketAddrV4},
+    /// };
+    ///
+    /// const MQTT_SERVER: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 168, 2, 10), 1883);
+    ///
+    /// w5500.tcp_connect(Sn::Sn0, 33650, &MQTT_SERVER).await?;
+    /// # Ok(()) }
+    /// ```
+    async fn tcp_connect(
+        &mut self,
+        sn: Sn,
+        port: u16,
+        addr: &SocketAddrV4,
+    ) -> Result<(), Self::Error> {
+        debug_assert!(
+            port_is_unique(self, sn, port).await?,
+            "Local port {port} is in use"
+        );
+
+        const MODE: SocketMode = SocketMode::DEFAULT.set_protocol(Protocol::Tcp);
+        let status: Result<SocketStatus, u8> = self.open_socket(sn, MODE, port).await?;
+        debug_assert_eq!(status, Ok(SocketStatus::Init));
+        self.set_sn_dest(sn, addr).await?;
+        self.set_sn_cr(sn, SocketCommand::Connect).await
+    }
+
+    /// Open a TCP listener on the given port.
+    ///
+    /// See [`crate::Tcp::tcp_listen`].
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The port must not be in use by any other socket on the W5500.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn example() -> Result<(), embedded_hal::spi::ErrorKind> {
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{aio::Tcp, ll::Sn};
+    ///
+    /// w5500.tcp_listen(Sn::Sn1, 80).await?;
+    /// # Ok(()) }
+    /// ```
+    async fn tcp_listen(&mut self, sn: Sn, port: u16) -> Result<(), Self::Error> {
+        debug_assert!(
+            port_is_unique(self, sn, port).await?,
+            "Local port {port} is in use"
+        );
+
+        const MODE: SocketMode = SocketMode::DEFAULT.set_protocol(Protocol::Tcp);
+        let status: Result<SocketStatus, u8> = self.open_socket(sn, MODE, port).await?;
+        debug_assert_eq!(status, Ok(SocketStatus::Init));
+        self.set_sn_cr(sn, SocketCommand::Listen).await
+    }
+
+    /// Read data from the remote host, returning the number of bytes read.
+    ///
+    /// See [`crate::Tcp::tcp_read`].
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The socket must be an [`Established`] TCP socket.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn example() -> Result<(), embedded_hal::spi::ErrorKind> {
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{aio::Tcp, ll::Sn};
+    ///
+    /// // ... wait for a RECV interrupt
+    ///
+    /// let mut buf = [0; 10];
+    /// let rx_bytes: u16 = w5500.tcp_read(Sn::Sn0, &mut buf).await?;
+    /// let filled_buf = &buf[..rx_bytes.into()];
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// [`Established`]: w5500_ll::SocketStatus::Established
+    async fn tcp_read(&mut self, sn: Sn, buf: &mut [u8]) -> Result<u16, Self::Error> {
+        debug_assert!(!matches!(
+            self.sn_sr(sn).await?,
+            Ok(SocketStatus::Udp) | Ok(SocketStatus::Init) | Ok(SocketStatus::Macraw)
+        ));
+
+        let rx_bytes: u16 = {
+            let rsr: u16 = self.sn_rx_rsr(sn).await?;
+            min(rsr, u16::try_from(buf.len()).unwrap_or(u16::MAX))
+        };
+        if rx_bytes != 0 {
+            let ptr: u16 = self.sn_rx_rd(sn).await?;
+            self.sn_rx_buf(sn, ptr, &mut buf[..usize::from(rx_bytes)])
+                .await?;
+            self.set_sn_rx_rd(sn, ptr.wrapping_add(rx_bytes)).await?;
+            self.set_sn_cr(sn, SocketCommand::Recv).await?;
+        }
+        Ok(rx_bytes)
+    }
+
+    /// Send data to the remote host, returning the number of bytes written.
+    ///
+    /// See [`crate::Tcp::tcp_write`].
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The socket must be an [`Established`] TCP socket.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn example() -> Result<(), embedded_hal::spi::ErrorKind> {
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{aio::Tcp, ll::Sn};
+    ///
+    /// let buf: [u8; 10] = [0; 10];
+    /// let tx_bytes: u16 = w5500.tcp_write(Sn::Sn0, &buf).await?;
+    /// assert_eq!(usize::from(tx_bytes), buf.len());
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// [`Established`]: w5500_ll::SocketStatus::Established
+    async fn tcp_write(&mut self, sn: Sn, buf: &[u8]) -> Result<u16, Self::Error> {
+        debug_assert!(!matches!(
+            self.sn_sr(sn).await?,
+            Ok(SocketStatus::Udp) | Ok(SocketStatus::Init) | Ok(SocketStatus::Macraw)
+        ));
+
+        let tx_bytes: u16 = {
+            let data_len: u16 = u16::try_from(buf.len()).unwrap_or(u16::MAX);
+            let free_size: u16 = self.sn_tx_fsr(sn).await?;
+            min(data_len, free_size)
+        };
+        if tx_bytes != 0 {
+            let ptr: u16 = self.sn_tx_wr(sn).await?;
+            self.set_sn_tx_buf(sn, ptr, &buf[..usize::from(tx_bytes)])
+                .await?;
+            self.set_sn_tx_wr(sn, ptr.wrapping_add(tx_bytes)).await?;
+            self.set_sn_cr(sn, SocketCommand::Send).await?;
+        }
+        Ok(tx_bytes)
+    }
+
+    /// Disconnect from the peer.
+    ///
+    /// See [`crate::Tcp::tcp_disconnect`].
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The socket must be an [`Established`] TCP socket.
+    ///
+    /// [`Established`]: w5500_ll::SocketStatus::Established
+    async fn tcp_disconnect(&mut self, sn: Sn) -> Result<(), Self::Error> {
+        debug_assert!(!matches!(
+            self.sn_sr(sn).await?,
+            Ok(SocketStatus::Udp) | Ok(SocketStatus::Init) | Ok(SocketStatus::Macraw)
+        ));
+        self.set_sn_cr(sn, SocketCommand::Disconnect).await
+    }
+}
+
+/// Implement the asynchronous TCP trait for any structure that implements [`w5500_ll::aio::Registers`].
+impl<T> Tcp for T where T: Registers {}
+
+/// An asynchronous W5500 UDP trait.
+///
+/// This is the asynchronous version of [`crate::Udp`].
+pub trait Udp: Registers {
+    /// Binds the socket to the given port.
+    ///
+    /// See [`crate::Udp::udp_bind`].
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The port must not be in use by any other socket on the W5500.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn example() -> Result<(), embedded_hal::spi::ErrorKind> {
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{aio::Udp, ll::Sn::Sn0};
+    ///
+    /// w5500.udp_bind(Sn0, 8080).await?;
+    /// # Ok(()) }
+    /// ```
+    async fn udp_bind(&mut self, sn: Sn, port: u16) -> Result<(), Self::Error> {
+        debug_assert!(
+            port_is_unique(self, sn, port).await?,
+            "Local port {port} is in use"
+        );
+
+        const MODE: SocketMode = SocketMode::DEFAULT.set_protocol(Protocol::Udp);
+        let status: Result<SocketStatus, u8> = self.open_socket(sn, MODE, port).await?;
+        debug_assert_eq!(status, Ok(SocketStatus::Udp));
+        Ok(())
+    }
+
+    /// Receives a single datagram message on the socket.
+    /// On success, returns the number of bytes read and the origin.
+    ///
+    /// See [`crate::Udp::udp_recv_from`].
+    ///
+    /// Like the synchronous method this returns [`Error::WouldBlock`] instead
+    /// of waiting when a complete datagram has not been received.
+    /// Wait for the socket [`recv`] interrupt before calling this method to
+    /// avoid repeatedly polling the W5500.
+    ///
+    /// If a message is too long to fit in the supplied buffer, excess bytes
+    /// will be discarded.
+    ///
+    /// # Errors
+    ///
+    /// This method can only return:
+    ///
+    /// * [`Error::Other`]
+    /// * [`Error::WouldBlock`]
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The socket must be opened as a UDP socket.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn example() -> Result<(), w5500_hl::Error<embedded_hal::spi::ErrorKind>> {
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{aio::Udp, ll::Sn::Sn0};
+    ///
+    /// w5500.udp_bind(Sn0, 8080).await?;
+    /// let mut buf = [0; 10];
+    /// let (number_of_bytes, src_addr) = w5500.udp_recv_from(Sn0, &mut buf).await?;
+    /// let filled_buf = &mut buf[..number_of_bytes.into()];
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// [`recv`]: w5500_ll::SocketInterrupt::recv_raised
+    async fn udp_recv_from(
+        &mut self,
+        sn: Sn,
+        buf: &mut [u8],
+    ) -> Result<(u16, SocketAddrV4), Error<Self::Error>> {
+        let rsr: u16 = match self.sn_rx_rsr(sn).await?.checked_sub(UdpHeader::LEN) {
+            Some(rsr) => rsr,
+            // nothing to recieve
+            None => return Err(Error::WouldBlock),
+        };
+
+        debug_assert_eq!(self.sn_sr(sn).await?, Ok(SocketStatus::Udp));
+
+        let mut ptr: u16 = self.sn_rx_rd(sn).await?;
+        let mut header: [u8; UdpHeader::LEN_USIZE] = [0; UdpHeader::LEN_USIZE];
+        self.sn_rx_buf(sn, ptr, &mut header).await?;
+        ptr = ptr.wrapping_add(UdpHeader::LEN);
+        let header: UdpHeader = UdpHeader::deser(header);
+
+        // not all data as indicated by the header has been buffered
+        if rsr < header.len {
+            return Err(Error::WouldBlock);
+        }
+
+        let read_size: u16 = min(header.len, buf.len().try_into().unwrap_or(u16::MAX));
+        if read_size != 0 {
+            self.sn_rx_buf(sn, ptr, &mut buf[..read_size.into()])
+                .await?;
+        }
+        ptr = ptr.wrapping_add(header.len);
+        self.set_sn_rx_rd(sn, ptr).await?;
+        self.set_sn_cr(sn, SocketCommand::Recv).await?;
+        Ok((read_size, header.origin))
+    }
+
+    /// Sends data on the socket to the given address.
+    /// On success, returns the number of bytes written.
+    ///
+    /// See [`crate::Udp::udp_send_to`].
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The socket must be opened as a UDP socket.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn example() -> Result<(), embedded_hal::spi::ErrorKind> {
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{
+    ///     aio::Udp,
+    ///     ll::Sn::Sn0,
+    ///     net::{Ipv4Addr, SocketAddrV4},
+    /// };
+    ///
+    /// const DEST: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 8081);
+    ///
+    /// w5500.udp_bind(Sn0, 8080).await?;
+    /// let buf: [u8; 10] = [0; 10];
+    /// let tx_bytes: u16 = w5500.udp_send_to(Sn0, &buf, &DEST).await?;
+    /// assert_eq!(usize::from(tx_bytes), buf.len());
+    /// # Ok(()) }
+    /// ```
+    async fn udp_send_to(
+        &mut self,
+        sn: Sn,
+        buf: &[u8],
+        addr: &SocketAddrV4,
+    ) -> Result<u16, Self::Error> {
+        self.set_sn_dest(sn, addr).await?;
+        self.udp_send(sn, buf).await
+    }
+
+    /// Sends data to the currently configured destination.
+    /// On success, returns the number of bytes written.
+    ///
+    /// See [`crate::Udp::udp_send`].
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The socket must be opened as a UDP socket.
+    async fn udp_send(&mut self, sn: Sn, buf: &[u8]) -> Result<u16, Self::Error> {
+        debug_assert_eq!(self.sn_sr(sn).await?, Ok(SocketStatus::Udp));
+
+        let data_len: u16 = u16::try_from(buf.len()).unwrap_or(u16::MAX);
+        let free_size: u16 = self.sn_tx_fsr(sn).await?;
+        let tx_bytes: u16 = min(data_len, free_size);
+        if tx_bytes != 0 {
+            let ptr: u16 = self.sn_tx_wr(sn).await?;
+            self.set_sn_tx_buf(sn, ptr, &buf[..tx_bytes.into()]).await?;
+            self.set_sn_tx_wr(sn, ptr.wrapping_add(tx_bytes)).await?;
+            self.set_sn_cr(sn, SocketCommand::Send).await?;
+        }
+        Ok(tx_bytes)
+    }
+}
+
+/// Implement the asynchronous UDP trait for any structure that implements [`w5500_ll::aio::Registers`].
+impl<T> Udp for T where T: Registers {}
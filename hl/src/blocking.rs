@@ -0,0 +1,186 @@
+//! Blocking wrappers for the non-blocking socket methods.
+//!
+//! These poll the non-blocking methods until data is available, or until
+//! there is free space to write data, optionally giving up after a maximum
+//! number of polls.
+//! This is the same as the [`block!`](crate::block) macro with an optional
+//! limit, and handles the TCP methods that return `0` instead of
+//! [`Error::WouldBlock`] when there is no data.
+//!
+//! There is no delay between polls, the time taken by each poll depends on
+//! the SPI clock.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+//! use w5500_hl::{
+//!     blocking,
+//!     ll::Sn,
+//!     net::SocketAddrV4,
+//!     Udp,
+//! };
+//!
+//! w5500.udp_bind(Sn::Sn0, 8080)?;
+//!
+//! let mut buf: [u8; 16] = [0; 16];
+//! let (len, origin): (u16, SocketAddrV4) =
+//!     blocking::udp_recv_from(&mut w5500, Sn::Sn0, &mut buf, None)?;
+//! # Ok::<(), w5500_hl::Error<_>>(())
+//! ```
+
+use crate::{Error, Tcp, Udp};
+use w5500_ll::{net::SocketAddrV4, Sn, SocketStatus};
+
+/// Returns `true` if another poll is allowed, counting the poll.
+fn poll_allowed(polls: &mut u32, max_polls: Option<u32>) -> bool {
+    match max_polls {
+        Some(max) if *polls >= max => false,
+        _ => {
+            *polls = polls.saturating_add(1);
+            true
+        }
+    }
+}
+
+/// Receive a UDP datagram, blocking until one is available.
+///
+/// This calls [`Udp::udp_recv_from`] until it returns a datagram or an error
+/// other than [`Error::WouldBlock`].
+///
+/// # Errors
+///
+/// * [`Error::WouldBlock`] if no datagram was received after `max_polls`
+///   calls, `None` polls without a limit.
+/// * Any other error from [`Udp::udp_recv_from`].
+///
+/// # Example
+///
+/// ```no_run
+/// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+/// use w5500_hl::{blocking, ll::Sn, Error};
+///
+/// let mut buf: [u8; 16] = [0; 16];
+/// match blocking::udp_recv_from(&mut w5500, Sn::Sn0, &mut buf, Some(10_000)) {
+///     Ok((len, origin)) => (),
+///     Err(Error::WouldBlock) => (), // nothing received
+///     Err(e) => return Err(e),
+/// }
+/// # Ok::<(), w5500_hl::Error<_>>(())
+/// ```
+pub fn udp_recv_from<T: Udp + ?Sized>(
+    w5500: &mut T,
+    sn: Sn,
+    buf: &mut [u8],
+    max_polls: Option<u32>,
+) -> Result<(u16, SocketAddrV4), Error<T::Error>> {
+    let mut polls: u32 = 0;
+    while poll_allowed(&mut polls, max_polls) {
+        match w5500.udp_recv_from(sn, buf) {
+            Err(Error::WouldBlock) => (),
+            result => return result,
+        }
+    }
+    Err(Error::WouldBlock)
+}
+
+/// Read data from the remote host, blocking until data is available.
+///
+/// This calls [`Tcp::tcp_read`] until it reads at least one byte, returning
+/// the number of bytes read.
+///
+/// # Errors
+///
+/// * [`Error::Disconnected`] if there is no data and the socket is neither
+///   connected nor waiting for a connection, this includes the remote host
+///   closing the connection.
+/// * [`Error::WouldBlock`] if no data was read after `max_polls` calls,
+///   `None` polls without a limit.
+/// * [`Error::Other`] for errors from the [`Registers`](w5500_ll::Registers)
+///   implementation.
+///
+/// # Example
+///
+/// ```no_run
+/// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+/// use w5500_hl::{blocking, ll::Sn};
+///
+/// let mut buf: [u8; 64] = [0; 64];
+/// let len: u16 = blocking::tcp_read(&mut w5500, Sn::Sn0, &mut buf, None)?;
+/// # Ok::<(), w5500_hl::Error<embedded_hal::spi::ErrorKind>>(())
+/// ```
+pub fn tcp_read<T: Tcp + ?Sized>(
+    w5500: &mut T,
+    sn: Sn,
+    buf: &mut [u8],
+    max_polls: Option<u32>,
+) -> Result<u16, Error<T::Error>> {
+    let mut polls: u32 = 0;
+    while poll_allowed(&mut polls, max_polls) {
+        // The status is read before the data so that data received before
+        // the remote host closed the connection is always returned.
+        let connected: bool = matches!(
+            w5500.sn_sr(sn)?,
+            Ok(SocketStatus::Listen)
+                | Ok(SocketStatus::SynSent)
+                | Ok(SocketStatus::SynRecv)
+                | Ok(SocketStatus::Established)
+        );
+        let rx_bytes: u16 = w5500.tcp_read(sn, buf)?;
+        if rx_bytes != 0 || buf.is_empty() {
+            return Ok(rx_bytes);
+        }
+        if !connected {
+            return Err(Error::Disconnected);
+        }
+    }
+    Err(Error::WouldBlock)
+}
+
+/// Write data to the remote host, blocking until there is free space in the
+/// socket TX buffer.
+///
+/// This calls [`Tcp::tcp_write`] until it writes at least one byte, returning
+/// the number of bytes written.
+///
+/// # Errors
+///
+/// * [`Error::Disconnected`] if the socket is not
+///   [`Established`](SocketStatus::Established) or
+///   [`CloseWait`](SocketStatus::CloseWait), for example when the connection
+///   times out while waiting for free space in the TX buffer.
+/// * [`Error::WouldBlock`] if no data was written after `max_polls` calls,
+///   `None` polls without a limit.
+/// * [`Error::Other`] for errors from the [`Registers`](w5500_ll::Registers)
+///   implementation.
+///
+/// # Example
+///
+/// ```no_run
+/// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+/// use w5500_hl::{blocking, ll::Sn};
+///
+/// let len: u16 = blocking::tcp_write(&mut w5500, Sn::Sn0, b"hello", None)?;
+/// # Ok::<(), w5500_hl::Error<embedded_hal::spi::ErrorKind>>(())
+/// ```
+pub fn tcp_write<T: Tcp + ?Sized>(
+    w5500: &mut T,
+    sn: Sn,
+    buf: &[u8],
+    max_polls: Option<u32>,
+) -> Result<u16, Error<T::Error>> {
+    let mut polls: u32 = 0;
+    while poll_allowed(&mut polls, max_polls) {
+        // The socket status will change to closed if the remote host
+        // stops acknowledging data, preventing an infinite loop.
+        match w5500.sn_sr(sn)? {
+            Ok(SocketStatus::Established) | Ok(SocketStatus::CloseWait) => (),
+            _ => return Err(Error::Disconnected),
+        }
+        let tx_bytes: u16 = w5500.tcp_write(sn, buf)?;
+        if tx_bytes != 0 || buf.is_empty() {
+            return Ok(tx_bytes);
+        }
+    }
+    Err(Error::WouldBlock)
+}
@@ -0,0 +1,142 @@
+//! Internet checksums for building frames in MACRAW mode.
+//!
+//! In MACRAW mode the W5500 sends and receives raw ethernet frames, the IPv4
+//! and UDP checksums that are normally calculated by the W5500 must be
+//! calculated in software.
+
+use w5500_ll::net::Ipv4Addr;
+
+/// IPv4 protocol number for UDP.
+const UDP_PROTOCOL: u8 = 17;
+
+/// Add `data` to a one's complement sum of 16-bit big endian words.
+///
+/// An odd length is padded with a trailing zero byte.
+const fn sum_words(mut sum: u32, data: &[u8]) -> u32 {
+    let mut idx: usize = 0;
+    while idx + 1 < data.len() {
+        sum += u16::from_be_bytes([data[idx], data[idx + 1]]) as u32;
+        idx += 2;
+    }
+    if idx < data.len() {
+        sum += u16::from_be_bytes([data[idx], 0]) as u32;
+    }
+    sum
+}
+
+/// Fold the carries into a 16-bit one's complement sum, and complement it.
+const fn finish(mut sum: u32) -> u16 {
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+/// Calculate the checksum of an IPv4 header.
+///
+/// The checksum field of `header` must be zero, or the result will be zero
+/// if the checksum field is already correct.
+///
+/// # Example
+///
+/// ```
+/// use w5500_hl::checksum::ipv4_header_checksum;
+///
+/// let mut header: [u8; 20] = [
+///     0x45, 0x00, 0x00, 0x73, 0x00, 0x00, 0x40, 0x00, 0x40, 0x11, 0x00, 0x00, 0xC0, 0xA8, 0x00,
+///     0x01, 0xC0, 0xA8, 0x00, 0xC7,
+/// ];
+/// let checksum: u16 = ipv4_header_checksum(&header);
+/// assert_eq!(checksum, 0xB861);
+///
+/// header[10..12].copy_from_slice(&checksum.to_be_bytes());
+/// assert_eq!(ipv4_header_checksum(&header), 0);
+/// ```
+pub const fn ipv4_header_checksum(header: &[u8]) -> u16 {
+    finish(sum_words(0, header))
+}
+
+/// Calculate the checksum of a UDP datagram, including the IPv4
+/// pseudo-header.
+///
+/// `datagram` is the UDP header followed by the payload, the checksum field
+/// of the UDP header must be zero.
+///
+/// A calculated checksum of zero is returned as `0xFFFF`, zero is reserved
+/// for datagrams sent without a checksum.
+///
+/// # Panics
+///
+/// * `datagram` must not be longer than 65535 bytes.
+///
+/// # Example
+///
+/// ```
+/// use w5500_hl::{checksum::udp_checksum, net::Ipv4Addr};
+///
+/// const SRC: Ipv4Addr = Ipv4Addr::new(192, 168, 0, 1);
+/// const DST: Ipv4Addr = Ipv4Addr::new(192, 168, 0, 199);
+///
+/// let mut datagram: [u8; 13] = [
+///     0x04, 0xD2, // source port 1234
+///     0x16, 0x2E, // destination port 5678
+///     0x00, 0x0D, // length
+///     0x00, 0x00, // checksum
+///     b'h', b'e', b'l', b'l', b'o',
+/// ];
+/// let checksum: u16 = udp_checksum(&SRC, &DST, &datagram);
+/// assert_eq!(checksum, 0x1EE9);
+/// datagram[6..8].copy_from_slice(&checksum.to_be_bytes());
+/// ```
+pub const fn udp_checksum(src: &Ipv4Addr, dst: &Ipv4Addr, datagram: &[u8]) -> u16 {
+    assert!(datagram.len() <= u16::MAX as usize, "datagram is too long");
+    let len: [u8; 2] = (datagram.len() as u16).to_be_bytes();
+    let pseudo_header: [u8; 12] = {
+        let src: [u8; 4] = src.octets();
+        let dst: [u8; 4] = dst.octets();
+        [
+            src[0],
+            src[1],
+            src[2],
+            src[3],
+            dst[0],
+            dst[1],
+            dst[2],
+            dst[3],
+            0,
+            UDP_PROTOCOL,
+            len[0],
+            len[1],
+        ]
+    };
+    match finish(sum_words(sum_words(0, &pseudo_header), datagram)) {
+        0 => 0xFFFF,
+        checksum => checksum,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ipv4_header_checksum, udp_checksum};
+    use w5500_ll::net::Ipv4Addr;
+
+    #[test]
+    fn odd_length() {
+        assert_eq!(ipv4_header_checksum(&[0x12, 0x34, 0x56]), !0x6834);
+    }
+
+    #[test]
+    fn carry() {
+        assert_eq!(ipv4_header_checksum(&[0xFF, 0xFF, 0x00, 0x01]), !0x0001);
+    }
+
+    #[test]
+    fn udp_zero_is_ffff() {
+        // protocol, pseudo-header length, and datagram sum to 0xFFFF
+        let datagram: [u8; 8] = [0xFF, 0xFF - 17 - 8 - 8, 0, 0, 0, 8, 0, 0];
+        assert_eq!(
+            udp_checksum(&Ipv4Addr::UNSPECIFIED, &Ipv4Addr::UNSPECIFIED, &datagram),
+            0xFFFF
+        );
+    }
+}
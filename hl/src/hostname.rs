@@ -113,6 +113,57 @@ impl<'a> Hostname<'a> {
         }
     }
 
+    /// Create a new hostname, returning the reason the hostname is invalid
+    /// on failure.
+    ///
+    /// This performs the same validation as [`new`](Self::new), it is
+    /// intended for hostnames provided at runtime, such as from a
+    /// configuration file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_hl::{Hostname, HostnameError};
+    ///
+    /// assert!(Hostname::try_new("is-valid-example").is_ok());
+    /// assert_eq!(
+    ///     Hostname::try_new("this-is-not-?-valid").unwrap_err(),
+    ///     HostnameError::InvalidChar('?')
+    /// );
+    /// assert_eq!(
+    ///     Hostname::try_new("empty..label").unwrap_err(),
+    ///     HostnameError::EmptyLabel
+    /// );
+    /// ```
+    pub fn try_new(hostname: &'a str) -> Result<Self, HostnameError> {
+        fn is_valid_char(c: char) -> bool {
+            c.is_ascii_alphanumeric() || c == '-' || c == '.'
+        }
+
+        if hostname.is_empty() {
+            return Err(HostnameError::Empty);
+        }
+        if hostname.len() > 253 {
+            return Err(HostnameError::TooLong);
+        }
+        if let Some(c) = hostname.chars().find(|c| !is_valid_char(*c)) {
+            return Err(HostnameError::InvalidChar(c));
+        }
+        for label in hostname.split('.') {
+            if label.is_empty() {
+                return Err(HostnameError::EmptyLabel);
+            }
+            if label.len() > 63 {
+                return Err(HostnameError::LabelTooLong);
+            }
+            if label.starts_with('-') || label.ends_with('-') {
+                return Err(HostnameError::InvalidHyphen);
+            }
+        }
+
+        Ok(Self { hostname })
+    }
+
     /// Returns an iterator over the labels of the hostname.
     ///
     /// # Example
@@ -187,33 +238,47 @@ impl<'a> Hostname<'a> {
     }
 }
 
+/// Reasons a hostname is invalid.
+///
+/// Returned by [`Hostname::try_new`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum HostnameError {
+    /// The hostname is empty.
+    Empty,
+    /// The hostname is longer than 253 characters.
+    TooLong,
+    /// A label is empty, the hostname starts or ends with `'.'`, or
+    /// contains `".."`.
+    EmptyLabel,
+    /// A label is longer than 63 characters.
+    LabelTooLong,
+    /// A label starts or ends with `'-'`.
+    InvalidHyphen,
+    /// The hostname contains a character other than ASCII alphanumerics,
+    /// `'-'`, and `'.'`.
+    InvalidChar(char),
+}
+
 /// The error type returned when a str to [`Hostname`] conversion fails.
+///
+/// Use [`Hostname::try_new`] for the reason the conversion failed.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct TryFromStrError(pub(crate) ());
 
+impl From<HostnameError> for TryFromStrError {
+    #[inline]
+    fn from(_: HostnameError) -> Self {
+        TryFromStrError(())
+    }
+}
+
 impl<'a> TryFrom<&'a str> for Hostname<'a> {
     type Error = TryFromStrError;
 
     fn try_from(hostname: &'a str) -> Result<Self, Self::Error> {
-        fn is_valid_char(byte: u8) -> bool {
-            byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'.'
-        }
-
-        if hostname.is_empty()
-            || hostname.len() > 253
-            || hostname.bytes().any(|byte| !is_valid_char(byte))
-            || hostname.split('.').any(|label| {
-                label.is_empty()
-                    || label.len() > 63
-                    || label.ends_with('-')
-                    || label.starts_with('-')
-            })
-        {
-            Err(TryFromStrError(()))
-        } else {
-            Ok(Self { hostname })
-        }
+        Ok(Self::try_new(hostname)?)
     }
 }
 
@@ -226,7 +291,7 @@ impl<'a> From<Hostname<'a>> for &'a str {
 
 #[cfg(test)]
 mod tests {
-    use super::Hostname;
+    use super::{Hostname, HostnameError};
 
     #[test]
     fn valid_hostnames() {
@@ -274,4 +339,27 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn try_new_errors() {
+        const LONG_LABEL: &str =
+            "label-is-way-to-longgggggggggggggggggggggggggggggggggggggggggggg.com";
+        let too_long: String = ["a"; 128].join(".");
+
+        for (hostname, expected) in [
+            ("", HostnameError::Empty),
+            (too_long.as_str(), HostnameError::TooLong),
+            (".invalid", HostnameError::EmptyLabel),
+            ("invalid.name.", HostnameError::EmptyLabel),
+            ("empty..label", HostnameError::EmptyLabel),
+            (LONG_LABEL, HostnameError::LabelTooLong),
+            ("-invalid-name", HostnameError::InvalidHyphen),
+            ("invalid.ending-.char", HostnameError::InvalidHyphen),
+            ("asdf@fasd", HostnameError::InvalidChar('@')),
+            ("asd f", HostnameError::InvalidChar(' ')),
+            ("caf\u{e9}", HostnameError::InvalidChar('\u{e9}')),
+        ] {
+            assert_eq!(Hostname::try_new(hostname).unwrap_err(), expected);
+        }
+    }
 }
@@ -24,6 +24,9 @@
 //! * `defmt`: Passthrough to [`w5500-ll`].
 //! * `eh0`: Passthrough to [`w5500-ll`].
 //! * `eh1`: Passthrough to [`w5500-ll`].
+//! * `eha1`: Passthrough to [`w5500-ll`].
+//! * `socket`: Enables the [`Socket`] wrapper.
+//! * `std`: Enables the [`StdTcpStream`] adapter for the `std::io` traits.
 //!
 //! # Examples
 //!
@@ -89,42 +92,58 @@
 //! ```
 //!
 //! [`Registers`]: https://docs.rs/w5500-ll/latest/w5500_ll/trait.Registers.html
+//! [`Socket`]: https://docs.rs/w5500-hl/latest/w5500_hl/struct.Socket.html
+//! [`StdTcpStream`]: https://docs.rs/w5500-hl/latest/w5500_hl/struct.StdTcpStream.html
 //! [`Tcp`]: https://docs.rs/w5500-hl/latest/w5500_hl/trait.Tcp.html
 //! [`Udp`]: https://docs.rs/w5500-hl/latest/w5500_hl/trait.Udp.html
 //! [`w5500-ll`]: https://crates.io/crates/w5500-ll
 //! [Wiznet W5500]: https://docs.wiznet.io/Product/iEthernet/W5500/overview
 #![cfg_attr(docsrs, feature(doc_cfg))]
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+#![allow(async_fn_in_trait)] // https://github.com/rust-embedded/embedded-hal/pull/515#issuecomment-1763525962
 
+pub mod aio;
+pub mod blocking;
+pub mod checksum;
 mod hostname;
 pub mod io;
+mod macraw;
+#[cfg(feature = "socket")]
+mod socket;
+#[cfg(feature = "std")]
+mod std_io;
 mod tcp;
 mod udp;
 
-pub use hostname::{Hostname, TryFromStrError};
+pub use hostname::{Hostname, HostnameError, TryFromStrError};
 pub use ll::net;
-use ll::{Registers, Sn, SocketCommand, SocketStatus, SOCKETS};
+use ll::{BufferSize, Registers, Sn, SocketCommand, SocketStatus, SOCKETS};
+pub use macraw::Macraw;
+#[cfg(feature = "socket")]
+pub use socket::Socket;
+#[cfg(feature = "std")]
+pub use std_io::StdTcpStream;
 pub use tcp::{Tcp, TcpReader, TcpWriter};
 pub use udp::{Udp, UdpHeader, UdpReader, UdpWriter};
 pub use w5500_ll as ll;
 
 use net::{Ipv4Addr, SocketAddrV4};
 
+/// Returns `true` if a socket with this status does not hold its port.
+fn port_is_free(status: Result<SocketStatus, u8>) -> bool {
+    matches!(
+        status,
+        Ok(SocketStatus::Closed) | Ok(SocketStatus::CloseWait) | Ok(SocketStatus::Closing)
+    )
+}
+
 fn port_is_unique<T, E>(w5500: &mut T, socket: Sn, port: u16) -> Result<bool, E>
 where
     T: Registers<Error = E> + ?Sized,
 {
-    const CLOSED_STATUS: [Result<SocketStatus, u8>; 3] = [
-        Ok(SocketStatus::Closed),
-        Ok(SocketStatus::CloseWait),
-        Ok(SocketStatus::Closing),
-    ];
     for socket in SOCKETS.iter().filter(|s| s != &&socket) {
-        if w5500.sn_port(*socket)? == port {
-            let status = w5500.sn_sr(*socket)?;
-            if !CLOSED_STATUS.iter().any(|x| x == &status) {
-                return Ok(false);
-            }
+        if w5500.sn_port(*socket)? == port && !port_is_free(w5500.sn_sr(*socket)?) {
+            return Ok(false);
         }
     }
     Ok(true)
@@ -133,6 +152,7 @@ where
 /// Higher level W5500 errors.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
 pub enum Error<E> {
     /// Unexpected "end of file".
     ///
@@ -150,6 +170,8 @@ pub enum Error<E> {
     ///
     /// [`nb`]: (https://docs.rs/nb/latest/nb/index.html)
     WouldBlock,
+    /// The socket was disconnected before the operation completed.
+    Disconnected,
     /// Errors from the [`Registers`] trait implementation.
     Other(E),
 }
@@ -160,6 +182,47 @@ impl<E> From<E> for Error<E> {
     }
 }
 
+/// Errors from binding a socket to a local port.
+///
+/// Returned by [`Udp::try_udp_bind`] and [`Tcp::try_tcp_connect`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BindError<E> {
+    /// The local port is in use by another socket on the W5500.
+    PortInUse,
+    /// Errors from the [`Registers`] trait implementation.
+    Other(E),
+}
+
+impl<E> From<E> for BindError<E> {
+    fn from(error: E) -> BindError<E> {
+        BindError::Other(error)
+    }
+}
+
+/// Errors from configuring the socket buffer sizes.
+///
+/// Returned by [`Common::configure_buffers`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BufferConfigError<E> {
+    /// The sum of the RX buffer sizes exceeds 16 KiB.
+    RxExceeded,
+    /// The sum of the TX buffer sizes exceeds 16 KiB.
+    TxExceeded,
+    /// Errors from the [`Registers`] trait implementation.
+    Other(E),
+}
+
+impl<E> From<E> for BufferConfigError<E> {
+    fn from(error: E) -> BufferConfigError<E> {
+        BufferConfigError::Other(error)
+    }
+}
+
+/// Size of the RX and TX buffer memory shared by all sockets.
+const BUFFER_POOL_SIZE: usize = 16 * 1024;
+
 /// Turns a non-blocking W5500 expression `$e` into a blocking operation.
 ///
 /// This is accomplished by continuously calling the expression `$e` until it no
@@ -338,6 +401,88 @@ pub trait Common: Registers {
     fn is_state_udp(&mut self, sn: Sn) -> Result<bool, Self::Error> {
         Ok(self.sn_sr(sn)? == Ok(SocketStatus::Udp))
     }
+
+    /// Set the RX and TX buffer sizes of all sockets.
+    ///
+    /// The W5500 has 16 KiB of RX buffer memory and 16 KiB of TX buffer
+    /// memory shared between all sockets.
+    /// The buffer sizes are validated before any registers are written,
+    /// nothing is written if either sum exceeds 16 KiB.
+    ///
+    /// The arrays are indexed by socket number.
+    /// Sockets should be closed before changing the buffer sizes.
+    ///
+    /// # Example
+    ///
+    /// Give all the buffer memory to socket 0.
+    ///
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{ll::BufferSize, Common};
+    ///
+    /// const SIZES: [BufferSize; 8] = [
+    ///     BufferSize::KB16,
+    ///     BufferSize::KB0,
+    ///     BufferSize::KB0,
+    ///     BufferSize::KB0,
+    ///     BufferSize::KB0,
+    ///     BufferSize::KB0,
+    ///     BufferSize::KB0,
+    ///     BufferSize::KB0,
+    /// ];
+    ///
+    /// w5500.configure_buffers(SIZES, SIZES)?;
+    /// # Ok::<(), w5500_hl::BufferConfigError<embedded_hal::spi::ErrorKind>>(())
+    /// ```
+    fn configure_buffers(
+        &mut self,
+        rx: [BufferSize; SOCKETS.len()],
+        tx: [BufferSize; SOCKETS.len()],
+    ) -> Result<(), BufferConfigError<Self::Error>> {
+        fn total(sizes: &[BufferSize]) -> usize {
+            sizes.iter().map(BufferSize::size_in_bytes).sum()
+        }
+
+        if total(&rx) > BUFFER_POOL_SIZE {
+            return Err(BufferConfigError::RxExceeded);
+        }
+        if total(&tx) > BUFFER_POOL_SIZE {
+            return Err(BufferConfigError::TxExceeded);
+        }
+
+        for ((sn, rx), tx) in SOCKETS.iter().zip(rx).zip(tx) {
+            self.set_sn_rxbuf_size(*sn, rx)?;
+            self.set_sn_txbuf_size(*sn, tx)?;
+        }
+        Ok(())
+    }
+
+    /// Discard all data in the socket RX buffer, returning the number of
+    /// bytes discarded.
+    ///
+    /// This advances the RX read pointer past the received data without
+    /// reading it.
+    /// For UDP sockets this discards all queued datagrams, including their
+    /// headers.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{ll::Sn, Common};
+    ///
+    /// let discarded: u16 = w5500.drain_rx(Sn::Sn0)?;
+    /// # Ok::<(), embedded_hal::spi::ErrorKind>(())
+    /// ```
+    fn drain_rx(&mut self, sn: Sn) -> Result<u16, Self::Error> {
+        let rsr: u16 = self.sn_rx_rsr(sn)?;
+        if rsr != 0 {
+            let ptr: u16 = self.sn_rx_rd(sn)?;
+            self.set_sn_rx_rd(sn, ptr.wrapping_add(rsr))?;
+            self.set_sn_cr(sn, SocketCommand::Recv)?;
+        }
+        Ok(rsr)
+    }
 }
 
 /// Implement the common socket trait for any structure that implements [`w5500_ll::Registers`].
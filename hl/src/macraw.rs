@@ -0,0 +1,167 @@
+use crate::Error;
+use core::cmp::min;
+use w5500_ll::{Protocol, Registers, Sn, SocketCommand, SocketMode, SocketStatus};
+
+/// MACRAW mode is only supported on socket 0.
+const MACRAW_SOCKET: Sn = Sn::Sn0;
+
+/// Length of the header the W5500 prepends to received MACRAW frames.
+const HEADER_LEN: u16 = 2;
+
+/// A W5500 MACRAW trait.
+///
+/// MACRAW mode sends and receives raw ethernet frames, and is only
+/// supported on socket 0.
+pub trait Macraw: Registers {
+    /// Opens socket 0 in MACRAW mode.
+    ///
+    /// This will close the socket, which will reset the RX and TX buffers.
+    ///
+    /// The MAC filter is disabled, all frames on the network will be
+    /// received.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::Macraw;
+    ///
+    /// w5500.macraw_open()?;
+    /// # Ok::<(), embedded_hal::spi::ErrorKind>(())
+    /// ```
+    fn macraw_open(&mut self) -> Result<(), Self::Error> {
+        const MODE: SocketMode = SocketMode::DEFAULT.set_protocol(Protocol::Macraw);
+        // the port is not used in MACRAW mode
+        let status: Result<SocketStatus, u8> = self.open_socket(MACRAW_SOCKET, MODE, 0)?;
+        debug_assert_eq!(status, Ok(SocketStatus::Macraw));
+        Ok(())
+    }
+
+    /// Receives a single ethernet frame.
+    /// On success, returns the number of bytes read.
+    ///
+    /// The W5500 prepends a 2 byte length to each frame in the socket
+    /// buffer, this is removed.
+    /// Unlike newer WIZnet devices the W5500 does not prepend any packet
+    /// information.
+    ///
+    /// If a frame is too long to fit in the supplied buffer, excess bytes
+    /// will be discarded.
+    ///
+    /// # Errors
+    ///
+    /// This method can only return:
+    ///
+    /// * [`Error::Other`]
+    /// * [`Error::WouldBlock`]
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The socket must be opened in MACRAW mode.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{block, Macraw};
+    ///
+    /// w5500.macraw_open()?;
+    /// let mut buf = [0; 1514];
+    /// let number_of_bytes: u16 = block!(w5500.macraw_recv_frame(&mut buf))?;
+    /// let frame = &buf[..number_of_bytes.into()];
+    /// # Ok::<(), w5500_hl::Error<_>>(())
+    /// ```
+    fn macraw_recv_frame(&mut self, buf: &mut [u8]) -> Result<u16, Error<Self::Error>> {
+        let rsr: u16 = self.sn_rx_rsr(MACRAW_SOCKET)?;
+
+        // nothing to recieve
+        if rsr < HEADER_LEN {
+            return Err(Error::WouldBlock);
+        }
+
+        debug_assert_eq!(self.sn_sr(MACRAW_SOCKET)?, Ok(SocketStatus::Macraw));
+
+        let mut ptr: u16 = self.sn_rx_rd(MACRAW_SOCKET)?;
+        let mut header: [u8; HEADER_LEN as usize] = [0; HEADER_LEN as usize];
+        self.sn_rx_buf(MACRAW_SOCKET, ptr, &mut header)?;
+        // the length includes the length of the header
+        let len: u16 = u16::from_be_bytes(header);
+
+        // not all data as indicated by the header has been buffered
+        if rsr < len {
+            return Err(Error::WouldBlock);
+        }
+
+        let frame_len: u16 = len.saturating_sub(HEADER_LEN);
+        let read_size: u16 = min(frame_len, buf.len().try_into().unwrap_or(u16::MAX));
+        if read_size != 0 {
+            self.sn_rx_buf(
+                MACRAW_SOCKET,
+                ptr.wrapping_add(HEADER_LEN),
+                &mut buf[..read_size.into()],
+            )?;
+        }
+        ptr = ptr.wrapping_add(HEADER_LEN).wrapping_add(frame_len);
+        self.set_sn_rx_rd(MACRAW_SOCKET, ptr)?;
+        self.set_sn_cr(MACRAW_SOCKET, SocketCommand::Recv)?;
+        Ok(read_size)
+    }
+
+    /// Sends a single ethernet frame.
+    ///
+    /// The frame must contain the ethernet header, the W5500 adds the
+    /// preamble and frame check sequence.
+    ///
+    /// This will transmit only if there is enough free space in the W5500
+    /// transmit buffer for the entire frame.
+    ///
+    /// # Errors
+    ///
+    /// This method can only return:
+    ///
+    /// * [`Error::Other`]
+    /// * [`Error::WouldBlock`] if there is not enough free space in the
+    ///   transmit buffer.
+    /// * [`Error::OutOfMemory`] if the frame is larger than the transmit
+    ///   buffer.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The socket must be opened in MACRAW mode.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{block, Macraw};
+    ///
+    /// w5500.macraw_open()?;
+    /// let frame: [u8; 60] = [0; 60];
+    /// block!(w5500.macraw_send_frame(&frame))?;
+    /// # Ok::<(), w5500_hl::Error<_>>(())
+    /// ```
+    fn macraw_send_frame(&mut self, frame: &[u8]) -> Result<(), Error<Self::Error>> {
+        debug_assert_eq!(self.sn_sr(MACRAW_SOCKET)?, Ok(SocketStatus::Macraw));
+
+        let buf_size: usize = self
+            .sn_txbuf_size(MACRAW_SOCKET)?
+            .map_or(0, |size| size.size_in_bytes());
+        // frames larger than the buffer would never be sent
+        let frame_len: u16 = match u16::try_from(frame.len()) {
+            Ok(len) if frame.len() <= buf_size => len,
+            _ => return Err(Error::OutOfMemory),
+        };
+        if frame_len > self.sn_tx_fsr(MACRAW_SOCKET)? {
+            return Err(Error::WouldBlock);
+        }
+
+        let ptr: u16 = self.sn_tx_wr(MACRAW_SOCKET)?;
+        self.set_sn_tx_buf(MACRAW_SOCKET, ptr, frame)?;
+        self.set_sn_tx_wr(MACRAW_SOCKET, ptr.wrapping_add(frame_len))?;
+        self.set_sn_cr(MACRAW_SOCKET, SocketCommand::Send)?;
+        Ok(())
+    }
+}
+
+/// Implement the MACRAW trait for any structure that implements [`w5500_ll::Registers`].
+impl<T> Macraw for T where T: Registers {}
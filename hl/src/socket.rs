@@ -0,0 +1,148 @@
+use crate::{Common, Error, Tcp, Udp};
+use w5500_ll::{net::SocketAddrV4, Registers, Sn};
+
+/// A socket borrowing the W5500.
+///
+/// This is a thin wrapper around the [`Common`], [`Tcp`], and [`Udp`] traits
+/// that stores the socket number, so it does not need to be passed to every
+/// method.
+///
+/// The socket borrows the W5500 mutably, only one socket can be used at a
+/// time.
+/// Use the traits directly, or drop the socket, to access other sockets.
+///
+/// # Example
+///
+/// ```no_run
+/// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+/// use w5500_hl::{
+///     ll::Sn,
+///     net::{Ipv4Addr, SocketAddrV4},
+///     Socket,
+/// };
+///
+/// const MQTT_SERVER: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 168, 2, 10), 1883);
+///
+/// let mut socket = Socket::new(&mut w5500, Sn::Sn0);
+/// socket.connect(33650, &MQTT_SERVER)?;
+///
+/// // ... wait for a CON interrupt
+///
+/// socket.write(&[0x10, 0x0C])?;
+///
+/// // ... wait for a RECV interrupt
+///
+/// let mut buf = [0; 10];
+/// let rx_bytes: u16 = socket.read(&mut buf)?;
+/// socket.close()?;
+/// # Ok::<(), embedded_hal::spi::ErrorKind>(())
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Socket<'a, W> {
+    w5500: &'a mut W,
+    sn: Sn,
+}
+
+impl<'a, W: Registers> Socket<'a, W> {
+    /// Create a new socket.
+    ///
+    /// This does not change the state of the socket.
+    pub fn new(w5500: &'a mut W, sn: Sn) -> Self {
+        Self { w5500, sn }
+    }
+
+    /// Socket number.
+    pub fn sn(&self) -> Sn {
+        self.sn
+    }
+
+    /// Get a mutable reference to the W5500.
+    pub fn w5500(&mut self) -> &mut W {
+        self.w5500
+    }
+
+    /// Returns the socket address.
+    ///
+    /// See [`Common::local_addr`].
+    pub fn local_addr(&mut self) -> Result<SocketAddrV4, W::Error> {
+        self.w5500.local_addr(self.sn)
+    }
+
+    /// Close the socket.
+    ///
+    /// See [`Common::close`].
+    pub fn close(&mut self) -> Result<(), W::Error> {
+        self.w5500.close(self.sn)
+    }
+
+    /// Starts the 3-way TCP handshake with the remote host.
+    ///
+    /// See [`Tcp::tcp_connect`].
+    pub fn connect(&mut self, port: u16, addr: &SocketAddrV4) -> Result<(), W::Error> {
+        self.w5500.tcp_connect(self.sn, port, addr)
+    }
+
+    /// Open a TCP listener on the given port.
+    ///
+    /// See [`Tcp::tcp_listen`].
+    pub fn listen(&mut self, port: u16) -> Result<(), W::Error> {
+        self.w5500.tcp_listen(self.sn, port)
+    }
+
+    /// Read data from the remote host, returning the number of bytes read.
+    ///
+    /// See [`Tcp::tcp_read`].
+    pub fn read(&mut self, buf: &mut [u8]) -> Result<u16, W::Error> {
+        self.w5500.tcp_read(self.sn, buf)
+    }
+
+    /// Send data to the remote host, returning the number of bytes written.
+    ///
+    /// See [`Tcp::tcp_write`].
+    pub fn write(&mut self, buf: &[u8]) -> Result<u16, W::Error> {
+        self.w5500.tcp_write(self.sn, buf)
+    }
+
+    /// Send all data to the remote host.
+    ///
+    /// See [`Tcp::tcp_write_all`].
+    pub fn write_all(&mut self, buf: &[u8]) -> Result<(), Error<W::Error>> {
+        self.w5500.tcp_write_all(self.sn, buf)
+    }
+
+    /// Disconnect from the peer.
+    ///
+    /// See [`Tcp::tcp_disconnect`].
+    pub fn disconnect(&mut self) -> Result<(), W::Error> {
+        self.w5500.tcp_disconnect(self.sn)
+    }
+
+    /// Binds the socket to the given port.
+    ///
+    /// See [`Udp::udp_bind`].
+    pub fn bind(&mut self, port: u16) -> Result<(), W::Error> {
+        self.w5500.udp_bind(self.sn, port)
+    }
+
+    /// Receives a single datagram message on the socket.
+    ///
+    /// See [`Udp::udp_recv_from`].
+    pub fn recv_from(&mut self, buf: &mut [u8]) -> Result<(u16, SocketAddrV4), Error<W::Error>> {
+        self.w5500.udp_recv_from(self.sn, buf)
+    }
+
+    /// Sends data on the socket to the given address.
+    ///
+    /// See [`Udp::udp_send_to`].
+    pub fn send_to(&mut self, buf: &[u8], addr: &SocketAddrV4) -> Result<u16, W::Error> {
+        self.w5500.udp_send_to(self.sn, buf, addr)
+    }
+
+    /// Sends data to the currently configured destination.
+    ///
+    /// See [`Udp::udp_send`].
+    pub fn send(&mut self, buf: &[u8]) -> Result<u16, W::Error> {
+        self.w5500.udp_send(self.sn, buf)
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{blocking, Error};
+use core::fmt::Debug;
+use w5500_ll::{Registers, Sn};
+
+/// A blocking TCP stream implementing [`std::io::Read`] and
+/// [`std::io::Write`].
+///
+/// This adapts a connected TCP socket to the standard library I/O traits, so
+/// libraries written for `std` can run against the W5500, or a simulated
+/// W5500 for integration testing.
+///
+/// The stream borrows the W5500 mutably, only one stream can be used at a
+/// time.
+///
+/// * Reads block until data is available, returning `0` at the end of the
+///   stream when the remote host has closed the connection.
+/// * Writes block until there is free space in the socket TX buffer.
+/// * Flushing does nothing, data is sent as it is written.
+///
+/// There is no delay between polls, and no timeout.
+///
+/// # Example
+///
+/// ```no_run
+/// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+/// use std::io::{Read, Write};
+/// use w5500_hl::{ll::Sn, StdTcpStream};
+///
+/// // ... connect, and wait for a CON interrupt
+///
+/// let mut stream = StdTcpStream::new(&mut w5500, Sn::Sn0);
+/// stream.write_all(b"GET / HTTP/1.0\r\n\r\n")?;
+/// let mut response: Vec<u8> = Vec::new();
+/// stream.read_to_end(&mut response)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct StdTcpStream<'a, W> {
+    w5500: &'a mut W,
+    sn: Sn,
+}
+
+impl<'a, W: Registers> StdTcpStream<'a, W> {
+    /// Create a new stream.
+    ///
+    /// This does not change the state of the socket, the socket should
+    /// already be connected.
+    pub fn new(w5500: &'a mut W, sn: Sn) -> Self {
+        Self { w5500, sn }
+    }
+
+    /// Socket number.
+    pub fn sn(&self) -> Sn {
+        self.sn
+    }
+
+    /// Get a mutable reference to the W5500.
+    pub fn w5500(&mut self) -> &mut W {
+        self.w5500
+    }
+}
+
+impl<W> std::io::Read for StdTcpStream<'_, W>
+where
+    W: Registers,
+    W::Error: Debug,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match blocking::tcp_read(self.w5500, self.sn, buf, None) {
+            Ok(rx_bytes) => Ok(usize::from(rx_bytes)),
+            Err(Error::Disconnected) => Ok(0),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl<W> std::io::Write for StdTcpStream<'_, W>
+where
+    W: Registers,
+    W::Error: Debug,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let tx_bytes: u16 = blocking::tcp_write(self.w5500, self.sn, buf, None)?;
+        Ok(usize::from(tx_bytes))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<E: Debug> From<Error<E>> for std::io::Error {
+    fn from(error: Error<E>) -> Self {
+        match error {
+            Error::UnexpectedEof => std::io::ErrorKind::UnexpectedEof.into(),
+            Error::OutOfMemory => std::io::ErrorKind::OutOfMemory.into(),
+            Error::WouldBlock => std::io::ErrorKind::WouldBlock.into(),
+            Error::Disconnected => std::io::ErrorKind::NotConnected.into(),
+            Error::Other(e) => std::io::Error::other(format!("{e:?}")),
+        }
+    }
+}
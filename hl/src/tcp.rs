@@ -1,6 +1,6 @@
 use crate::{
     io::{Read, Seek, SeekFrom, Write},
-    port_is_unique, Error,
+    port_is_unique, BindError, Error,
 };
 use core::cmp::min;
 use w5500_ll::{
@@ -66,6 +66,44 @@ pub struct TcpReader<'w, W5500> {
     pub(crate) ptr: u16,
 }
 
+impl<W5500: Registers> TcpReader<'_, W5500> {
+    /// Read data from the socket buffer without advancing the stream
+    /// position, and return the number of bytes read.
+    ///
+    /// This is useful to inspect a header before deciding how much data to
+    /// read.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use ehm::eh1 as h;
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(h::spi::Mock::new(&[]));
+    /// use w5500_hl::{io::Read, ll::Sn, Tcp, TcpReader};
+    ///
+    /// const MQTT_SOCKET: Sn = Sn::Sn0;
+    ///
+    /// let mut reader: TcpReader<_> = w5500.tcp_reader(MQTT_SOCKET)?;
+    ///
+    /// // fixed header, this assumes the remaining length fits in one byte
+    /// let mut header: [u8; 2] = [0; 2];
+    /// if reader.peek(&mut header)? == 2 {
+    ///     let mut packet: [u8; 129] = [0; 129];
+    ///     let len: usize = 2 + usize::from(header[1] & 0x7F);
+    ///     reader.read_exact(&mut packet[..len])?;
+    ///     reader.done()?;
+    /// }
+    /// # Ok::<(), w5500_hl::Error<_>>(())
+    /// ```
+    pub fn peek(&mut self, buf: &mut [u8]) -> Result<u16, W5500::Error> {
+        let read_size: u16 = min(self.remain(), buf.len().try_into().unwrap_or(u16::MAX));
+        if read_size != 0 {
+            self.w5500
+                .sn_rx_buf(self.sn, self.ptr, &mut buf[..usize::from(read_size)])?;
+        }
+        Ok(read_size)
+    }
+}
+
 impl<W5500> Seek for TcpReader<'_, W5500> {
     fn seek<E>(&mut self, pos: SeekFrom) -> Result<(), Error<E>> {
         self.ptr = pos.new_ptr(self.ptr, self.head_ptr, self.tail_ptr)?;
@@ -124,7 +162,8 @@ impl<W5500: Registers> Read<W5500::Error> for TcpReader<'_, W5500> {
 
 /// Streaming writer for a TCP socket buffer.
 ///
-/// This implements the [`Seek`] traits.
+/// This implements the [`Seek`] traits, and [`core::fmt::Write`] for
+/// formatting text directly into the socket buffer.
 ///
 /// Created with [`Tcp::tcp_writer`](crate::Tcp::tcp_writer).
 ///
@@ -222,6 +261,42 @@ impl<W5500: Registers> Write<W5500::Error> for TcpWriter<'_, W5500> {
     }
 }
 
+/// Format text into the socket buffer.
+///
+/// Each call to [`write_str`](core::fmt::Write::write_str) appends to the
+/// socket buffer with [`Write::write_all`], the data is not transmitted
+/// until [`Write::send`] is called.
+///
+/// [`core::fmt::Error`] does not carry any information, it is returned for
+/// bus errors, and when the formatted text exceeds the free space in the
+/// socket buffer.
+/// Text written before the error remains in the socket buffer, use
+/// [`Seek::stream_position`] to check how much was written.
+///
+/// # Example
+///
+/// ```no_run
+/// # use ehm::eh1 as h;
+/// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(h::spi::Mock::new(&[]));
+/// use core::fmt::Write as _;
+/// use w5500_hl::{io::Write, ll::Sn, Tcp, TcpWriter};
+///
+/// const HTTP_SOCKET: Sn = Sn::Sn1;
+///
+/// // ... accept a connection and read the request
+///
+/// let code: u16 = 200;
+/// let mut writer: TcpWriter<_> = w5500.tcp_writer(HTTP_SOCKET)?;
+/// write!(writer, "HTTP/1.1 {code} OK\r\n\r\n").map_err(|_| w5500_hl::Error::OutOfMemory)?;
+/// writer.send()?;
+/// # Ok::<(), w5500_hl::Error<_>>(())
+/// ```
+impl<W5500: Registers> core::fmt::Write for TcpWriter<'_, W5500> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.write_all(s.as_bytes()).map_err(|_| core::fmt::Error)
+    }
+}
+
 /// A W5500 TCP trait.
 pub trait Tcp: Registers {
     /// Starts the 3-way TCP handshake with the remote host.
@@ -296,25 +371,135 @@ pub trait Tcp: Registers {
             "Local port {port} is in use"
         );
 
-        self.set_sn_cr(sn, SocketCommand::Close)?;
-        // This will not hang, the socket status will always change to closed
-        // after a close command.
-        // (unless you do somthing silly like holding the W5500 in reset)
-        while self.sn_sr(sn)? != Ok(SocketStatus::Closed) {}
-
         const MODE: SocketMode = SocketMode::DEFAULT.set_protocol(Protocol::Tcp);
-        self.set_sn_mr(sn, MODE)?;
-        self.set_sn_port(sn, port)?;
-        self.set_sn_cr(sn, SocketCommand::Open)?;
+        let status: Result<SocketStatus, u8> = self.open_socket(sn, MODE, port)?;
+        debug_assert_eq!(status, Ok(SocketStatus::Init));
         self.set_sn_dest(sn, addr)?;
-        // This will not hang, the socket status will always change to Init
-        // after a open command with SN_MR set to TCP.
-        // (unless you do somthing silly like holding the W5500 in reset)
-        while self.sn_sr(sn)? != Ok(SocketStatus::Init) {}
-
         self.set_sn_cr(sn, SocketCommand::Connect)
     }
 
+    /// Starts the 3-way TCP handshake with the remote host, returning an
+    /// error if the port is in use.
+    ///
+    /// This is the same as [`Tcp::tcp_connect`], but the port is always
+    /// checked instead of a debug assertion.
+    ///
+    /// # Errors
+    ///
+    /// * [`BindError::PortInUse`] if the port is in use by any other socket
+    ///   on the W5500.
+    /// * [`BindError::Other`]
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{
+    ///     ll::{Registers, Sn},
+    ///     net::{Ipv4Addr, SocketAddrV4},
+    ///     BindError, Tcp,
+    /// };
+    ///
+    /// const MQTT_SERVER: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 168, 2, 10), 1883);
+    ///
+    /// match w5500.try_tcp_connect(Sn::Sn0, 33650, &MQTT_SERVER) {
+    ///     Ok(()) => (),
+    ///     Err(BindError::PortInUse) => (), // try another port
+    ///     Err(BindError::Other(e)) => return Err(e),
+    /// }
+    /// # Ok::<(), embedded_hal::spi::ErrorKind>(())
+    /// ```
+    fn try_tcp_connect(
+        &mut self,
+        sn: Sn,
+        port: u16,
+        addr: &SocketAddrV4,
+    ) -> Result<(), BindError<Self::Error>> {
+        if !port_is_unique(self, sn, port)? {
+            return Err(BindError::PortInUse);
+        }
+        Ok(self.tcp_connect(sn, port, addr)?)
+    }
+
+    /// Starts the 3-way TCP handshake with the remote host without waiting
+    /// for the socket status to change.
+    ///
+    /// This is the same as [`Tcp::tcp_connect`], but instead of polling the
+    /// socket status until the socket has closed and opened, each call
+    /// advances one step based on the current socket status:
+    ///
+    /// 1. Any status other than [`Closed`] or [`Init`]: issue the `CLOSE`
+    ///    command.
+    /// 2. [`Closed`]: set the mode and port, then issue the `OPEN` command.
+    /// 3. [`Init`] with the requested port: set the destination, then issue
+    ///    the `CONNECT` command and return `Ok(())`.
+    ///
+    /// [`Error::WouldBlock`] is returned until the `CONNECT` command has been
+    /// issued, call this method again with the same arguments to continue.
+    /// As with [`Tcp::tcp_connect`], wait for the [`con`] interrupt to know
+    /// when the connection is established.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The port must not be in use by any other socket on the W5500.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{
+    ///     ll::Sn,
+    ///     net::{Ipv4Addr, SocketAddrV4},
+    ///     Error, Tcp,
+    /// };
+    ///
+    /// const MQTT_SOCKET: Sn = Sn::Sn0;
+    /// const MQTT_SOURCE_PORT: u16 = 33650;
+    /// const MQTT_SERVER: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 168, 2, 10), 1883);
+    ///
+    /// loop {
+    ///     match w5500.tcp_connect_nb(MQTT_SOCKET, MQTT_SOURCE_PORT, &MQTT_SERVER) {
+    ///         Ok(()) => break,
+    ///         // run other tasks
+    ///         Err(Error::WouldBlock) => (),
+    ///         Err(e) => return Err(e),
+    ///     }
+    /// }
+    /// # Ok::<(), w5500_hl::Error<_>>(())
+    /// ```
+    ///
+    /// [`Closed`]: w5500_ll::SocketStatus::Closed
+    /// [`Init`]: w5500_ll::SocketStatus::Init
+    /// [`con`]: w5500_ll::SocketInterrupt::con_raised
+    fn tcp_connect_nb(
+        &mut self,
+        sn: Sn,
+        port: u16,
+        addr: &SocketAddrV4,
+    ) -> Result<(), Error<Self::Error>> {
+        const MODE: SocketMode = SocketMode::DEFAULT.set_protocol(Protocol::Tcp);
+
+        match self.sn_sr(sn)? {
+            Ok(SocketStatus::Closed) => {
+                debug_assert!(
+                    port_is_unique(self, sn, port)?,
+                    "Local port {port} is in use"
+                );
+                self.set_sn_mr(sn, MODE)?;
+                self.set_sn_port(sn, port)?;
+                self.set_sn_cr(sn, SocketCommand::Open)?;
+            }
+            // the socket may have been opened for a different purpose
+            Ok(SocketStatus::Init) if self.sn_port(sn)? == port => {
+                self.set_sn_dest(sn, addr)?;
+                self.set_sn_cr(sn, SocketCommand::Connect)?;
+                return Ok(());
+            }
+            _ => self.set_sn_cr(sn, SocketCommand::Close)?,
+        }
+        Err(Error::WouldBlock)
+    }
+
     /// Open a TCP listener on the given port.
     ///
     /// After opening a listener with [`tcp_listen`] and receiving the
@@ -383,19 +568,9 @@ pub trait Tcp: Registers {
             "Local port {port} is in use"
         );
 
-        self.set_sn_cr(sn, SocketCommand::Close)?;
-        // This will not hang, the socket status will always change to closed
-        // after a close command.
-        // (unless you do somthing silly like holding the W5500 in reset)
-        while self.sn_sr(sn)? != Ok(SocketStatus::Closed) {}
         const MODE: SocketMode = SocketMode::DEFAULT.set_protocol(Protocol::Tcp);
-        self.set_sn_mr(sn, MODE)?;
-        self.set_sn_port(sn, port)?;
-        self.set_sn_cr(sn, SocketCommand::Open)?;
-        // This will not hang, the socket status will always change to Init
-        // after a open command with SN_MR set to TCP.
-        // (unless you do somthing silly like holding the W5500 in reset)
-        while self.sn_sr(sn)? != Ok(SocketStatus::Init) {}
+        let status: Result<SocketStatus, u8> = self.open_socket(sn, MODE, port)?;
+        debug_assert_eq!(status, Ok(SocketStatus::Init));
         self.set_sn_cr(sn, SocketCommand::Listen)
     }
 
@@ -462,6 +637,113 @@ pub trait Tcp: Registers {
         Ok(rx_bytes)
     }
 
+    /// Read all buffered data from the remote host, returning the number of
+    /// bytes read.
+    ///
+    /// This calls [`tcp_read`] until the socket RX buffer is empty, or `buf`
+    /// is full.
+    ///
+    /// This does not block waiting for more data, it only consumes the data
+    /// that is currently in the socket RX buffer.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The socket must be an [`Established`] TCP socket.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{ll::Sn, Tcp};
+    ///
+    /// // ... wait for a RECV interrupt
+    ///
+    /// let mut buf = [0; 2048];
+    /// let rx_bytes: usize = w5500.tcp_read_to_end(Sn::Sn0, &mut buf)?;
+    /// let filled_buf = &buf[..rx_bytes];
+    /// # Ok::<(), embedded_hal::spi::ErrorKind>(())
+    /// ```
+    ///
+    /// [`Established`]: w5500_ll::SocketStatus::Established
+    /// [`tcp_read`]: Tcp::tcp_read
+    fn tcp_read_to_end(&mut self, sn: Sn, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let mut total: usize = 0;
+        loop {
+            let rx_bytes: u16 = self.tcp_read(sn, &mut buf[total..])?;
+            if rx_bytes == 0 {
+                return Ok(total);
+            }
+            total += usize::from(rx_bytes);
+        }
+    }
+
+    /// Get the number of bytes available to read from the socket RX buffer.
+    ///
+    /// This is a wrapper around [`sn_rx_rsr`], it is useful for frame-based
+    /// protocols to check if a complete message has been received before
+    /// reading.
+    ///
+    /// The same value is available from a [`TcpReader`] with
+    /// [`Seek::stream_len`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{ll::Sn, Tcp};
+    ///
+    /// const HEADER_LEN: u16 = 4;
+    ///
+    /// if w5500.tcp_bytes_available(Sn::Sn0)? >= HEADER_LEN {
+    ///     let mut header: [u8; 4] = [0; 4];
+    ///     w5500.tcp_read(Sn::Sn0, &mut header)?;
+    /// }
+    /// # Ok::<(), embedded_hal::spi::ErrorKind>(())
+    /// ```
+    ///
+    /// [`sn_rx_rsr`]: w5500_ll::Registers::sn_rx_rsr
+    fn tcp_bytes_available(&mut self, sn: Sn) -> Result<u16, Self::Error> {
+        self.sn_rx_rsr(sn)
+    }
+
+    /// Get the number of bytes in the socket TX buffer that have not been
+    /// acknowledged by the peer.
+    ///
+    /// This includes data that has been written but not yet sent, and data
+    /// that has been sent and is waiting for an acknowledgment.
+    /// In TCP mode [`sn_tx_fsr`] only counts space freed by acknowledged data,
+    /// this is the TX buffer size minus the free size.
+    ///
+    /// This is useful for application-level backpressure, and to detect a
+    /// stalled connection before the retransmission timeout fires.
+    ///
+    /// The difference of [`sn_tx_wr`] and [`sn_tx_rd`] is not used because
+    /// the W5500 advances [`sn_tx_rd`] when the data is transmitted, not when
+    /// it is acknowledged.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{ll::Sn, Tcp};
+    ///
+    /// // wait for the peer to catch up before queuing more data
+    /// if w5500.tcp_unacked_bytes(Sn::Sn0)? < 1024 {
+    ///     w5500.tcp_write(Sn::Sn0, b"more data")?;
+    /// }
+    /// # Ok::<(), embedded_hal::spi::ErrorKind>(())
+    /// ```
+    ///
+    /// [`sn_tx_fsr`]: w5500_ll::Registers::sn_tx_fsr
+    /// [`sn_tx_wr`]: w5500_ll::Registers::sn_tx_wr
+    /// [`sn_tx_rd`]: w5500_ll::Registers::sn_tx_rd
+    fn tcp_unacked_bytes(&mut self, sn: Sn) -> Result<u16, Self::Error> {
+        let buf_size: u16 = self.sn_txbuf_size(sn)?.map_or(0, |size| {
+            u16::try_from(size.size_in_bytes()).unwrap_or(u16::MAX)
+        });
+        Ok(buf_size.saturating_sub(self.sn_tx_fsr(sn)?))
+    }
+
     /// Send data to the remote host, returning the number of bytes written.
     ///
     /// # Panics
@@ -517,6 +799,48 @@ pub trait Tcp: Registers {
         Ok(tx_bytes)
     }
 
+    /// Send all data to the remote host.
+    ///
+    /// This calls [`tcp_write`] until all data has been written to the socket
+    /// TX buffer, polling the socket free size register while the TX buffer
+    /// is full.
+    ///
+    /// This will block until the remote host has acknowledged enough data to
+    /// write the remainder of `buf`.
+    ///
+    /// # Errors
+    ///
+    /// This method can only return:
+    ///
+    /// * [`Error::Other`]
+    /// * [`Error::Disconnected`] if the socket is not [`Established`] or
+    ///   [`CloseWait`], for example when the connection times out while
+    ///   waiting for free space in the TX buffer.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{ll::Sn, Tcp};
+    ///
+    /// // ... wait for a CON interrupt
+    ///
+    /// const DATA: [u8; 4096] = [0; 4096];
+    /// w5500.tcp_write_all(Sn::Sn0, &DATA)?;
+    /// # Ok::<(), w5500_hl::Error<embedded_hal::spi::ErrorKind>>(())
+    /// ```
+    ///
+    /// [`CloseWait`]: w5500_ll::SocketStatus::CloseWait
+    /// [`Established`]: w5500_ll::SocketStatus::Established
+    /// [`tcp_write`]: Tcp::tcp_write
+    fn tcp_write_all(&mut self, sn: Sn, mut buf: &[u8]) -> Result<(), Error<Self::Error>> {
+        while !buf.is_empty() {
+            let tx_bytes: u16 = crate::blocking::tcp_write(self, sn, buf, None)?;
+            buf = &buf[usize::from(tx_bytes)..];
+        }
+        Ok(())
+    }
+
     /// Disconnect from the peer.
     ///
     /// If the disconnect is successful (FIN/ACK packet is received) the socket
@@ -563,6 +887,82 @@ pub trait Tcp: Registers {
         self.set_sn_cr(sn, SocketCommand::Disconnect)
     }
 
+    /// Configure the automatic keep-alive timer.
+    ///
+    /// With `Some(secs)` the W5500 sends a keep-alive packet every `secs`
+    /// seconds when the connection is idle.
+    /// The timer has a unit of 5 seconds, `secs` is rounded to the nearest
+    /// multiple of 5 seconds, with a minimum of 5 seconds.
+    /// Longer intervals, up to 1275 seconds, can be set directly with
+    /// [`set_sn_kpalvtr`].
+    ///
+    /// `None` disables the automatic keep-alive, keep-alive packets can
+    /// then be sent manually with [`tcp_send_keepalive`].
+    ///
+    /// Keep-alive packets are only sent after the socket is [`Established`],
+    /// and after data has been sent to or received from the peer at least
+    /// once.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{ll::Sn, Tcp};
+    ///
+    /// // send a keep-alive packet after 60 seconds of inactivity
+    /// w5500.tcp_set_keepalive(Sn::Sn0, Some(60))?;
+    /// # Ok::<(), embedded_hal::spi::ErrorKind>(())
+    /// ```
+    ///
+    /// [`Established`]: w5500_ll::SocketStatus::Established
+    /// [`set_sn_kpalvtr`]: w5500_ll::Registers::set_sn_kpalvtr
+    /// [`tcp_send_keepalive`]: Tcp::tcp_send_keepalive
+    fn tcp_set_keepalive(&mut self, sn: Sn, secs: Option<u8>) -> Result<(), Self::Error> {
+        let kpalvtr: u8 = match secs {
+            // at most 51, (255 + 2) / 5
+            Some(secs) => ((u16::from(secs) + 2) / 5).max(1) as u8,
+            None => 0,
+        };
+        self.set_sn_kpalvtr(sn, kpalvtr)
+    }
+
+    /// Send a keep-alive packet.
+    ///
+    /// If the peer does not respond the connection is terminated, and the
+    /// [timeout interrupt] is raised.
+    ///
+    /// This is ignored by the W5500 when the automatic keep-alive is
+    /// enabled with [`tcp_set_keepalive`].
+    ///
+    /// Keep-alive packets are only sent after the socket is [`Established`],
+    /// and after data has been sent to or received from the peer at least
+    /// once.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The socket must be an [`Established`] TCP socket.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{ll::Sn, Tcp};
+    ///
+    /// w5500.tcp_set_keepalive(Sn::Sn0, None)?;
+    ///
+    /// // ... connect and exchange data
+    ///
+    /// w5500.tcp_send_keepalive(Sn::Sn0)?;
+    /// # Ok::<(), embedded_hal::spi::ErrorKind>(())
+    /// ```
+    ///
+    /// [`Established`]: w5500_ll::SocketStatus::Established
+    /// [`tcp_set_keepalive`]: Tcp::tcp_set_keepalive
+    /// [timeout interrupt]: w5500_ll::SocketInterrupt::timeout_raised
+    fn tcp_send_keepalive(&mut self, sn: Sn) -> Result<(), Self::Error> {
+        self.send_keep(sn)
+    }
+
     /// Create a TCP reader.
     ///
     /// This returns a [`TcpReader`] structure, which contains functions to
@@ -1,14 +1,20 @@
 use crate::{
     io::{Read, Seek, SeekFrom, Write},
-    port_is_unique, Error, TcpReader,
+    port_is_unique, BindError, Error, TcpReader,
 };
 use core::cmp::min;
 use w5500_ll::{
-    net::{Ipv4Addr, SocketAddrV4},
+    net::{self, Eui48Addr, Ipv4Addr, SocketAddrV4},
     Protocol, Registers, Sn, SocketCommand, SocketMode, SocketStatus, TxPtrs,
 };
 
 /// W5500 UDP Header.
+///
+/// The W5500 prepends this header to each datagram in the socket RX buffer.
+/// The header format is fixed, the source hardware address of a datagram is
+/// not available in UDP mode.
+/// Use a [`Macraw`](crate::Macraw) socket to receive the Ethernet frames
+/// when the source hardware address is required.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct UdpHeader {
@@ -27,16 +33,13 @@ impl UdpHeader {
     // * 4 bytes origin IP
     // * 2 bytes origin port
     // * 2 bytes size
-    const LEN: u16 = 8;
-    const LEN_USIZE: usize = Self::LEN as usize;
+    pub(crate) const LEN: u16 = 8;
+    pub(crate) const LEN_USIZE: usize = Self::LEN as usize;
 
     /// Deserialize a UDP header.
-    fn deser(buf: [u8; Self::LEN_USIZE]) -> UdpHeader {
+    pub(crate) fn deser(buf: [u8; Self::LEN_USIZE]) -> UdpHeader {
         UdpHeader {
-            origin: SocketAddrV4::new(
-                Ipv4Addr::new(buf[0], buf[1], buf[2], buf[3]),
-                u16::from_be_bytes([buf[4], buf[5]]),
-            ),
+            origin: net::socket_addr_from_bytes([buf[0], buf[1], buf[2], buf[3], buf[4], buf[5]]),
             len: u16::from_be_bytes([buf[6], buf[7]]),
         }
     }
@@ -329,22 +332,152 @@ pub trait Udp: Registers {
             "Local port {port} is in use"
         );
 
-        self.set_sn_cr(sn, SocketCommand::Close)?;
-        // This will not hang, the socket status will always change to closed
-        // after a close command.
-        // (unless you do somthing silly like holding the W5500 in reset)
-        while self.sn_sr(sn)? != Ok(SocketStatus::Closed) {}
-        self.set_sn_port(sn, port)?;
         const MODE: SocketMode = SocketMode::DEFAULT.set_protocol(Protocol::Udp);
-        self.set_sn_mr(sn, MODE)?;
-        self.set_sn_cr(sn, SocketCommand::Open)?;
-        // This will not hang, the socket status will always change to Udp
-        // after a open command with SN_MR set to UDP.
-        // (unless you do somthing silly like holding the W5500 in reset)
-        while self.sn_sr(sn)? != Ok(SocketStatus::Udp) {}
+        let status: Result<SocketStatus, u8> = self.open_socket(sn, MODE, port)?;
+        debug_assert_eq!(status, Ok(SocketStatus::Udp));
+        Ok(())
+    }
+
+    /// Binds the socket to the given port, returning an error if the port is
+    /// in use.
+    ///
+    /// This is the same as [`Udp::udp_bind`], but the port is always checked
+    /// instead of a debug assertion.
+    ///
+    /// # Errors
+    ///
+    /// * [`BindError::PortInUse`] if the port is in use by any other socket
+    ///   on the W5500.
+    /// * [`BindError::Other`]
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{
+    ///     ll::{Registers, Sn::Sn0},
+    ///     BindError, Udp,
+    /// };
+    ///
+    /// match w5500.try_udp_bind(Sn0, 8080) {
+    ///     Ok(()) => (),
+    ///     Err(BindError::PortInUse) => (), // try another port
+    ///     Err(BindError::Other(e)) => return Err(e),
+    /// }
+    /// # Ok::<(), embedded_hal::spi::ErrorKind>(())
+    /// ```
+    fn try_udp_bind(&mut self, sn: Sn, port: u16) -> Result<(), BindError<Self::Error>> {
+        if !port_is_unique(self, sn, port)? {
+            return Err(BindError::PortInUse);
+        }
+        Ok(self.udp_bind(sn, port)?)
+    }
+
+    /// Binds the socket to the given port, and joins a multicast group.
+    ///
+    /// This will close the socket, which will reset the RX and TX buffers.
+    ///
+    /// The W5500 sends an IGMP join when the socket is opened, and an IGMP
+    /// leave when the socket is closed.
+    /// Data sent with [`Udp::udp_send`] is sent to the multicast group.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The port must not be in use by any other socket on the W5500.
+    /// * (debug) The group address must be a multicast address.
+    ///
+    /// # Example
+    ///
+    /// Join the mDNS multicast group.
+    ///
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{
+    ///     ll::{Registers, Sn::Sn0},
+    ///     net::{Ipv4Addr, SocketAddrV4},
+    ///     Udp,
+    /// };
+    ///
+    /// const MDNS: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(224, 0, 0, 251), 5353);
+    ///
+    /// w5500.udp_multicast_bind(Sn0, 5353, &MDNS)?;
+    /// # Ok::<(), embedded_hal::spi::ErrorKind>(())
+    /// ```
+    fn udp_multicast_bind(
+        &mut self,
+        sn: Sn,
+        port: u16,
+        group: &SocketAddrV4,
+    ) -> Result<(), Self::Error> {
+        debug_assert!(
+            port_is_unique(self, sn, port)?,
+            "Local port {port} is in use"
+        );
+        debug_assert!(
+            group.ip().is_multicast(),
+            "{} is not a multicast address",
+            group.ip()
+        );
+
+        // The multicast group must be configured before opening the socket,
+        // closing the socket does not change these registers.
+        // The hardware address is derived from the lower 23 bits of the
+        // group IP address as described in RFC 1112.
+        let ip: [u8; 4] = group.ip().octets();
+        let dhar: Eui48Addr = Eui48Addr::new(0x01, 0x00, 0x5E, ip[1] & 0x7F, ip[2], ip[3]);
+        self.set_sn_dhar(sn, &dhar)?;
+        self.set_sn_dest(sn, group)?;
+        const MODE: SocketMode = SocketMode::DEFAULT
+            .set_protocol(Protocol::Udp)
+            .enable_multi();
+        let status: Result<SocketStatus, u8> = self.open_socket(sn, MODE, port)?;
+        debug_assert_eq!(status, Ok(SocketStatus::Udp));
         Ok(())
     }
 
+    /// Binds the socket to the given port, and connects it to a remote peer.
+    ///
+    /// This will close the socket, which will reset the RX and TX buffers.
+    ///
+    /// The peer is stored in the socket destination registers, data can be
+    /// sent to the peer with [`Udp::udp_send`], and received from the peer
+    /// with [`Udp::udp_recv`].
+    ///
+    /// # Comparison to [`std::net::UdpSocket::connect`]
+    ///
+    /// * The W5500 does not filter incoming datagrams, datagrams from other
+    ///   origins are discarded by [`Udp::udp_recv`], but not by
+    ///   [`Udp::udp_recv_from`].
+    /// * Calling [`Udp::udp_send_to`] or [`Registers::set_sn_dest`] changes
+    ///   the connected peer.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The port must not be in use by any other socket on the W5500.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{
+    ///     ll::{Registers, Sn::Sn0},
+    ///     net::{Ipv4Addr, SocketAddrV4},
+    ///     Udp,
+    /// };
+    ///
+    /// const PEER: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 8081);
+    ///
+    /// w5500.udp_connect(Sn0, 8080, &PEER)?;
+    /// let tx_bytes: u16 = w5500.udp_send(Sn0, b"ping")?;
+    /// # Ok::<(), embedded_hal::spi::ErrorKind>(())
+    /// ```
+    ///
+    /// [`std::net::UdpSocket::connect`]: https://doc.rust-lang.org/std/net/struct.UdpSocket.html#method.connect
+    fn udp_connect(&mut self, sn: Sn, port: u16, peer: &SocketAddrV4) -> Result<(), Self::Error> {
+        self.udp_bind(sn, port)?;
+        self.set_sn_dest(sn, peer)
+    }
+
     /// Receives a single datagram message on the socket.
     /// On success, returns the number of bytes read and the origin.
     ///
@@ -429,6 +562,94 @@ pub trait Udp: Registers {
         Ok((read_size, header.origin))
     }
 
+    /// Receives a single datagram message from the connected peer.
+    /// On success, returns the number of bytes read.
+    ///
+    /// The connected peer is the destination set by [`Udp::udp_connect`].
+    /// Datagrams from any other origin are discarded.
+    ///
+    /// If a message is too long to fit in the supplied buffer, excess bytes
+    /// will be discarded.
+    ///
+    /// # Comparison to [`std::net::UdpSocket::recv`]
+    ///
+    /// * This method will always discard excess bytes from the socket buffer.
+    /// * This method is non-blocking, use [`block`] to treat it as blocking.
+    ///
+    /// # Errors
+    ///
+    /// This method can only return:
+    ///
+    /// * [`Error::Other`]
+    /// * [`Error::WouldBlock`]
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The socket must be opened as a UDP socket.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{
+    ///     block,
+    ///     ll::{Registers, Sn::Sn0},
+    ///     net::{Ipv4Addr, SocketAddrV4},
+    ///     Udp,
+    /// };
+    ///
+    /// const PEER: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 8081);
+    ///
+    /// w5500.udp_connect(Sn0, 8080, &PEER)?;
+    /// w5500.udp_send(Sn0, b"ping")?;
+    /// let mut buf = [0; 10];
+    /// let number_of_bytes: u16 = block!(w5500.udp_recv(Sn0, &mut buf))?;
+    /// let filled_buf = &mut buf[..number_of_bytes.into()];
+    /// # Ok::<(), w5500_hl::Error<_>>(())
+    /// ```
+    ///
+    /// [`std::net::UdpSocket::recv`]: https://doc.rust-lang.org/std/net/struct.UdpSocket.html#method.recv
+    /// [`block`]: crate::block
+    fn udp_recv(&mut self, sn: Sn, buf: &mut [u8]) -> Result<u16, Error<Self::Error>> {
+        loop {
+            let rsr: u16 = match self.sn_rx_rsr(sn)?.checked_sub(UdpHeader::LEN) {
+                Some(rsr) => rsr,
+                // nothing to recieve
+                None => return Err(Error::WouldBlock),
+            };
+
+            debug_assert_eq!(self.sn_sr(sn)?, Ok(SocketStatus::Udp));
+
+            let mut ptr: u16 = self.sn_rx_rd(sn)?;
+            let mut header: [u8; UdpHeader::LEN_USIZE] = [0; UdpHeader::LEN_USIZE];
+            self.sn_rx_buf(sn, ptr, &mut header)?;
+            ptr = ptr.wrapping_add(UdpHeader::LEN);
+            let header: UdpHeader = UdpHeader::deser(header);
+
+            // not all data as indicated by the header has been buffered
+            if rsr < header.len {
+                return Err(Error::WouldBlock);
+            }
+
+            let connected: bool = header.origin == self.sn_dest(sn)?;
+            let read_size: u16 = if connected {
+                min(header.len, buf.len().try_into().unwrap_or(u16::MAX))
+            } else {
+                0
+            };
+            if read_size != 0 {
+                self.sn_rx_buf(sn, ptr, &mut buf[..read_size.into()])?;
+            }
+            ptr = ptr.wrapping_add(header.len);
+            self.set_sn_rx_rd(sn, ptr)?;
+            self.set_sn_cr(sn, SocketCommand::Recv)?;
+
+            if connected {
+                return Ok(read_size);
+            }
+        }
+    }
+
     /// Receives a single datagram message on the socket, without removing it
     /// from the queue.
     /// On success, returns the number of bytes read and the UDP header.
@@ -648,6 +869,150 @@ pub trait Udp: Registers {
         self.udp_send_if_free(sn, buf)
     }
 
+    /// Sends data from multiple buffers on the socket to the given address,
+    /// as a single datagram.
+    /// On success, returns the number of bytes written.
+    ///
+    /// The buffers are written one after another into the socket TX buffer,
+    /// this avoids copying a header and a payload into a single buffer.
+    ///
+    /// Like [`udp_send_to`](Udp::udp_send_to) the datagram is truncated to the
+    /// free space in the W5500 transmit buffer.
+    ///
+    /// # Comparison to [`std::io::Write::write_vectored`]
+    ///
+    /// * You cannot transmit more than `u16::MAX` bytes at once.
+    /// * The buffers are `&[u8]`, not [`std::io::IoSlice`].
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The socket must be opened as a UDP socket.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{
+    ///     ll::{Registers, Sn::Sn0},
+    ///     net::{Ipv4Addr, SocketAddrV4},
+    ///     Udp,
+    /// };
+    ///
+    /// const DEST: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 8081);
+    ///
+    /// w5500.udp_bind(Sn0, 8080)?;
+    /// let header: [u8; 4] = [0x01, 0x02, 0x03, 0x04];
+    /// let payload: [u8; 10] = [0; 10];
+    /// let tx_bytes: u16 = w5500.udp_send_to_vectored(Sn0, &[&header, &payload], &DEST)?;
+    /// assert_eq!(usize::from(tx_bytes), header.len() + payload.len());
+    /// # Ok::<(), embedded_hal::spi::ErrorKind>(())
+    /// ```
+    ///
+    /// [`std::io::Write::write_vectored`]: https://doc.rust-lang.org/std/io/trait.Write.html#method.write_vectored
+    /// [`std::io::IoSlice`]: https://doc.rust-lang.org/std/io/struct.IoSlice.html
+    fn udp_send_to_vectored(
+        &mut self,
+        sn: Sn,
+        bufs: &[&[u8]],
+        addr: &SocketAddrV4,
+    ) -> Result<u16, Self::Error> {
+        debug_assert_eq!(self.sn_sr(sn)?, Ok(SocketStatus::Udp));
+
+        self.set_sn_dest(sn, addr)?;
+
+        let data_len: usize = bufs
+            .iter()
+            .fold(0, |acc, buf| acc.saturating_add(buf.len()));
+        let data_len: u16 = u16::try_from(data_len).unwrap_or(u16::MAX);
+        let free_size: u16 = self.sn_tx_fsr(sn)?;
+        let tx_bytes: u16 = min(data_len, free_size);
+        if tx_bytes != 0 {
+            let mut ptr: u16 = self.sn_tx_wr(sn)?;
+            let mut remain: u16 = tx_bytes;
+            for buf in bufs {
+                if remain == 0 {
+                    break;
+                }
+                let len: u16 = min(remain, u16::try_from(buf.len()).unwrap_or(u16::MAX));
+                self.set_sn_tx_buf(sn, ptr, &buf[..len.into()])?;
+                ptr = ptr.wrapping_add(len);
+                remain -= len;
+            }
+            self.set_sn_tx_wr(sn, ptr)?;
+            self.set_sn_cr(sn, SocketCommand::Send)?;
+        }
+        Ok(tx_bytes)
+    }
+
+    /// Sends data on the socket to the limited broadcast address
+    /// (`255.255.255.255`) with the given port.
+    /// On success, returns the number of bytes written.
+    ///
+    /// Broadcast blocking ([`SocketMode::bcastb_enabled`]) only filters received
+    /// broadcast datagrams, it does not need to be cleared to send.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The socket must be opened as a UDP socket.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{
+    ///     ll::{Registers, Sn::Sn0},
+    ///     Udp,
+    /// };
+    ///
+    /// w5500.udp_bind(Sn0, 8080)?;
+    /// let buf: [u8; 10] = [0; 10];
+    /// let tx_bytes: u16 = w5500.udp_broadcast(Sn0, &buf, 8081)?;
+    /// assert_eq!(usize::from(tx_bytes), buf.len());
+    /// # Ok::<(), embedded_hal::spi::ErrorKind>(())
+    /// ```
+    ///
+    /// [`SocketMode::bcastb_enabled`]: w5500_ll::SocketMode::bcastb_enabled
+    fn udp_broadcast(&mut self, sn: Sn, buf: &[u8], port: u16) -> Result<u16, Self::Error> {
+        self.udp_send_to(sn, buf, &SocketAddrV4::new(Ipv4Addr::BROADCAST, port))
+    }
+
+    /// Sends data on the socket to the directed broadcast address of the
+    /// local subnet with the given port.
+    /// On success, returns the number of bytes written.
+    ///
+    /// The broadcast address is calculated from the source IP address
+    /// ([`Registers::sipr`]) and the subnet mask ([`Registers::subr`]).
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The socket must be opened as a UDP socket.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{
+    ///     ll::{Registers, Sn::Sn0},
+    ///     net::Ipv4Addr,
+    ///     Udp,
+    /// };
+    ///
+    /// w5500.set_sipr(&Ipv4Addr::new(192, 168, 1, 10))?;
+    /// w5500.set_subr(&Ipv4Addr::new(255, 255, 255, 0))?;
+    /// w5500.udp_bind(Sn0, 8080)?;
+    /// let buf: [u8; 10] = [0; 10];
+    /// // sends to 192.168.1.255:8081
+    /// let tx_bytes: u16 = w5500.udp_subnet_broadcast(Sn0, &buf, 8081)?;
+    /// assert_eq!(usize::from(tx_bytes), buf.len());
+    /// # Ok::<(), embedded_hal::spi::ErrorKind>(())
+    /// ```
+    fn udp_subnet_broadcast(&mut self, sn: Sn, buf: &[u8], port: u16) -> Result<u16, Self::Error> {
+        let sipr: u32 = self.sipr()?.into();
+        let subr: u32 = self.subr()?.into();
+        let broadcast: Ipv4Addr = Ipv4Addr::from(sipr | !subr);
+        self.udp_send_to(sn, buf, &SocketAddrV4::new(broadcast, port))
+    }
+
     /// Sends data to the currently configured destination.
     /// On success, returns the number of bytes written.
     ///
@@ -749,6 +1114,101 @@ pub trait Udp: Registers {
         Ok(data_len)
     }
 
+    /// Sends a request to `peer` and waits for a response from `peer`,
+    /// retransmitting the request if no response is received.
+    /// On success, returns the number of bytes read into `buf`.
+    ///
+    /// After each transmission [`Udp::udp_recv_from`] is polled up to
+    /// `polls` times.
+    /// Datagrams from any origin other than `peer` are discarded.
+    /// If no response is received the request is retransmitted, up to
+    /// `retries` times.
+    ///
+    /// Before each transmission the free size of the transmit buffer is
+    /// polled up to `polls` times, waiting for previous datagrams to be sent.
+    ///
+    /// There is no delay between polls, the number of polls required for a
+    /// given timeout depends on the SPI clock.
+    ///
+    /// If a response is too long to fit in the supplied buffer, excess bytes
+    /// will be discarded.
+    ///
+    /// # Errors
+    ///
+    /// This method can only return:
+    ///
+    /// * [`Error::Other`]
+    /// * [`Error::WouldBlock`] if no response was received from `peer` after
+    ///   all retries, or if there was not enough free space in the transmit
+    ///   buffer for `payload` after `polls` polls.
+    /// * [`Error::OutOfMemory`] if `payload` is larger than the transmit
+    ///   buffer.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The socket must be opened as a UDP socket.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_hl::{
+    ///     ll::{Registers, Sn::Sn0},
+    ///     net::{Ipv4Addr, SocketAddrV4},
+    ///     Udp,
+    /// };
+    ///
+    /// const PEER: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 8081);
+    ///
+    /// w5500.udp_bind(Sn0, 8080)?;
+    /// let mut buf = [0; 10];
+    /// let number_of_bytes: u16 = w5500.udp_send_recv(Sn0, b"ping", &PEER, &mut buf, 3, 10_000)?;
+    /// let response = &buf[..number_of_bytes.into()];
+    /// # Ok::<(), w5500_hl::Error<_>>(())
+    /// ```
+    fn udp_send_recv(
+        &mut self,
+        sn: Sn,
+        payload: &[u8],
+        peer: &SocketAddrV4,
+        buf: &mut [u8],
+        retries: u8,
+        polls: u32,
+    ) -> Result<u16, Error<Self::Error>> {
+        let buf_size: usize = self
+            .sn_txbuf_size(sn)?
+            .map_or(0, |size| size.size_in_bytes());
+        // payloads larger than the buffer would never be sent
+        let payload_len: u16 = match u16::try_from(payload.len()) {
+            Ok(len) if payload.len() <= buf_size => len,
+            _ => return Err(Error::OutOfMemory),
+        };
+
+        for _ in 0..=retries {
+            let mut free: bool = false;
+            for _ in 0..polls.max(1) {
+                if payload_len <= self.sn_tx_fsr(sn)? {
+                    free = true;
+                    break;
+                }
+            }
+            if !free {
+                return Err(Error::WouldBlock);
+            }
+            self.udp_send_to(sn, payload, peer)?;
+
+            for _ in 0..polls {
+                match self.udp_recv_from(sn, buf) {
+                    Ok((n, origin)) if origin == *peer => return Ok(n),
+                    Ok(_) | Err(Error::WouldBlock) => (),
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        Err(Error::WouldBlock)
+    }
+
     /// Create a UDP reader.
     ///
     /// This returns a [`UdpReader`] structure, which contains functions to
@@ -0,0 +1,117 @@
+use std::{collections::VecDeque, convert::Infallible};
+use w5500_hl::{
+    aio::{Tcp, Udp},
+    Error,
+};
+use w5500_ll::{
+    aio::Registers,
+    net::{Ipv4Addr, SocketAddrV4},
+    Sn, SocketCommand, SocketStatus,
+};
+
+/// Datagram in the socket buffer: 192.168.2.4:8080, 4 bytes
+const RX: [u8; 12] = [192, 168, 2, 4, 0x1F, 0x90, 0, 4, 0xDE, 0xAD, 0xBE, 0xEF];
+
+struct MockRegisters {
+    status: SocketStatus,
+    rsr: VecDeque<u16>,
+    ptr: u16,
+    cmds: Vec<SocketCommand>,
+}
+
+impl Registers for MockRegisters {
+    type Error = Infallible;
+
+    async fn sn_sr(&mut self, _sn: Sn) -> Result<Result<SocketStatus, u8>, Self::Error> {
+        Ok(Ok(self.status))
+    }
+
+    async fn sn_rx_rsr(&mut self, _sn: Sn) -> Result<u16, Self::Error> {
+        Ok(if self.rsr.len() > 1 {
+            self.rsr.pop_front().unwrap()
+        } else {
+            self.rsr.front().copied().unwrap_or(0)
+        })
+    }
+
+    async fn sn_rx_rd(&mut self, _sn: Sn) -> Result<u16, Self::Error> {
+        Ok(self.ptr)
+    }
+
+    async fn set_sn_rx_rd(&mut self, _sn: Sn, ptr: u16) -> Result<(), Self::Error> {
+        self.ptr = ptr;
+        Ok(())
+    }
+
+    async fn sn_rx_buf(&mut self, _sn: Sn, ptr: u16, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let ptr: usize = ptr.into();
+        buf.copy_from_slice(&RX[ptr..ptr + buf.len()]);
+        Ok(())
+    }
+
+    async fn set_sn_cr(&mut self, _sn: Sn, cmd: SocketCommand) -> Result<(), Self::Error> {
+        self.cmds.push(cmd);
+        Ok(())
+    }
+
+    async fn read(
+        &mut self,
+        _address: u16,
+        _block: u8,
+        _data: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        unimplemented!()
+    }
+
+    async fn write(&mut self, _address: u16, _block: u8, _data: &[u8]) -> Result<(), Self::Error> {
+        unimplemented!()
+    }
+}
+
+#[tokio::test]
+async fn udp_recv_from_would_block() {
+    let mut mock = MockRegisters {
+        status: SocketStatus::Udp,
+        rsr: VecDeque::from([0, 10, 12]),
+        ptr: 0,
+        cmds: Vec::new(),
+    };
+    let mut buf: [u8; 8] = [0; 8];
+
+    // no header
+    assert_eq!(
+        mock.udp_recv_from(Sn::Sn0, &mut buf).await,
+        Err(Error::WouldBlock)
+    );
+    // header, but incomplete data
+    assert_eq!(
+        mock.udp_recv_from(Sn::Sn0, &mut buf).await,
+        Err(Error::WouldBlock)
+    );
+    assert_eq!(mock.ptr, 0);
+    assert!(mock.cmds.is_empty());
+
+    let (n, origin) = mock.udp_recv_from(Sn::Sn0, &mut buf).await.unwrap();
+    assert_eq!(&buf[..n.into()], &[0xDE, 0xAD, 0xBE, 0xEF]);
+    assert_eq!(
+        origin,
+        SocketAddrV4::new(Ipv4Addr::new(192, 168, 2, 4), 8080)
+    );
+    assert_eq!(mock.ptr, 12);
+    assert_eq!(mock.cmds, [SocketCommand::Recv]);
+}
+
+#[tokio::test]
+async fn tcp_read() {
+    let mut mock = MockRegisters {
+        status: SocketStatus::Established,
+        rsr: VecDeque::from([12]),
+        ptr: 0,
+        cmds: Vec::new(),
+    };
+    let mut buf: [u8; 4] = [0; 4];
+    assert_eq!(mock.tcp_read(Sn::Sn0, &mut buf).await, Ok(4));
+    assert_eq!(buf, [192, 168, 2, 4]);
+    assert_eq!(mock.ptr, 4);
+    assert_eq!(mock.cmds, [SocketCommand::Recv]);
+}
@@ -0,0 +1,135 @@
+use std::{collections::VecDeque, convert::Infallible};
+use w5500_hl::{
+    blocking,
+    ll::{Registers, Sn, SocketCommand, SocketStatus},
+    net::{Ipv4Addr, SocketAddrV4},
+    Error,
+};
+
+/// Socket with an RX buffer filled with incrementing bytes.
+struct MockRegisters {
+    sr: VecDeque<SocketStatus>,
+    rsr: VecDeque<u16>,
+    polls: usize,
+}
+
+impl MockRegisters {
+    fn new(sr: &[SocketStatus], rsr: &[u16]) -> Self {
+        Self {
+            sr: VecDeque::from(sr.to_vec()),
+            rsr: VecDeque::from(rsr.to_vec()),
+            polls: 0,
+        }
+    }
+}
+
+impl Registers for MockRegisters {
+    type Error = Infallible;
+
+    fn sn_sr(&mut self, _socket: Sn) -> Result<Result<SocketStatus, u8>, Self::Error> {
+        Ok(Ok(if self.sr.len() > 1 {
+            self.sr.pop_front().unwrap()
+        } else {
+            self.sr[0]
+        }))
+    }
+
+    fn sn_rx_rsr(&mut self, _socket: Sn) -> Result<u16, Self::Error> {
+        self.polls += 1;
+        Ok(self.rsr.pop_front().unwrap_or(0))
+    }
+
+    fn sn_rx_rd(&mut self, _socket: Sn) -> Result<u16, Self::Error> {
+        Ok(0)
+    }
+
+    fn set_sn_rx_rd(&mut self, _socket: Sn, _ptr: u16) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn sn_rx_buf(&mut self, _socket: Sn, ptr: u16, buf: &mut [u8]) -> Result<(), Self::Error> {
+        // UDP header from 10.0.0.1:1234 with a 4 byte payload
+        const UDP: [u8; 12] = [10, 0, 0, 1, 0x04, 0xD2, 0x00, 0x04, 1, 2, 3, 4];
+        let start: usize = usize::from(ptr);
+        buf.copy_from_slice(&UDP[start..start + buf.len()]);
+        Ok(())
+    }
+
+    fn sn_dest(&mut self, _socket: Sn) -> Result<SocketAddrV4, Self::Error> {
+        Ok(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0))
+    }
+
+    fn set_sn_cr(&mut self, _socket: Sn, cmd: SocketCommand) -> Result<(), Self::Error> {
+        assert_eq!(cmd, SocketCommand::Recv);
+        Ok(())
+    }
+
+    fn read(&mut self, _address: u16, _block: u8, _data: &mut [u8]) -> Result<(), Self::Error> {
+        unimplemented!()
+    }
+
+    fn write(&mut self, _address: u16, _block: u8, _data: &[u8]) -> Result<(), Self::Error> {
+        unimplemented!()
+    }
+}
+
+#[test]
+fn udp_recv_from_waits() {
+    let mut mock = MockRegisters::new(&[SocketStatus::Udp], &[0, 0, 12]);
+    let mut buf: [u8; 8] = [0; 8];
+    assert_eq!(
+        blocking::udp_recv_from(&mut mock, Sn::Sn0, &mut buf, None),
+        Ok((4, SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 1), 1234)))
+    );
+    assert_eq!(&buf[..4], &[1, 2, 3, 4]);
+    assert_eq!(mock.polls, 3);
+}
+
+#[test]
+fn udp_recv_from_max_polls() {
+    let mut mock = MockRegisters::new(&[SocketStatus::Udp], &[]);
+    let mut buf: [u8; 8] = [0; 8];
+    assert_eq!(
+        blocking::udp_recv_from(&mut mock, Sn::Sn0, &mut buf, Some(5)),
+        Err(Error::WouldBlock)
+    );
+    assert_eq!(mock.polls, 5);
+}
+
+#[test]
+fn tcp_read_waits() {
+    let mut mock = MockRegisters::new(&[SocketStatus::Established], &[0, 0, 3]);
+    let mut buf: [u8; 8] = [0; 8];
+    assert_eq!(
+        blocking::tcp_read(&mut mock, Sn::Sn0, &mut buf, Some(10)),
+        Ok(3)
+    );
+    assert_eq!(&buf[..3], &[10, 0, 0]);
+    assert_eq!(mock.polls, 3);
+}
+
+#[test]
+fn tcp_read_max_polls() {
+    let mut mock = MockRegisters::new(&[SocketStatus::Established], &[]);
+    let mut buf: [u8; 8] = [0; 8];
+    assert_eq!(
+        blocking::tcp_read(&mut mock, Sn::Sn0, &mut buf, Some(2)),
+        Err(Error::WouldBlock)
+    );
+    assert_eq!(mock.polls, 2);
+}
+
+#[test]
+fn tcp_read_data_before_close() {
+    // the remote host sent data and closed the connection
+    let mut mock = MockRegisters::new(&[SocketStatus::CloseWait], &[2, 0]);
+    let mut buf: [u8; 8] = [0; 8];
+    assert_eq!(
+        blocking::tcp_read(&mut mock, Sn::Sn0, &mut buf, None),
+        Ok(2)
+    );
+    assert_eq!(
+        blocking::tcp_read(&mut mock, Sn::Sn0, &mut buf, None),
+        Err(Error::Disconnected)
+    );
+}
@@ -249,3 +249,70 @@ mod is_state_udp {
         assert!(mock.states.is_empty())
     }
 }
+
+mod drain_rx {
+    use super::*;
+
+    struct MockRegisters {
+        rsr: u16,
+        ptr: u16,
+        recv_cmds: usize,
+    }
+
+    impl Registers for MockRegisters {
+        type Error = Infallible;
+
+        fn sn_rx_rsr(&mut self, _socket: Sn) -> Result<u16, Self::Error> {
+            Ok(self.rsr)
+        }
+
+        fn sn_rx_rd(&mut self, _socket: Sn) -> Result<u16, Self::Error> {
+            Ok(self.ptr)
+        }
+
+        fn set_sn_rx_rd(&mut self, _socket: Sn, ptr: u16) -> Result<(), Self::Error> {
+            self.ptr = ptr;
+            Ok(())
+        }
+
+        fn set_sn_cr(&mut self, _socket: Sn, cmd: SocketCommand) -> Result<(), Self::Error> {
+            assert_eq!(cmd, SocketCommand::Recv);
+            self.recv_cmds += 1;
+            self.rsr = 0;
+            Ok(())
+        }
+
+        fn read(&mut self, _address: u16, _block: u8, _data: &mut [u8]) -> Result<(), Self::Error> {
+            unimplemented!()
+        }
+
+        fn write(&mut self, _address: u16, _block: u8, _data: &[u8]) -> Result<(), Self::Error> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn drain() {
+        let mut mock = MockRegisters {
+            rsr: 100,
+            ptr: 0xFFF0,
+            recv_cmds: 0,
+        };
+        assert_eq!(mock.drain_rx(Sn::Sn0), Ok(100));
+        assert_eq!(mock.ptr, 0xFFF0_u16.wrapping_add(100));
+        assert_eq!(mock.recv_cmds, 1);
+        assert_eq!(mock.rsr, 0);
+    }
+
+    #[test]
+    fn empty() {
+        let mut mock = MockRegisters {
+            rsr: 0,
+            ptr: 0x1234,
+            recv_cmds: 0,
+        };
+        assert_eq!(mock.drain_rx(Sn::Sn0), Ok(0));
+        assert_eq!(mock.ptr, 0x1234);
+        assert_eq!(mock.recv_cmds, 0);
+    }
+}
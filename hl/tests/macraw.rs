@@ -0,0 +1,128 @@
+use std::convert::Infallible;
+use w5500_hl::{Error, Macraw};
+use w5500_ll::{BufferSize, Registers, Sn, SocketCommand, SocketStatus};
+
+/// Socket buffer with a 6 byte frame and the first byte of a 4 byte frame.
+const RX: [u8; 11] = [0x00, 0x08, 1, 2, 3, 4, 5, 6, 0x00, 0x06, 7];
+
+struct MockRegisters {
+    rsr: u16,
+    ptr: u16,
+    fsr: u16,
+    cmds: Vec<SocketCommand>,
+    sent: Vec<u8>,
+}
+
+impl MockRegisters {
+    fn new(rsr: u16) -> Self {
+        Self {
+            rsr,
+            ptr: 0,
+            fsr: 2048,
+            cmds: Vec::new(),
+            sent: Vec::new(),
+        }
+    }
+}
+
+impl Registers for MockRegisters {
+    type Error = Infallible;
+
+    fn sn_sr(&mut self, socket: Sn) -> Result<Result<SocketStatus, u8>, Self::Error> {
+        assert_eq!(socket, Sn::Sn0);
+        Ok(Ok(SocketStatus::Macraw))
+    }
+
+    fn sn_rx_rsr(&mut self, _socket: Sn) -> Result<u16, Self::Error> {
+        Ok(self.rsr - self.ptr)
+    }
+
+    fn sn_rx_rd(&mut self, _socket: Sn) -> Result<u16, Self::Error> {
+        Ok(self.ptr)
+    }
+
+    fn set_sn_rx_rd(&mut self, _socket: Sn, ptr: u16) -> Result<(), Self::Error> {
+        self.ptr = ptr;
+        Ok(())
+    }
+
+    fn sn_rx_buf(&mut self, _socket: Sn, ptr: u16, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let ptr: usize = ptr.into();
+        buf.copy_from_slice(&RX[ptr..ptr + buf.len()]);
+        Ok(())
+    }
+
+    fn sn_txbuf_size(&mut self, _socket: Sn) -> Result<Result<BufferSize, u8>, Self::Error> {
+        Ok(Ok(BufferSize::KB2))
+    }
+
+    fn sn_tx_fsr(&mut self, _socket: Sn) -> Result<u16, Self::Error> {
+        Ok(self.fsr)
+    }
+
+    fn sn_tx_wr(&mut self, _socket: Sn) -> Result<u16, Self::Error> {
+        Ok(0)
+    }
+
+    fn set_sn_tx_wr(&mut self, _socket: Sn, _ptr: u16) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn set_sn_tx_buf(&mut self, _socket: Sn, _ptr: u16, buf: &[u8]) -> Result<(), Self::Error> {
+        self.sent.extend_from_slice(buf);
+        Ok(())
+    }
+
+    fn set_sn_cr(&mut self, _socket: Sn, cmd: SocketCommand) -> Result<(), Self::Error> {
+        self.cmds.push(cmd);
+        Ok(())
+    }
+
+    fn read(&mut self, _address: u16, _block: u8, _data: &mut [u8]) -> Result<(), Self::Error> {
+        unimplemented!()
+    }
+
+    fn write(&mut self, _address: u16, _block: u8, _data: &[u8]) -> Result<(), Self::Error> {
+        unimplemented!()
+    }
+}
+
+#[test]
+fn recv_frame() {
+    let mut mock = MockRegisters::new(RX.len() as u16);
+    let mut buf: [u8; 8] = [0; 8];
+    assert_eq!(mock.macraw_recv_frame(&mut buf), Ok(6));
+    assert_eq!(buf[..6], [1, 2, 3, 4, 5, 6]);
+    assert_eq!(mock.ptr, 8);
+    assert_eq!(mock.cmds, [SocketCommand::Recv]);
+
+    // second frame is incomplete
+    assert_eq!(mock.macraw_recv_frame(&mut buf), Err(Error::WouldBlock));
+}
+
+#[test]
+fn recv_frame_truncated() {
+    let mut mock = MockRegisters::new(RX.len() as u16);
+    let mut buf: [u8; 4] = [0; 4];
+    assert_eq!(mock.macraw_recv_frame(&mut buf), Ok(4));
+    assert_eq!(buf, [1, 2, 3, 4]);
+    assert_eq!(mock.ptr, 8);
+}
+
+#[test]
+fn recv_frame_empty() {
+    let mut mock = MockRegisters::new(0);
+    assert_eq!(mock.macraw_recv_frame(&mut []), Err(Error::WouldBlock));
+}
+
+#[test]
+fn send_frame() {
+    let mut mock = MockRegisters::new(0);
+    assert_eq!(mock.macraw_send_frame(&[1, 2, 3]), Ok(()));
+    assert_eq!(mock.sent, [1, 2, 3]);
+    assert_eq!(mock.cmds, [SocketCommand::Send]);
+
+    mock.fsr = 2;
+    assert_eq!(mock.macraw_send_frame(&[1, 2, 3]), Err(Error::WouldBlock));
+    assert_eq!(mock.macraw_send_frame(&[0; 2049]), Err(Error::OutOfMemory));
+}
@@ -0,0 +1,420 @@
+use std::{collections::VecDeque, convert::Infallible};
+use w5500_hl::Tcp;
+use w5500_ll::{Registers, Sn, SocketCommand, SocketStatus};
+
+/// Tests reading until the socket RX buffer is drained.
+mod read_to_end {
+    use super::*;
+
+    struct MockRegisters {
+        rsr: VecDeque<u16>,
+        ptr: u16,
+        recv_cmds: usize,
+    }
+
+    impl Registers for MockRegisters {
+        type Error = Infallible;
+
+        fn sn_sr(&mut self, _socket: Sn) -> Result<Result<SocketStatus, u8>, Self::Error> {
+            Ok(Ok(SocketStatus::Established))
+        }
+
+        fn sn_rx_rsr(&mut self, _socket: Sn) -> Result<u16, Self::Error> {
+            Ok(self.rsr.front().copied().unwrap_or(0))
+        }
+
+        fn sn_rx_rd(&mut self, _socket: Sn) -> Result<u16, Self::Error> {
+            Ok(self.ptr)
+        }
+
+        fn set_sn_rx_rd(&mut self, _socket: Sn, ptr: u16) -> Result<(), Self::Error> {
+            self.ptr = ptr;
+            Ok(())
+        }
+
+        fn sn_rx_buf(&mut self, _socket: Sn, ptr: u16, buf: &mut [u8]) -> Result<(), Self::Error> {
+            buf.iter_mut()
+                .zip(ptr..)
+                .for_each(|(byte, ptr)| *byte = ptr as u8);
+            Ok(())
+        }
+
+        fn set_sn_cr(&mut self, _socket: Sn, cmd: SocketCommand) -> Result<(), Self::Error> {
+            assert_eq!(cmd, SocketCommand::Recv);
+            self.recv_cmds += 1;
+            self.rsr.pop_front();
+            Ok(())
+        }
+
+        fn read(&mut self, _address: u16, _block: u8, _data: &mut [u8]) -> Result<(), Self::Error> {
+            unimplemented!()
+        }
+
+        fn write(&mut self, _address: u16, _block: u8, _data: &[u8]) -> Result<(), Self::Error> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn drains_rx_buffer() {
+        let mut test = MockRegisters {
+            rsr: VecDeque::from([3, 2]),
+            ptr: 0,
+            recv_cmds: 0,
+        };
+        let mut buf: [u8; 8] = [0xFF; 8];
+        assert_eq!(test.tcp_read_to_end(Sn::Sn0, &mut buf), Ok(5));
+        assert_eq!(buf, [0, 1, 2, 3, 4, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(test.recv_cmds, 2);
+    }
+
+    #[test]
+    fn empty() {
+        let mut test = MockRegisters {
+            rsr: VecDeque::new(),
+            ptr: 0,
+            recv_cmds: 0,
+        };
+        let mut buf: [u8; 8] = [0; 8];
+        assert_eq!(test.tcp_read_to_end(Sn::Sn0, &mut buf), Ok(0));
+        assert_eq!(test.recv_cmds, 0);
+    }
+
+    #[test]
+    fn bytes_available() {
+        let mut test = MockRegisters {
+            rsr: VecDeque::from([3, 2]),
+            ptr: 0,
+            recv_cmds: 0,
+        };
+        assert_eq!(test.tcp_bytes_available(Sn::Sn0), Ok(3));
+        let mut buf: [u8; 3] = [0; 3];
+        assert_eq!(test.tcp_read(Sn::Sn0, &mut buf), Ok(3));
+        assert_eq!(test.tcp_bytes_available(Sn::Sn0), Ok(2));
+    }
+
+    #[test]
+    fn buffer_full() {
+        let mut test = MockRegisters {
+            rsr: VecDeque::from([6]),
+            ptr: 0,
+            recv_cmds: 0,
+        };
+        let mut buf: [u8; 4] = [0; 4];
+        assert_eq!(test.tcp_read_to_end(Sn::Sn0, &mut buf), Ok(4));
+        assert_eq!(buf, [0, 1, 2, 3]);
+    }
+}
+
+/// Tests the non-blocking TCP connect state machine.
+mod connect_nb {
+    use super::*;
+    use w5500_hl::{
+        net::{Ipv4Addr, SocketAddrV4},
+        Error,
+    };
+    use w5500_ll::{Protocol, SocketMode};
+
+    const PORT: u16 = 12345;
+    const ADDR: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 168, 2, 10), 1883);
+
+    /// Socket 0 with status changes applied immediately.
+    struct MockRegisters {
+        sr: SocketStatus,
+        port: u16,
+        dest: Option<SocketAddrV4>,
+        cmds: Vec<SocketCommand>,
+    }
+
+    impl Registers for MockRegisters {
+        type Error = Infallible;
+
+        fn sn_sr(&mut self, socket: Sn) -> Result<Result<SocketStatus, u8>, Self::Error> {
+            Ok(Ok(if socket == Sn::Sn0 {
+                self.sr
+            } else {
+                SocketStatus::Closed
+            }))
+        }
+
+        fn sn_port(&mut self, socket: Sn) -> Result<u16, Self::Error> {
+            Ok(if socket == Sn::Sn0 { self.port } else { 0 })
+        }
+
+        fn set_sn_port(&mut self, _socket: Sn, port: u16) -> Result<(), Self::Error> {
+            self.port = port;
+            Ok(())
+        }
+
+        fn set_sn_mr(&mut self, _socket: Sn, mode: SocketMode) -> Result<(), Self::Error> {
+            assert_eq!(mode.protocol(), Ok(Protocol::Tcp));
+            Ok(())
+        }
+
+        fn set_sn_dest(&mut self, _socket: Sn, addr: &SocketAddrV4) -> Result<(), Self::Error> {
+            self.dest = Some(*addr);
+            Ok(())
+        }
+
+        fn set_sn_cr(&mut self, _socket: Sn, cmd: SocketCommand) -> Result<(), Self::Error> {
+            self.sr = match cmd {
+                SocketCommand::Close => SocketStatus::Closed,
+                SocketCommand::Open => SocketStatus::Init,
+                SocketCommand::Connect => SocketStatus::SynSent,
+                _ => panic!("unexpected command {cmd:?}"),
+            };
+            self.cmds.push(cmd);
+            Ok(())
+        }
+
+        fn read(&mut self, _address: u16, _block: u8, _data: &mut [u8]) -> Result<(), Self::Error> {
+            unimplemented!()
+        }
+
+        fn write(&mut self, _address: u16, _block: u8, _data: &[u8]) -> Result<(), Self::Error> {
+            unimplemented!()
+        }
+    }
+
+    fn connect(mock: &mut MockRegisters) -> usize {
+        let mut calls: usize = 1;
+        while mock.tcp_connect_nb(Sn::Sn0, PORT, &ADDR) == Err(Error::WouldBlock) {
+            calls += 1;
+            assert!(calls < 10);
+        }
+        calls
+    }
+
+    #[test]
+    fn from_established() {
+        let mut mock = MockRegisters {
+            sr: SocketStatus::Established,
+            port: 80,
+            dest: None,
+            cmds: Vec::new(),
+        };
+        assert_eq!(connect(&mut mock), 3);
+        assert_eq!(
+            mock.cmds,
+            [
+                SocketCommand::Close,
+                SocketCommand::Open,
+                SocketCommand::Connect
+            ]
+        );
+        assert_eq!(mock.port, PORT);
+        assert_eq!(mock.dest, Some(ADDR));
+        assert_eq!(mock.sr, SocketStatus::SynSent);
+    }
+
+    #[test]
+    fn from_closed() {
+        let mut mock = MockRegisters {
+            sr: SocketStatus::Closed,
+            port: 0,
+            dest: None,
+            cmds: Vec::new(),
+        };
+        assert_eq!(connect(&mut mock), 2);
+        assert_eq!(mock.cmds, [SocketCommand::Open, SocketCommand::Connect]);
+    }
+
+    #[test]
+    fn init_with_other_port() {
+        let mut mock = MockRegisters {
+            sr: SocketStatus::Init,
+            port: 80,
+            dest: None,
+            cmds: Vec::new(),
+        };
+        assert_eq!(connect(&mut mock), 3);
+        assert_eq!(mock.cmds[0], SocketCommand::Close);
+        assert_eq!(mock.port, PORT);
+    }
+}
+
+/// Tests counting the bytes in the TX buffer not acknowledged by the peer.
+mod unacked_bytes {
+    use super::*;
+    use w5500_ll::BufferSize;
+
+    struct MockRegisters {
+        size: Result<BufferSize, u8>,
+        fsr: u16,
+    }
+
+    impl Registers for MockRegisters {
+        type Error = Infallible;
+
+        fn sn_txbuf_size(&mut self, _socket: Sn) -> Result<Result<BufferSize, u8>, Self::Error> {
+            Ok(self.size)
+        }
+
+        fn sn_tx_fsr(&mut self, _socket: Sn) -> Result<u16, Self::Error> {
+            Ok(self.fsr)
+        }
+
+        fn read(&mut self, _address: u16, _block: u8, _data: &mut [u8]) -> Result<(), Self::Error> {
+            unimplemented!()
+        }
+
+        fn write(&mut self, _address: u16, _block: u8, _data: &[u8]) -> Result<(), Self::Error> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn unacked() {
+        let mut test = MockRegisters {
+            size: Ok(BufferSize::KB2),
+            fsr: 2048,
+        };
+        assert_eq!(test.tcp_unacked_bytes(Sn::Sn0), Ok(0));
+        test.fsr = 2000;
+        assert_eq!(test.tcp_unacked_bytes(Sn::Sn0), Ok(48));
+        test.size = Ok(BufferSize::KB16);
+        test.fsr = 0;
+        assert_eq!(test.tcp_unacked_bytes(Sn::Sn0), Ok(16384));
+    }
+
+    #[test]
+    fn invalid_size() {
+        let mut test = MockRegisters {
+            size: Err(0x03),
+            fsr: 0,
+        };
+        assert_eq!(test.tcp_unacked_bytes(Sn::Sn0), Ok(0));
+    }
+}
+
+/// Tests the try_tcp_connect method returns an error for a port in use
+mod try_tcp_connect {
+    use super::*;
+    use w5500_hl::{
+        net::{Ipv4Addr, SocketAddrV4},
+        BindError,
+    };
+
+    struct MockRegisters {}
+
+    impl Registers for MockRegisters {
+        type Error = Infallible;
+
+        fn sn_port(&mut self, _socket: Sn) -> Result<u16, Self::Error> {
+            Ok(33650)
+        }
+
+        fn sn_sr(&mut self, _socket: Sn) -> Result<Result<SocketStatus, u8>, Self::Error> {
+            Ok(Ok(SocketStatus::Established))
+        }
+
+        fn read(&mut self, _address: u16, _block: u8, _data: &mut [u8]) -> Result<(), Self::Error> {
+            unimplemented!()
+        }
+
+        fn write(&mut self, _address: u16, _block: u8, _data: &[u8]) -> Result<(), Self::Error> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn port_in_use() {
+        const SERVER: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 168, 2, 10), 1883);
+        let mut mock = MockRegisters {};
+        assert_eq!(
+            mock.try_tcp_connect(Sn::Sn0, 33650, &SERVER),
+            Err(BindError::PortInUse)
+        );
+    }
+}
+
+/// Tests writing until the entire buffer is sent
+mod write_all {
+    use super::*;
+    use w5500_hl::Error;
+
+    struct MockRegisters {
+        sr: VecDeque<SocketStatus>,
+        fsr: VecDeque<u16>,
+        ptr: u16,
+        sent: Vec<u8>,
+    }
+
+    impl Registers for MockRegisters {
+        type Error = Infallible;
+
+        fn sn_sr(&mut self, _socket: Sn) -> Result<Result<SocketStatus, u8>, Self::Error> {
+            Ok(Ok(if self.sr.len() > 1 {
+                self.sr.pop_front().unwrap()
+            } else {
+                self.sr[0]
+            }))
+        }
+
+        fn sn_tx_fsr(&mut self, _socket: Sn) -> Result<u16, Self::Error> {
+            Ok(self.fsr.pop_front().unwrap_or(0))
+        }
+
+        fn sn_tx_wr(&mut self, _socket: Sn) -> Result<u16, Self::Error> {
+            Ok(self.ptr)
+        }
+
+        fn set_sn_tx_wr(&mut self, _socket: Sn, ptr: u16) -> Result<(), Self::Error> {
+            self.ptr = ptr;
+            Ok(())
+        }
+
+        fn set_sn_tx_buf(&mut self, _socket: Sn, _ptr: u16, buf: &[u8]) -> Result<(), Self::Error> {
+            self.sent.extend_from_slice(buf);
+            Ok(())
+        }
+
+        fn set_sn_cr(&mut self, _socket: Sn, cmd: SocketCommand) -> Result<(), Self::Error> {
+            assert_eq!(cmd, SocketCommand::Send);
+            Ok(())
+        }
+
+        fn read(&mut self, _address: u16, _block: u8, _data: &mut [u8]) -> Result<(), Self::Error> {
+            unimplemented!()
+        }
+
+        fn write(&mut self, _address: u16, _block: u8, _data: &[u8]) -> Result<(), Self::Error> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn waits_for_free_space() {
+        let mut test = MockRegisters {
+            sr: VecDeque::from([SocketStatus::Established]),
+            fsr: VecDeque::from([2, 0, 0, 3]),
+            ptr: 0,
+            sent: Vec::new(),
+        };
+        assert_eq!(test.tcp_write_all(Sn::Sn0, &[1, 2, 3, 4, 5]), Ok(()));
+        assert_eq!(test.sent, [1, 2, 3, 4, 5]);
+        assert_eq!(test.ptr, 5);
+    }
+
+    #[test]
+    fn disconnected() {
+        let mut test = MockRegisters {
+            // status is read twice per write, once more after each write
+            sr: VecDeque::from([
+                SocketStatus::Established,
+                SocketStatus::Established,
+                SocketStatus::Established,
+                SocketStatus::Established,
+                SocketStatus::Closed,
+            ]),
+            fsr: VecDeque::from([2]),
+            ptr: 0,
+            sent: Vec::new(),
+        };
+        assert_eq!(
+            test.tcp_write_all(Sn::Sn0, &[1, 2, 3, 4, 5]),
+            Err(Error::Disconnected)
+        );
+        assert_eq!(test.sent, [1, 2]);
+    }
+}
@@ -63,6 +63,19 @@ mod socket_status_debug_assert {
             .ok();
     }
 
+    #[test]
+    #[should_panic]
+    fn udp_send_to_vectored() {
+        let mut test = MockRegisters {};
+        let buf: [u8; 1] = [0];
+        test.udp_send_to_vectored(
+            Sn::Sn0,
+            &[&buf, &buf],
+            &SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0),
+        )
+        .ok();
+    }
+
     #[test]
     #[should_panic]
     fn udp_send() {
@@ -119,6 +132,13 @@ mod udp_would_block_header {
             Err(Error::WouldBlock)
         );
     }
+
+    #[test]
+    fn udp_recv() {
+        let mut mock = MockRegisters {};
+        let mut buf: [u8; 1] = [0];
+        assert_eq!(mock.udp_recv(Sn::Sn0, &mut buf), Err(Error::WouldBlock));
+    }
 }
 
 /// Tests the udp_bind method
@@ -190,3 +210,37 @@ mod udp_bind {
         mock.udp_bind(TEST_SOCKET, TEST_PORT).unwrap();
     }
 }
+
+/// Tests the try_udp_bind method returns an error for a port in use
+mod try_udp_bind {
+    use super::*;
+    use w5500_hl::BindError;
+
+    struct MockRegisters {}
+
+    impl Registers for MockRegisters {
+        type Error = Infallible;
+
+        fn sn_port(&mut self, _socket: Sn) -> Result<u16, Self::Error> {
+            Ok(8080)
+        }
+
+        fn sn_sr(&mut self, _socket: Sn) -> Result<Result<SocketStatus, u8>, Self::Error> {
+            Ok(Ok(SocketStatus::Udp))
+        }
+
+        fn read(&mut self, _address: u16, _block: u8, _data: &mut [u8]) -> Result<(), Self::Error> {
+            unimplemented!()
+        }
+
+        fn write(&mut self, _address: u16, _block: u8, _data: &[u8]) -> Result<(), Self::Error> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn port_in_use() {
+        let mut mock = MockRegisters {};
+        assert_eq!(mock.try_udp_bind(Sn::Sn0, 8080), Err(BindError::PortInUse));
+    }
+}
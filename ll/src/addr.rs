@@ -1,5 +1,6 @@
 /// W5500 common register addresses.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(u16)]
 #[allow(non_camel_case_types)]
 pub enum Reg {
@@ -153,6 +154,9 @@ impl TryFrom<u16> for Reg {
             x if x == Self::PMRU0 as u16 => Ok(Self::PMRU0),
             x if x == Self::PMRU1 as u16 => Ok(Self::PMRU1),
             x if x == Self::UIPR0 as u16 => Ok(Self::UIPR0),
+            x if x == Self::UIPR1 as u16 => Ok(Self::UIPR1),
+            x if x == Self::UIPR2 as u16 => Ok(Self::UIPR2),
+            x if x == Self::UIPR3 as u16 => Ok(Self::UIPR3),
             x if x == Self::UPORTR0 as u16 => Ok(Self::UPORTR0),
             x if x == Self::UPORTR1 as u16 => Ok(Self::UPORTR1),
             x if x == Self::PHYCFGR as u16 => Ok(Self::PHYCFGR),
@@ -202,6 +206,7 @@ impl Reg {
 
 /// W5500 socket register addresses.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(u16)]
 #[allow(non_camel_case_types)]
 pub enum SnReg {
@@ -377,6 +382,8 @@ impl SnReg {
                 | Self::TX_RD1
                 | Self::RX_RSR0
                 | Self::RX_RSR1
+                | Self::RX_WR0
+                | Self::RX_WR1
         )
     }
 }
@@ -1,11 +1,20 @@
 //! Asynchronous W5500 traits.
 use crate::{
-    BufferSize, Eui48Addr, Interrupt, Ipv4Addr, Mode, PhyCfg, Reg, RxPtrs, Sn, SnReg, SocketAddrV4,
-    SocketCommand, SocketInterrupt, SocketInterruptMask, SocketMode, SocketStatus, TxPtrs,
-    COMMON_BLOCK_OFFSET,
+    net, open_socket_status, BufferSize, Eui48Addr, Interrupt, Ipv4Addr, LinkStatus, Mode,
+    NetConfig, PhyCfg, ProbeResult, Protocol, Reg, RegisterDump, RxPtrs, Sn, SnReg, SnRegisterDump,
+    SnStatusBlock, SocketAddrV4, SocketCommand, SocketInterrupt, SocketInterruptMask, SocketMode,
+    SocketStatus, StatusError, TxPtrs, COMMON_BLOCK_LEN, COMMON_BLOCK_OFFSET, PROBE_PATTERNS,
+    SOCKETS, SW_RESET_ATTEMPTS, VERSION,
 };
 
 /// Asynchronous register IO.
+///
+/// # Socket buffer pointers
+///
+/// The socket buffer methods take a free-running 16-bit pointer, the W5500
+/// maps it onto the socket buffer modulo the configured buffer size.
+/// An access that runs past the end of the buffer wraps around to the start
+/// of the buffer, pointers larger than the buffer size are expected.
 pub trait Registers {
     /// Register accessor error type.
     type Error;
@@ -109,6 +118,36 @@ pub trait Registers {
         Ok::<Ipv4Addr, Self::Error>(gar.into())
     }
 
+    /// Get the gateway IP address as raw bytes.
+    ///
+    /// This is the same as [`gar`](Registers::gar) without the
+    /// conversion, the bytes are in network order, as they appear in a packet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x01, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read_vec(vec![192, 168, 0, 1]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let gar: [u8; 4] = w5500.gar_bytes().await?;
+    /// assert_eq!(gar, [192, 168, 0, 1]);
+    /// # w5500.free().done(); Ok(()) }
+    /// ```
+    async fn gar_bytes(&mut self) -> Result<[u8; 4], Self::Error> {
+        let mut gar: [u8; 4] = [0; 4];
+        self.read(Reg::GAR0.addr(), COMMON_BLOCK_OFFSET, &mut gar)
+            .await?;
+        Ok::<[u8; 4], Self::Error>(gar)
+    }
+
     /// Set the gateway IP address.
     ///
     /// # Example
@@ -160,6 +199,36 @@ pub trait Registers {
         Ok::<Ipv4Addr, Self::Error>(subr.into())
     }
 
+    /// Get the subnet mask as raw bytes.
+    ///
+    /// This is the same as [`subr`](Registers::subr) without the
+    /// conversion, the bytes are in network order, as they appear in a packet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x05, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read_vec(vec![255, 255, 255, 0]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let subr: [u8; 4] = w5500.subr_bytes().await?;
+    /// assert_eq!(subr, [255, 255, 255, 0]);
+    /// # w5500.free().done(); Ok(()) }
+    /// ```
+    async fn subr_bytes(&mut self) -> Result<[u8; 4], Self::Error> {
+        let mut subr: [u8; 4] = [0; 4];
+        self.read(Reg::SUBR0.addr(), COMMON_BLOCK_OFFSET, &mut subr)
+            .await?;
+        Ok::<[u8; 4], Self::Error>(subr)
+    }
+
     /// Set the subnet mask.
     ///
     /// # Example
@@ -211,6 +280,36 @@ pub trait Registers {
         Ok::<Eui48Addr, Self::Error>(shar)
     }
 
+    /// Get the source hardware address as raw bytes.
+    ///
+    /// This is the same as [`shar`](Registers::shar) without the
+    /// conversion, the bytes are in network order, as they appear in a packet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x09, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read_vec(vec![0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let shar: [u8; 6] = w5500.shar_bytes().await?;
+    /// assert_eq!(shar, [0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC]);
+    /// # w5500.free().done(); Ok(()) }
+    /// ```
+    async fn shar_bytes(&mut self) -> Result<[u8; 6], Self::Error> {
+        let mut shar: [u8; 6] = [0; 6];
+        self.read(Reg::SHAR0.addr(), COMMON_BLOCK_OFFSET, &mut shar)
+            .await?;
+        Ok::<[u8; 6], Self::Error>(shar)
+    }
+
     /// Set the source hardware address.
     ///
     /// # Example
@@ -264,6 +363,36 @@ pub trait Registers {
         Ok::<Ipv4Addr, Self::Error>(sipr.into())
     }
 
+    /// Get the source (client) IP address as raw bytes.
+    ///
+    /// This is the same as [`sipr`](Registers::sipr) without the
+    /// conversion, the bytes are in network order, as they appear in a packet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x0F, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read_vec(vec![192, 168, 0, 150]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let sipr: [u8; 4] = w5500.sipr_bytes().await?;
+    /// assert_eq!(sipr, [192, 168, 0, 150]);
+    /// # w5500.free().done(); Ok(()) }
+    /// ```
+    async fn sipr_bytes(&mut self) -> Result<[u8; 4], Self::Error> {
+        let mut sipr: [u8; 4] = [0; 4];
+        self.read(Reg::SIPR0.addr(), COMMON_BLOCK_OFFSET, &mut sipr)
+            .await?;
+        Ok::<[u8; 4], Self::Error>(sipr)
+    }
+
     /// Set the source (client) IP address.
     ///
     /// # Example
@@ -288,6 +417,88 @@ pub trait Registers {
             .await
     }
 
+    /// Get the gateway, subnet mask, source hardware address, and source IP
+    /// address.
+    ///
+    /// This is equivalent to [`Registers::gar`], [`Registers::subr`],
+    /// [`Registers::shar`], and [`Registers::sipr`] in a single read
+    /// transaction.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x01, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read_vec(vec![
+    /// #       192, 168, 0, 1, 255, 255, 255, 0, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 192, 168, 0, 150,
+    /// #   ]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{
+    ///     aio::Registers,
+    ///     eh1::vdm::W5500,
+    ///     net::{Eui48Addr, Ipv4Addr},
+    ///     NetConfig,
+    /// };
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let config: NetConfig = w5500.net_config().await?;
+    /// assert_eq!(config.ip, Ipv4Addr::new(192, 168, 0, 150));
+    /// # w5500.free().done(); Ok(()) }
+    /// ```
+    async fn net_config(&mut self) -> Result<NetConfig, Self::Error> {
+        let mut buf: [u8; NetConfig::LEN] = [0; NetConfig::LEN];
+        self.read(Reg::GAR0.addr(), COMMON_BLOCK_OFFSET, &mut buf)
+            .await?;
+        Ok::<NetConfig, Self::Error>(NetConfig::from_bytes(buf))
+    }
+
+    /// Set the gateway, subnet mask, source hardware address, and source IP
+    /// address.
+    ///
+    /// This is equivalent to [`Registers::set_gar`], [`Registers::set_subr`],
+    /// [`Registers::set_shar`], and [`Registers::set_sipr`] in a single write
+    /// transaction.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x01, 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![
+    /// #       192, 168, 0, 1, 255, 255, 255, 0, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 192, 168, 0, 150,
+    /// #   ]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{
+    ///     aio::Registers,
+    ///     eh1::vdm::W5500,
+    ///     net::{Eui48Addr, Ipv4Addr},
+    ///     NetConfig,
+    /// };
+    ///
+    /// let config = NetConfig {
+    ///     gateway: Ipv4Addr::new(192, 168, 0, 1),
+    ///     subnet_mask: Ipv4Addr::new(255, 255, 255, 0),
+    ///     mac: Eui48Addr::new(0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC),
+    ///     ip: Ipv4Addr::new(192, 168, 0, 150),
+    /// };
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// w5500.set_net_config(&config).await?;
+    /// # w5500.free().done(); Ok(()) }
+    /// ```
+    async fn set_net_config(&mut self, config: &NetConfig) -> Result<(), Self::Error> {
+        self.write(Reg::GAR0.addr(), COMMON_BLOCK_OFFSET, &config.to_bytes())
+            .await
+    }
+
     /// Get the interrupt low level time.
     ///
     /// INTLEVEL configures the assert wait time (I<sub>AWT</sub>).
@@ -521,6 +732,57 @@ pub trait Registers {
         Ok::<u8, Self::Error>(reg[0])
     }
 
+    /// Get and clear the interrupts of all sockets with a bit set in
+    /// [`sir`].
+    ///
+    /// The returned array is indexed by socket number, sockets without a
+    /// pending interrupt are `None`.
+    ///
+    /// This is a convenience method that calls [`sir`], and then
+    /// [`clear_sn_ir_all`] for each socket with a pending interrupt.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x17, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0x02),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x02, 0x28]),
+    /// #   ehm::eh1::spi::Transaction::read(SocketInterrupt::RECV_MASK),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x02, 0x28 | 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(SocketInterrupt::RECV_MASK),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500, Sn, SocketInterrupt};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let pending = w5500.pending_socket_interrupts().await?;
+    /// assert!(pending[usize::from(Sn::Sn1)].unwrap().recv_raised());
+    /// # w5500.free().done(); Ok(()) }
+    /// ```
+    ///
+    /// [`sir`]: Registers::sir
+    /// [`clear_sn_ir_all`]: Registers::clear_sn_ir_all
+    async fn pending_socket_interrupts(
+        &mut self,
+    ) -> Result<[Option<SocketInterrupt>; SOCKETS.len()], Self::Error> {
+        let sir: u8 = self.sir().await?;
+        let mut ret: [Option<SocketInterrupt>; SOCKETS.len()] = [None; SOCKETS.len()];
+        for sn in SOCKETS {
+            if sir & sn.bitmask() != 0 {
+                ret[usize::from(sn)] = Some(self.clear_sn_ir_all(sn).await?);
+            }
+        }
+        Ok(ret)
+    }
+
     /// Get the socket interrupt mask.
     ///
     /// Each bit of SIMR corresponds to each bit of [`Registers::sir`].
@@ -1030,17 +1292,289 @@ pub trait Registers {
     /// use w5500_ll::{aio::Registers, eh1::vdm::W5500};
     ///
     /// let mut w5500 = W5500::new(spi);
-    /// let uportr = w5500.uportr().await?;
+    /// let uportr = w5500.uportr().await?;
+    /// # w5500.free().done(); Ok(()) }
+    /// ```
+    async fn uportr(&mut self) -> Result<u16, Self::Error> {
+        let mut buf: [u8; 2] = [0; 2];
+        self.read(Reg::UPORTR0.addr(), COMMON_BLOCK_OFFSET, &mut buf)
+            .await?;
+        Ok::<u16, Self::Error>(u16::from_be_bytes(buf))
+    }
+
+    /// Get the PHY configuration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x2E, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0b10111000),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500, PhyCfg};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let phy_cfg: PhyCfg = w5500.phycfgr().await?;
+    /// assert_eq!(phy_cfg, PhyCfg::default());
+    /// # w5500.free().done(); Ok(()) }
+    /// ```
+    async fn phycfgr(&mut self) -> Result<PhyCfg, Self::Error> {
+        let mut reg: [u8; 1] = [0];
+        self.read(Reg::PHYCFGR.addr(), COMMON_BLOCK_OFFSET, &mut reg)
+            .await?;
+        Ok::<PhyCfg, Self::Error>(PhyCfg::from(reg[0]))
+    }
+
+    /// Set the PHY configuration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x2E, 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(0b11111000),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500, OperationMode, PhyCfg};
+    ///
+    /// const PHY_CFG: PhyCfg = PhyCfg::DEFAULT.set_opmdc(OperationMode::Auto);
+    /// let mut w5500 = W5500::new(spi);
+    /// w5500.set_phycfgr(PHY_CFG).await?;
+    /// # w5500.free().done(); Ok(()) }
+    /// ```
+    async fn set_phycfgr(&mut self, phycfg: PhyCfg) -> Result<(), Self::Error> {
+        self.write(Reg::PHYCFGR.addr(), COMMON_BLOCK_OFFSET, &[phycfg.into()])
+            .await
+    }
+
+    /// Returns `true` if the ethernet link is up.
+    ///
+    /// This reads the [PHY configuration] register and checks the
+    /// [link status](PhyCfg::lnk).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x2E, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0b10111111),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// assert!(w5500.link_up().await?);
+    /// # w5500.free().done(); Ok(()) }
+    /// ```
+    ///
+    /// [PHY configuration]: Registers::phycfgr
+    async fn link_up(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.phycfgr().await?.lnk() == LinkStatus::Up)
+    }
+
+    /// Poll the link status until the ethernet link is up.
+    ///
+    /// The link status is read once, then up to `retries` more times.
+    /// Returns `true` if the link came up, or `false` if the retry budget was
+    /// exhausted.
+    ///
+    /// Auto-negotiation may take over a second to complete after a reset or
+    /// after the cable is connected.
+    /// There is no delay between reads, the number of retries required
+    /// depends on the SPI clock.
+    /// Use [`link_up`] with a delay between calls if you need a bounded wait
+    /// time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x2E, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0b10111000),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x2E, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0b10111111),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// assert!(w5500.wait_for_link(10_000).await?);
+    /// # w5500.free().done(); Ok(()) }
+    /// ```
+    ///
+    /// [`link_up`]: Registers::link_up
+    async fn wait_for_link(&mut self, retries: u32) -> Result<bool, Self::Error> {
+        for _ in 0..=retries {
+            if self.link_up().await? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Get the version.
+    ///
+    /// The value returned is always `0x04`.
+    ///
+    /// This register is extremely useful as a sanity check to ensure SPI
+    /// communications are working with the W5500.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x39, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0x04),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let version = w5500.version().await?;
+    /// assert_eq!(version, 0x04);
+    /// # w5500.free().done(); Ok(()) }
+    /// ```
+    async fn version(&mut self) -> Result<u8, Self::Error> {
+        let mut reg: [u8; 1] = [0];
+        self.read(Reg::VERSIONR.addr(), COMMON_BLOCK_OFFSET, &mut reg)
+            .await?;
+        Ok::<u8, Self::Error>(reg[0])
+    }
+
+    /// Check that the W5500 is present and responding.
+    ///
+    /// This reads the [version] register, then writes test patterns to the
+    /// [PPP LCP magic number] register and reads them back.
+    /// The original value of the magic number register is restored afterwards.
+    ///
+    /// This should be called once during bring-up, it is not useful when
+    /// PPPoE is active.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x39, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0x04),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x1D, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0x00),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x1D, 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(0x55),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x1D, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0x55),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x1D, 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(0xAA),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x1D, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0xAA),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x1D, 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(0x00),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500, ProbeResult};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let probe: ProbeResult = w5500.probe().await?;
+    /// assert!(probe.is_ok());
+    /// # w5500.free().done(); Ok(()) }
+    /// ```
+    ///
+    /// [version]: Registers::version
+    /// [PPP LCP magic number]: Registers::pmagic
+    async fn probe(&mut self) -> Result<ProbeResult, Self::Error> {
+        let version: u8 = self.version().await?;
+        let original: u8 = self.pmagic().await?;
+        let mut scratch: bool = true;
+        for pattern in PROBE_PATTERNS {
+            self.set_pmagic(pattern).await?;
+            scratch &= self.pmagic().await? == pattern;
+        }
+        self.set_pmagic(original).await?;
+        Ok(ProbeResult { version, scratch })
+    }
+
+    /// Check that the W5500 is alive.
+    ///
+    /// Returns `true` if the [version] register reads [`VERSION`].
+    ///
+    /// All registers read back as `0x00` when the W5500 is held in reset, this
+    /// is intended to be called periodically as a watchdog.
+    /// If the W5500 is not alive, wait for it to leave reset, then reset it
+    /// with [`sw_reset`](Registers::sw_reset) and reinitialize it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x39, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0x04),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x39, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0x00),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// assert!(w5500.is_alive().await?);
+    /// // held in reset
+    /// assert!(!w5500.is_alive().await?);
     /// # w5500.free().done(); Ok(()) }
     /// ```
-    async fn uportr(&mut self) -> Result<u16, Self::Error> {
-        let mut buf: [u8; 2] = [0; 2];
-        self.read(Reg::UPORTR0.addr(), COMMON_BLOCK_OFFSET, &mut buf)
-            .await?;
-        Ok::<u16, Self::Error>(u16::from_be_bytes(buf))
+    ///
+    /// [version]: Registers::version
+    async fn is_alive(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.version().await? == VERSION)
     }
 
-    /// Get the PHY configuration.
+    /// Reset the W5500 with the software reset bit, and wait for the W5500 to
+    /// respond.
+    ///
+    /// This sets [`Mode::RST_MASK`] in the mode register, then polls the
+    /// [version] register until it reads [`VERSION`].
+    ///
+    /// This is an alternative to a reset with the reset pin for boards that
+    /// do not have the reset pin connected.
+    ///
+    /// The innermost `Result<(), u8>` is [`Err`] with the last value read
+    /// from the version register if the W5500 did not respond after
+    /// 255 attempts.
     ///
     /// # Example
     ///
@@ -1049,25 +1583,43 @@ pub trait Registers {
     /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
     /// # let spi = ehm::eh1::spi::Mock::new(&[
     /// #   ehm::eh1::spi::Transaction::transaction_start(),
-    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x2E, 0x00]),
-    /// #   ehm::eh1::spi::Transaction::read(0b10111000),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x00, 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(w5500_ll::Mode::RST_MASK),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x39, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0x00),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x39, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0x04),
     /// #   ehm::eh1::spi::Transaction::transaction_end(),
     /// # ]);
-    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500, PhyCfg};
+    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500};
     ///
     /// let mut w5500 = W5500::new(spi);
-    /// let phy_cfg: PhyCfg = w5500.phycfgr().await?;
-    /// assert_eq!(phy_cfg, PhyCfg::default());
+    /// w5500.sw_reset().await?.expect("W5500 did not respond after reset");
     /// # w5500.free().done(); Ok(()) }
     /// ```
-    async fn phycfgr(&mut self) -> Result<PhyCfg, Self::Error> {
-        let mut reg: [u8; 1] = [0];
-        self.read(Reg::PHYCFGR.addr(), COMMON_BLOCK_OFFSET, &mut reg)
-            .await?;
-        Ok::<PhyCfg, Self::Error>(PhyCfg::from(reg[0]))
+    ///
+    /// [version]: Registers::version
+    /// [`Err`]: https://doc.rust-lang.org/core/result/enum.Result.html#variant.Err
+    async fn sw_reset(&mut self) -> Result<Result<(), u8>, Self::Error> {
+        self.set_mr(Mode::DEFAULT.rst()).await?;
+        let mut version: u8 = 0;
+        for _ in 0..SW_RESET_ATTEMPTS {
+            version = self.version().await?;
+            if version == VERSION {
+                return Ok(Ok(()));
+            }
+        }
+        Ok(Err(version))
     }
 
-    /// Set the PHY configuration.
+    /// Get the socket status and buffer pointer registers.
+    ///
+    /// This is equivalent to [`Registers::sn_status_block`],
+    /// [`Registers::sn_tx_ptrs`], and [`Registers::sn_rx_ptrs`].
     ///
     /// # Example
     ///
@@ -1076,52 +1628,73 @@ pub trait Registers {
     /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
     /// # let spi = ehm::eh1::spi::Mock::new(&[
     /// #   ehm::eh1::spi::Transaction::transaction_start(),
-    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x2E, 0x04]),
-    /// #   ehm::eh1::spi::Transaction::write(0b11111000),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x00, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read_vec(vec![0x02, 0x00, 0x00, 0x22]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x20, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read_vec(vec![0x08, 0x00, 0x00, 0x00, 0x00, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x26, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read_vec(vec![0x00, 0x00, 0x00, 0x00]),
     /// #   ehm::eh1::spi::Transaction::transaction_end(),
     /// # ]);
-    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500, OperationMode, PhyCfg};
+    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500, Sn, SnRegisterDump, SocketStatus};
     ///
-    /// const PHY_CFG: PhyCfg = PhyCfg::DEFAULT.set_opmdc(OperationMode::Auto);
     /// let mut w5500 = W5500::new(spi);
-    /// w5500.set_phycfgr(PHY_CFG).await?;
+    /// let dump: SnRegisterDump = w5500.sn_register_dump(Sn::Sn0).await?;
+    /// assert_eq!(dump.status.sr, Ok(SocketStatus::Udp));
+    /// assert_eq!(dump.tx.fsr, 2048);
     /// # w5500.free().done(); Ok(()) }
     /// ```
-    async fn set_phycfgr(&mut self, phycfg: PhyCfg) -> Result<(), Self::Error> {
-        self.write(Reg::PHYCFGR.addr(), COMMON_BLOCK_OFFSET, &[phycfg.into()])
-            .await
+    async fn sn_register_dump(&mut self, sn: Sn) -> Result<SnRegisterDump, Self::Error> {
+        Ok(SnRegisterDump {
+            status: self.sn_status_block(sn).await?,
+            tx: self.sn_tx_ptrs(sn).await?,
+            rx: self.sn_rx_ptrs(sn).await?,
+        })
     }
 
-    /// Get the version.
+    /// Get a snapshot of the common registers and the socket status
+    /// registers.
     ///
-    /// The value returned is always `0x04`.
+    /// The common register block is read in a single transaction, followed
+    /// by [`Registers::sn_register_dump`] for each socket.
+    /// The registers are not read atomically, values may change between
+    /// transactions.
     ///
-    /// This register is extremely useful as a sanity check to ensure SPI
-    /// communications are working with the W5500.
+    /// This is intended for debug logging and issue reports.
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
     /// # #[tokio::main(flavor = "current_thread")]
     /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
-    /// # let spi = ehm::eh1::spi::Mock::new(&[
-    /// #   ehm::eh1::spi::Transaction::transaction_start(),
-    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x39, 0x00]),
-    /// #   ehm::eh1::spi::Transaction::read(0x04),
-    /// #   ehm::eh1::spi::Transaction::transaction_end(),
-    /// # ]);
-    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500};
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_ll::{aio::Registers, RegisterDump};
     ///
-    /// let mut w5500 = W5500::new(spi);
-    /// let version = w5500.version().await?;
-    /// assert_eq!(version, 0x04);
-    /// # w5500.free().done(); Ok(()) }
+    /// let dump: RegisterDump = w5500.register_dump().await?;
+    /// println!("{dump:?}");
+    /// # Ok(()) }
     /// ```
-    async fn version(&mut self) -> Result<u8, Self::Error> {
-        let mut reg: [u8; 1] = [0];
-        self.read(Reg::VERSIONR.addr(), COMMON_BLOCK_OFFSET, &mut reg)
+    async fn register_dump(&mut self) -> Result<RegisterDump, Self::Error> {
+        let mut common: [u8; COMMON_BLOCK_LEN] = [0; COMMON_BLOCK_LEN];
+        self.read(Reg::MR.addr(), COMMON_BLOCK_OFFSET, &mut common)
             .await?;
-        Ok::<u8, Self::Error>(reg[0])
+        Ok(RegisterDump {
+            common,
+            sn: [
+                self.sn_register_dump(Sn::Sn0).await?,
+                self.sn_register_dump(Sn::Sn1).await?,
+                self.sn_register_dump(Sn::Sn2).await?,
+                self.sn_register_dump(Sn::Sn3).await?,
+                self.sn_register_dump(Sn::Sn4).await?,
+                self.sn_register_dump(Sn::Sn5).await?,
+                self.sn_register_dump(Sn::Sn6).await?,
+                self.sn_register_dump(Sn::Sn7).await?,
+            ],
+        })
     }
 
     /// Get the socket mode.
@@ -1236,6 +1809,180 @@ pub trait Registers {
             .await
     }
 
+    /// Issue the [`SendKeep`] socket command to send a TCP keep-alive packet.
+    ///
+    /// The W5500 ignores this command unless the socket is
+    /// [`Established`](SocketStatus::Established).
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The socket must be an [`Established`](SocketStatus::Established) TCP socket.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x03, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read(0x17),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x01, 0x08 | 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(0x22),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500, Sn};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// w5500.send_keep(Sn::Sn0).await?;
+    /// # w5500.free().done(); Ok(()) }
+    /// ```
+    ///
+    /// [`SendKeep`]: SocketCommand::SendKeep
+    async fn send_keep(&mut self, sn: Sn) -> Result<(), Self::Error> {
+        debug_assert_eq!(self.sn_sr(sn).await?, Ok(SocketStatus::Established));
+        self.set_sn_cr(sn, SocketCommand::SendKeep).await
+    }
+
+    /// Issue the [`SendMac`] socket command to send UDP data without an ARP
+    /// request, using the hardware address in [`sn_dhar`](Self::sn_dhar).
+    ///
+    /// The W5500 ignores this command unless the socket is in
+    /// [`Udp`](SocketStatus::Udp) mode.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The socket must be a [`Udp`](SocketStatus::Udp) socket.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x03, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read(0x22),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x01, 0x08 | 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(0x21),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500, Sn};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// w5500.send_mac(Sn::Sn0).await?;
+    /// # w5500.free().done(); Ok(()) }
+    /// ```
+    ///
+    /// [`SendMac`]: SocketCommand::SendMac
+    async fn send_mac(&mut self, sn: Sn) -> Result<(), Self::Error> {
+        debug_assert_eq!(self.sn_sr(sn).await?, Ok(SocketStatus::Udp));
+        self.set_sn_cr(sn, SocketCommand::SendMac).await
+    }
+
+    /// Close the socket, then open it with the given mode and local port,
+    /// waiting for the socket to open.
+    ///
+    /// This performs the following sequence:
+    ///
+    /// 1. Issue the [`Close`](SocketCommand::Close) command and wait for the
+    ///    socket to be [`Closed`](SocketStatus::Closed).
+    /// 2. Set the socket port and mode.
+    /// 3. Issue the [`Open`](SocketCommand::Open) command and wait for the
+    ///    socket status for the protocol in `mode`, [`Init`] for TCP,
+    ///    [`Udp`] for UDP, and [`Macraw`] for MACRAW.
+    ///
+    /// The resulting socket status is returned.
+    /// If the protocol in `mode` is closed or invalid, or MACRAW on a socket
+    /// other than [`Sn::Sn0`], the W5500 does not open the socket, the status
+    /// is read once without waiting.
+    ///
+    /// Registers that must be set before opening the socket, such as the
+    /// multicast destination, must be set before calling this method.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x01, 0x08 | 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(0x10),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x03, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read(0x00),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x04, 0x08 | 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 68]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x00, 0x08 | 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(0x02),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x01, 0x08 | 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(0x01),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x03, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read(0x22),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500, Protocol, Sn, SocketMode, SocketStatus};
+    ///
+    /// const MODE: SocketMode = SocketMode::DEFAULT.set_protocol(Protocol::Udp);
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// assert_eq!(
+    ///     w5500.open_socket(Sn::Sn0, MODE, 68).await?,
+    ///     Ok(SocketStatus::Udp)
+    /// );
+    /// # w5500.free().done(); Ok(()) }
+    /// ```
+    ///
+    /// [`Init`]: SocketStatus::Init
+    /// [`Udp`]: SocketStatus::Udp
+    /// [`Macraw`]: SocketStatus::Macraw
+    async fn open_socket(
+        &mut self,
+        sn: Sn,
+        mode: SocketMode,
+        port: u16,
+    ) -> Result<Result<SocketStatus, u8>, Self::Error> {
+        self.set_sn_cr(sn, SocketCommand::Close).await?;
+        // This will not hang, the socket status will always change to closed
+        // after a close command.
+        // (unless you do something silly like holding the W5500 in reset)
+        let closed: Result<SocketStatus, u8> = open_socket_status(
+            self.wait_for_status(sn, SocketStatus::Closed, u32::MAX)
+                .await,
+            SocketStatus::Closed,
+        )?;
+        if closed != Ok(SocketStatus::Closed) {
+            return Ok(closed);
+        }
+        self.set_sn_port(sn, port).await?;
+        self.set_sn_mr(sn, mode).await?;
+        self.set_sn_cr(sn, SocketCommand::Open).await?;
+        let status: SocketStatus = match mode.protocol() {
+            Ok(Protocol::Tcp) => SocketStatus::Init,
+            Ok(Protocol::Udp) => SocketStatus::Udp,
+            Ok(Protocol::Macraw) if sn == Sn::Sn0 => SocketStatus::Macraw,
+            Ok(Protocol::Macraw) | Ok(Protocol::Closed) | Err(_) => return self.sn_sr(sn).await,
+        };
+        // This will not hang, the socket status will always change to the
+        // status for the protocol after a open command.
+        // (unless you do something silly like holding the W5500 in reset)
+        open_socket_status(self.wait_for_status(sn, status, u32::MAX).await, status)
+    }
+
     /// Get the socket interrupt status.
     ///
     /// # Example
@@ -1315,6 +2062,47 @@ pub trait Registers {
         self.write(SnReg::IR.addr(), sn.block(), &[sn_ir]).await
     }
 
+    /// Clear all raised socket interrupts.
+    ///
+    /// This reads [`sn_ir`], and writes the raised interrupts back to
+    /// [`set_sn_ir`] to clear them.
+    /// The write is skipped if no interrupts are raised.
+    ///
+    /// Returns the interrupts that were cleared.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x02, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read(SocketInterrupt::SENDOK_MASK),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x02, 0x08 | 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(SocketInterrupt::SENDOK_MASK),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500, Sn, SocketInterrupt};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let cleared: SocketInterrupt = w5500.clear_sn_ir_all(Sn::Sn0).await?;
+    /// assert!(cleared.sendok_raised());
+    /// # w5500.free().done(); Ok(()) }
+    /// ```
+    ///
+    /// [`sn_ir`]: Registers::sn_ir
+    /// [`set_sn_ir`]: Registers::set_sn_ir
+    async fn clear_sn_ir_all(&mut self, sn: Sn) -> Result<SocketInterrupt, Self::Error> {
+        let sn_ir: SocketInterrupt = self.sn_ir(sn).await?;
+        if sn_ir.any_raised() {
+            self.set_sn_ir(sn, sn_ir.into()).await?;
+        }
+        Ok(sn_ir)
+    }
+
     /// Get the socket status.
     ///
     /// **Note:** This method returns a nested [`core::result::Result`].
@@ -1357,6 +2145,103 @@ pub trait Registers {
         Ok::<Result<SocketStatus, u8>, Self::Error>(SocketStatus::try_from(reg[0]))
     }
 
+    /// Poll the socket status until it reaches `target`.
+    ///
+    /// The socket status is read up to `max_polls` times, and at least once.
+    ///
+    /// Returns [`StatusError::Timeout`] with the last raw value read from the
+    /// [socket status] register if the socket did not reach `target` within
+    /// `max_polls` reads.
+    ///
+    /// The status can pass through intermediate values after a command, for
+    /// example [`SocketStatus::SynSent`] after a connect command.
+    /// There is no delay between reads, the number of polls required depends
+    /// on the SPI clock.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), w5500_ll::StatusError<eh1::spi::ErrorKind>> {
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x03, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read(0x00),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x03, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read(0x22),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x03, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read(0x00),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500, Sn, SocketStatus, StatusError};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// w5500.wait_for_status(Sn::Sn0, SocketStatus::Udp, 10).await?;
+    /// assert_eq!(
+    ///     w5500.wait_for_status(Sn::Sn0, SocketStatus::Init, 1).await,
+    ///     Err(StatusError::Timeout(0x00))
+    /// );
+    /// # w5500.free().done(); Ok(()) }
+    /// ```
+    ///
+    /// [socket status]: Registers::sn_sr
+    async fn wait_for_status(
+        &mut self,
+        sn: Sn,
+        target: SocketStatus,
+        max_polls: u32,
+    ) -> Result<(), StatusError<Self::Error>> {
+        let mut sr: u8 = 0;
+        for _ in 0..max_polls.max(1) {
+            match self.sn_sr(sn).await? {
+                Ok(status) if status == target => return Ok(()),
+                Ok(status) => sr = status.into(),
+                Err(raw) => sr = raw,
+            }
+        }
+        Err(StatusError::Timeout(sr))
+    }
+
+    /// Get the socket mode, command, interrupt, and status registers.
+    ///
+    /// This is equivalent to [`Registers::sn_mr`], [`Registers::sn_cr`],
+    /// [`Registers::sn_ir`], and [`Registers::sn_sr`] in a single read
+    /// transaction.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x00, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read_vec(vec![0x02, 0x00, 0x04, 0x22]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500, Sn, SnStatusBlock, SocketStatus};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let status: SnStatusBlock = w5500.sn_status_block(Sn::Sn0).await?;
+    /// assert_eq!(status.sr, Ok(SocketStatus::Udp));
+    /// assert!(status.ir.recv_raised());
+    /// # w5500.free().done(); Ok(()) }
+    /// ```
+    async fn sn_status_block(&mut self, sn: Sn) -> Result<SnStatusBlock, Self::Error> {
+        let mut buf: [u8; 4] = [0; 4];
+        self.read(SnReg::MR.addr(), sn.block(), &mut buf).await?;
+        Ok(SnStatusBlock {
+            mr: SocketMode::from(buf[0]),
+            cr: buf[1],
+            ir: SocketInterrupt::from(buf[2]),
+            sr: SocketStatus::try_from(buf[3]),
+        })
+    }
+
     /// Get the socket source port.
     ///
     /// This is only valid in TCP/UDP mode.
@@ -1633,10 +2518,7 @@ pub trait Registers {
     async fn sn_dest(&mut self, sn: Sn) -> Result<SocketAddrV4, Self::Error> {
         let mut buf: [u8; 6] = [0; 6];
         self.read(SnReg::DIPR0.addr(), sn.block(), &mut buf).await?;
-        Ok::<SocketAddrV4, Self::Error>(SocketAddrV4::new(
-            Ipv4Addr::new(buf[0], buf[1], buf[2], buf[3]),
-            u16::from_be_bytes([buf[4], buf[5]]),
-        ))
+        Ok::<SocketAddrV4, Self::Error>(net::socket_addr_from_bytes(buf))
     }
 
     /// Set the socket destination IPv4 and port.
@@ -1668,15 +2550,12 @@ pub trait Registers {
     /// # w5500.free().done(); Ok(()) }
     /// ```
     async fn set_sn_dest(&mut self, sn: Sn, addr: &SocketAddrV4) -> Result<(), Self::Error> {
-        let buf: [u8; 6] = [
-            addr.ip().octets()[0],
-            addr.ip().octets()[1],
-            addr.ip().octets()[2],
-            addr.ip().octets()[3],
-            (addr.port() >> 8) as u8,
-            addr.port() as u8,
-        ];
-        self.write(SnReg::DIPR0.addr(), sn.block(), &buf).await
+        self.write(
+            SnReg::DIPR0.addr(),
+            sn.block(),
+            &net::socket_addr_to_bytes(addr),
+        )
+        .await
     }
 
     /// Get the socket maximum segment size.
@@ -2297,6 +3176,41 @@ pub trait Registers {
         })
     }
 
+    /// Get the socket RX read and write pointers.
+    ///
+    /// This is equivalent to [`Registers::sn_rx_rd`] and
+    /// [`Registers::sn_rx_wr`] in a single read transaction.
+    /// The received size is the difference between the pointers,
+    /// `wr.wrapping_sub(rd)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), eh1::spi::ErrorKind> {
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x28, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read_vec(vec![0xFF, 0xF0, 0x00, 0x10]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{aio::Registers, eh1::vdm::W5500, Sn};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let (rd, wr): (u16, u16) = w5500.sn_rx_rd_wr(Sn::Sn0).await?;
+    /// assert_eq!(wr.wrapping_sub(rd), 32);
+    /// # w5500.free().done(); Ok(()) }
+    /// ```
+    async fn sn_rx_rd_wr(&mut self, sn: Sn) -> Result<(u16, u16), Self::Error> {
+        let mut buf: [u8; 4] = [0; 4];
+        self.read(SnReg::RX_RD0.addr(), sn.block(), &mut buf)
+            .await?;
+        Ok((
+            u16::from_be_bytes(buf[..2].try_into().unwrap()),
+            u16::from_be_bytes(buf[2..].try_into().unwrap()),
+        ))
+    }
+
     /// Get the socket interrupt mask.
     ///
     /// # Example
@@ -2476,6 +3390,9 @@ pub trait Registers {
 
     /// Write the socket TX buffer.
     ///
+    /// `ptr` is a free-running pointer, see
+    /// [socket buffer pointers](Registers#socket-buffer-pointers).
+    ///
     /// # Example
     ///
     /// ```
@@ -2538,6 +3455,9 @@ pub trait Registers {
 
     /// Read the socket TX buffer.
     ///
+    /// `ptr` is a free-running pointer, see
+    /// [socket buffer pointers](Registers#socket-buffer-pointers).
+    ///
     /// This method is typically unused; there are very few use cases that
     /// require reading the TX buffer.
     ///
@@ -2565,6 +3485,9 @@ pub trait Registers {
 
     /// Read the socket RX buffer.
     ///
+    /// `ptr` is a free-running pointer, see
+    /// [socket buffer pointers](Registers#socket-buffer-pointers).
+    ///
     /// # Example
     ///
     /// ```
@@ -2623,6 +3546,9 @@ pub trait Registers {
 
     /// Write the socket RX buffer.
     ///
+    /// `ptr` is a free-running pointer, see
+    /// [socket buffer pointers](Registers#socket-buffer-pointers).
+    ///
     /// This method is typically unused; there are very few use cases that
     /// require writing the RX buffer.
     ///
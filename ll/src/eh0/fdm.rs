@@ -2,8 +2,11 @@
 //! [`embedded-hal`] blocking SPI trait.
 //!
 //! This uses the W5500 fixed data length mode (FDM).
-//! In FSM mode the SPI chip select pin is always tied low, and it is not
+//! In FDM mode the SPI chip select pin is always tied low, and it is not
 //! possible to share the bus with other devices.
+//! The W5500 frames each access by counting bytes instead of using chip
+//! select, transfers are split into 4, 2, and 1 byte frames, each with a
+//! 3 byte header.
 //!
 //! If possible, you should use the [VDM] implementation instead.
 //!
@@ -2,13 +2,21 @@
 //! [`embedded-hal`] blocking SPI trait.
 //!
 //! This uses the W5500 fixed data length mode (FDM).
-//! In FSM mode the SPI chip select pin is always tied low, and it is not
+//! In FDM mode the SPI chip select pin is always tied low, and it is not
 //! possible to share the bus with other devices.
+//! The W5500 frames each access by counting bytes instead of using chip
+//! select, transfers are split into 4, 2, and 1 byte frames, each with a
+//! 3 byte header.
 //!
 //! If possible, you should use the [VDM] implementation instead.
 //!
+//! With the `eha1` feature this also implements the [`aio::Registers`] trait
+//! using the [`embedded-hal-async`] SPI bus trait.
+//!
 //! [`embedded-hal`]: https://github.com/rust-embedded/embedded-hal
+//! [`embedded-hal-async`]: https://github.com/rust-embedded/embedded-hal
 //! [`Registers`]: crate::Registers
+//! [`aio::Registers`]: crate::aio::Registers
 //! [VDM]: crate::eh1::vdm
 
 use crate::spi::{self, AccessMode};
@@ -124,3 +132,73 @@ where
         Ok(())
     }
 }
+
+#[cfg(feature = "eha1")]
+impl<SPI> crate::aio::Registers for W5500<SPI>
+where
+    SPI: eha1::spi::SpiBus<u8>,
+{
+    /// SPI IO error type.
+    type Error = SPI::Error;
+
+    /// Read from the W5500 asynchronously.
+    #[allow(clippy::while_let_on_iterator)]
+    async fn read(
+        &mut self,
+        mut address: u16,
+        block: u8,
+        data: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        let mut chunks = data.chunks_exact_mut(4);
+        while let Some(chunk) = chunks.next() {
+            let header = spi::fdm_header_4b(address, block, AccessMode::Read);
+            self.spi.write(&header).await?;
+            self.spi.read(chunk).await?;
+            address = address.wrapping_add(4);
+        }
+        let mut chunks = chunks.into_remainder().chunks_exact_mut(2);
+        while let Some(chunk) = chunks.next() {
+            let header = spi::fdm_header_2b(address, block, AccessMode::Read);
+            self.spi.write(&header).await?;
+            self.spi.read(chunk).await?;
+            address = address.wrapping_add(2);
+        }
+        let mut chunks = chunks.into_remainder().chunks_exact_mut(1);
+        while let Some(chunk) = chunks.next() {
+            let header = spi::fdm_header_1b(address, block, AccessMode::Read);
+            self.spi.write(&header).await?;
+            self.spi.read(chunk).await?;
+            address = address.wrapping_add(1);
+        }
+
+        Ok(())
+    }
+
+    /// Write to the W5500 asynchronously.
+    #[allow(clippy::while_let_on_iterator)]
+    async fn write(&mut self, mut address: u16, block: u8, data: &[u8]) -> Result<(), Self::Error> {
+        let mut chunks = data.chunks_exact(4);
+        while let Some(chunk) = chunks.next() {
+            let header = spi::fdm_header_4b(address, block, AccessMode::Write);
+            self.spi.write(&header).await?;
+            self.spi.write(chunk).await?;
+            address = address.wrapping_add(4);
+        }
+        let mut chunks = chunks.remainder().chunks_exact(2);
+        while let Some(chunk) = chunks.next() {
+            let header = spi::fdm_header_2b(address, block, AccessMode::Write);
+            self.spi.write(&header).await?;
+            self.spi.write(chunk).await?;
+            address = address.wrapping_add(2);
+        }
+        let mut chunks = chunks.remainder().chunks_exact(1);
+        while let Some(chunk) = chunks.next() {
+            let header = spi::fdm_header_1b(address, block, AccessMode::Write);
+            self.spi.write(&header).await?;
+            self.spi.write(chunk).await?;
+            address = address.wrapping_add(1);
+        }
+
+        Ok(())
+    }
+}
@@ -1,18 +1,26 @@
 //! Variable data length implementation of the [`Registers`] trait using the
-//! [`embedded-hal`] blocking SPI trait, and a fallible GPIO pin.
+//! [`embedded-hal`] SPI device trait.
 //!
 //! This uses the W5500 variable data length mode (VDM).
 //! In VDM mode the SPI frame data length is determined by the chip select pin.
-//! This is the preferred blocking implementation if your W5500 has a fallible
-//! chip select pin.
+//! This is the preferred implementation.
+//!
+//! The [`SpiDevice`] owns the chip select pin, each register access is a
+//! single [`SpiDevice::transaction`] containing the 3 byte header followed by
+//! the data.
+//! To share the SPI bus with other devices use a [`SpiDevice`] implementation
+//! from [`embedded-hal-bus`].
 //!
 //! [`embedded-hal`]: https://github.com/rust-embedded/embedded-hal
+//! [`embedded-hal-bus`]: https://crates.io/crates/embedded-hal-bus
 //! [`Registers`]: crate::Registers
+//! [`SpiDevice`]: eh1::spi::SpiDevice
+//! [`SpiDevice::transaction`]: eh1::spi::SpiDevice::transaction
 
 use crate::spi::{vdm_header, AccessMode};
 use eh1::spi::ErrorType;
 
-/// W5500 blocking variable data length implementation.
+/// W5500 variable data length implementation.
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct W5500<SPI> {
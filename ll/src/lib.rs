@@ -42,12 +42,17 @@
 //! * `eha1`:
 //!   Implements the [`aio::Registers`] trait for types in the [`eh1`] module
 //!   using the `embedded-hal-async` traits.
+//! * `serde`: Implements `Serialize` and `Deserialize` for the networking
+//!   types and register value enumerations.
+//! * `test-util`: Enables the [`mock`] module which contains an in-memory
+//!   implementation of the [`Registers`] trait for unit tests.
 //!
 //! [Wiznet W5500]: https://docs.wiznet.io/Product/iEthernet/W5500/overview
 //! [`aio::Registers`]: https://docs.rs/w5500-ll/latest/w5500_ll/aio/trait.Registers.html
 //! [`aio`]: https://docs.rs/w5500-ll/latest/w5500_ll/aio/index.html
 //! [`eh0`]: https://docs.rs/w5500-ll/latest/w5500_ll/eh0/index.html
 //! [`eh1`]: https://docs.rs/w5500-ll/latest/w5500_ll/eh1/index.html
+//! [`mock`]: https://docs.rs/w5500-ll/latest/w5500_ll/mock/index.html
 //! [`Registers`]: https://docs.rs/w5500-ll/latest/w5500_ll/trait.Registers.html
 #![cfg_attr(docsrs, feature(doc_cfg), feature(doc_auto_cfg))]
 #![no_std]
@@ -62,6 +67,8 @@ pub mod eh0;
 #[cfg(feature = "eh1")]
 pub mod eh1;
 
+#[cfg(feature = "test-util")]
+pub mod mock;
 pub mod net;
 pub mod spi;
 
@@ -71,10 +78,12 @@ mod specifiers;
 use net::{Eui48Addr, Ipv4Addr, SocketAddrV4};
 
 pub use addr::{Reg, SnReg};
-pub use registers::{Interrupt, Mode, PhyCfg, SocketInterrupt, SocketInterruptMask, SocketMode};
+pub use registers::{
+    Interrupt, Mode, PhyCfg, SocketInterrupt, SocketInterruptIter, SocketInterruptMask, SocketMode,
+};
 pub use specifiers::{
-    BufferSize, DuplexStatus, LinkStatus, OperationMode, Protocol, SocketCommand, SocketStatus,
-    SpeedStatus,
+    BufferSize, DuplexStatus, LinkStatus, OperationMode, Protocol, SocketCommand,
+    SocketInterruptKind, SocketStatus, SpeedStatus,
 };
 
 /// Common register block address offset.
@@ -113,6 +122,38 @@ const SOCKET_RX_OFFSET: u8 = 0x03;
 /// ```
 pub const VERSION: u8 = 0x04;
 
+/// Errors from waiting for a socket status.
+///
+/// Returned by [`Registers::wait_for_status`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum StatusError<E> {
+    /// The socket did not reach the target status within the poll limit.
+    ///
+    /// This contains the last raw value read from the socket status register.
+    Timeout(u8),
+    /// Errors from the [`Registers`] trait implementation.
+    Other(E),
+}
+
+impl<E> From<E> for StatusError<E> {
+    fn from(error: E) -> StatusError<E> {
+        StatusError::Other(error)
+    }
+}
+
+/// Socket status after waiting for `target` in `open_socket`.
+fn open_socket_status<E>(
+    result: Result<(), StatusError<E>>,
+    target: SocketStatus,
+) -> Result<Result<SocketStatus, u8>, E> {
+    match result {
+        Ok(()) => Ok(Ok(target)),
+        Err(StatusError::Timeout(sr)) => Ok(SocketStatus::try_from(sr)),
+        Err(StatusError::Other(e)) => Err(e),
+    }
+}
+
 /// W5500 socket numbers.
 #[repr(u8)]
 #[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug, Hash)]
@@ -418,12 +459,242 @@ pub struct RxPtrs {
     pub rd: u16,
 }
 
+/// Socket mode, command, interrupt, and status registers.
+///
+/// Returned by [`Registers::sn_status_block`] and
+/// [`aio::Registers::sn_status_block`].
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SnStatusBlock {
+    /// Socket mode, see [`Registers::sn_mr`].
+    pub mr: SocketMode,
+    /// Raw socket command, see [`Registers::sn_cr`].
+    pub cr: u8,
+    /// Socket interrupts, see [`Registers::sn_ir`].
+    pub ir: SocketInterrupt,
+    /// Socket status, see [`Registers::sn_sr`].
+    pub sr: Result<SocketStatus, u8>,
+}
+
+/// Socket status and buffer pointer registers.
+///
+/// Returned by [`Registers::sn_register_dump`] and
+/// [`aio::Registers::sn_register_dump`].
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SnRegisterDump {
+    /// Socket mode, command, interrupt, and status registers.
+    pub status: SnStatusBlock,
+    /// TX buffer free size and write pointer.
+    pub tx: TxPtrs,
+    /// RX buffer received size and read pointer.
+    pub rx: RxPtrs,
+}
+
+/// Gateway, subnet mask, source hardware address, and source IP address
+/// registers.
+///
+/// These registers are adjacent, [`Registers::set_net_config`] and
+/// [`aio::Registers::set_net_config`] write them in a single transaction.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
+pub struct NetConfig {
+    /// Gateway IP address, see [`Registers::gar`].
+    pub gateway: Ipv4Addr,
+    /// Subnet mask, see [`Registers::subr`].
+    pub subnet_mask: Ipv4Addr,
+    /// Source hardware address, see [`Registers::shar`].
+    pub mac: Eui48Addr,
+    /// Source IP address, see [`Registers::sipr`].
+    pub ip: Ipv4Addr,
+}
+
+impl NetConfig {
+    /// Length of the registers in bytes, from [`Reg::GAR0`] to
+    /// [`Reg::SIPR3`].
+    pub const LEN: usize = 18;
+
+    /// Convert the configuration to the register values, starting at
+    /// [`Reg::GAR0`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_ll::{
+    ///     net::{Eui48Addr, Ipv4Addr},
+    ///     NetConfig,
+    /// };
+    ///
+    /// let config = NetConfig {
+    ///     gateway: Ipv4Addr::new(192, 168, 0, 1),
+    ///     subnet_mask: Ipv4Addr::new(255, 255, 255, 0),
+    ///     mac: Eui48Addr::new(0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC),
+    ///     ip: Ipv4Addr::new(192, 168, 0, 150),
+    /// };
+    /// assert_eq!(
+    ///     config.to_bytes(),
+    ///     [
+    ///         192, 168, 0, 1, 255, 255, 255, 0, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 192, 168, 0,
+    ///         150
+    ///     ]
+    /// );
+    /// assert_eq!(NetConfig::from_bytes(config.to_bytes()), config);
+    /// ```
+    pub const fn to_bytes(&self) -> [u8; Self::LEN] {
+        let gateway: [u8; 4] = self.gateway.octets();
+        let subnet_mask: [u8; 4] = self.subnet_mask.octets();
+        let ip: [u8; 4] = self.ip.octets();
+        let mac: [u8; 6] = self.mac.octets;
+        [
+            gateway[0],
+            gateway[1],
+            gateway[2],
+            gateway[3],
+            subnet_mask[0],
+            subnet_mask[1],
+            subnet_mask[2],
+            subnet_mask[3],
+            mac[0],
+            mac[1],
+            mac[2],
+            mac[3],
+            mac[4],
+            mac[5],
+            ip[0],
+            ip[1],
+            ip[2],
+            ip[3],
+        ]
+    }
+
+    /// Create a configuration from the register values, starting at
+    /// [`Reg::GAR0`].
+    pub const fn from_bytes(bytes: [u8; Self::LEN]) -> Self {
+        Self {
+            gateway: Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]),
+            subnet_mask: Ipv4Addr::new(bytes[4], bytes[5], bytes[6], bytes[7]),
+            mac: Eui48Addr::new(
+                bytes[8], bytes[9], bytes[10], bytes[11], bytes[12], bytes[13],
+            ),
+            ip: Ipv4Addr::new(bytes[14], bytes[15], bytes[16], bytes[17]),
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for NetConfig {
+    fn format(&self, fmt: defmt::Formatter) {
+        let gateway: [u8; 4] = self.gateway.octets();
+        let subnet_mask: [u8; 4] = self.subnet_mask.octets();
+        let ip: [u8; 4] = self.ip.octets();
+        defmt::write!(
+            fmt,
+            "NetConfig {{ gateway: {}.{}.{}.{}, subnet_mask: {}.{}.{}.{}, mac: {}, ip: {}.{}.{}.{} }}",
+            gateway[0],
+            gateway[1],
+            gateway[2],
+            gateway[3],
+            subnet_mask[0],
+            subnet_mask[1],
+            subnet_mask[2],
+            subnet_mask[3],
+            self.mac,
+            ip[0],
+            ip[1],
+            ip[2],
+            ip[3],
+        )
+    }
+}
+
+/// Length of the common register block, up to and including
+/// [`Reg::VERSIONR`].
+pub const COMMON_BLOCK_LEN: usize = Reg::VERSIONR.addr() as usize + 1;
+
+/// Snapshot of the common registers and the socket status registers.
+///
+/// This is intended for debug logging and issue reports.
+///
+/// Returned by [`Registers::register_dump`] and
+/// [`aio::Registers::register_dump`].
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RegisterDump {
+    /// Raw common register block, indexed by [`Reg::addr`].
+    ///
+    /// Reserved addresses are included.
+    pub common: [u8; COMMON_BLOCK_LEN],
+    /// Socket registers, indexed by [`Sn`].
+    pub sn: [SnRegisterDump; SOCKETS.len()],
+}
+
+/// Test patterns written to the scratch register by [`Registers::probe`].
+const PROBE_PATTERNS: [u8; 2] = [0x55, 0xAA];
+
+/// Maximum number of version register reads by [`Registers::sw_reset`].
+const SW_RESET_ATTEMPTS: u8 = u8::MAX;
+
+/// Result of a W5500 bring-up check.
+///
+/// Returned by [`Registers::probe`] and [`aio::Registers::probe`].
+#[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ProbeResult {
+    /// Value read from the version register.
+    pub version: u8,
+    /// `true` if the scratch register write and read back succeeded.
+    pub scratch: bool,
+}
+
+impl ProbeResult {
+    /// Returns `true` if the version register matches [`VERSION`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_ll::{ProbeResult, VERSION};
+    ///
+    /// let probe = ProbeResult {
+    ///     version: VERSION,
+    ///     scratch: true,
+    /// };
+    /// assert!(probe.version_matches());
+    /// ```
+    pub const fn version_matches(&self) -> bool {
+        self.version == VERSION
+    }
+
+    /// Returns `true` if the version matched and the scratch register
+    /// round-trip succeeded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_ll::{ProbeResult, VERSION};
+    ///
+    /// let probe = ProbeResult {
+    ///     version: VERSION,
+    ///     scratch: false,
+    /// };
+    /// assert!(!probe.is_ok());
+    /// ```
+    pub const fn is_ok(&self) -> bool {
+        self.version_matches() && self.scratch
+    }
+}
+
 /// W5500 register setters and getters.
 ///
 /// * All register getters are simply the name of the register.
 /// * All register setters are the name of the register prefixed with `set_`.
 ///
 /// Most of the register documentation is taken from the data sheet.
+///
+/// # Socket buffer pointers
+///
+/// The socket buffer methods take a free-running 16-bit pointer, the W5500
+/// maps it onto the socket buffer modulo the configured buffer size.
+/// An access that runs past the end of the buffer wraps around to the start
+/// of the buffer, pointers larger than the buffer size are expected.
 pub trait Registers {
     /// Register accessor error type.
     type Error;
@@ -521,6 +792,34 @@ pub trait Registers {
         Ok(gar.into())
     }
 
+    /// Get the gateway IP address as raw bytes.
+    ///
+    /// This is the same as [`gar`](Registers::gar) without the
+    /// conversion, the bytes are in network order, as they appear in a packet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x01, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read_vec(vec![192, 168, 0, 1]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{eh1::vdm::W5500, Registers};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let gar: [u8; 4] = w5500.gar_bytes()?;
+    /// assert_eq!(gar, [192, 168, 0, 1]);
+    /// # w5500.free().done();
+    /// # Ok::<(), eh1::spi::ErrorKind>(())
+    /// ```
+    fn gar_bytes(&mut self) -> Result<[u8; 4], Self::Error> {
+        let mut gar: [u8; 4] = [0; 4];
+        self.read(Reg::GAR0.addr(), COMMON_BLOCK_OFFSET, &mut gar)?;
+        Ok(gar)
+    }
+
     /// Set the gateway IP address.
     ///
     /// # Example
@@ -568,6 +867,34 @@ pub trait Registers {
         Ok(subr.into())
     }
 
+    /// Get the subnet mask as raw bytes.
+    ///
+    /// This is the same as [`subr`](Registers::subr) without the
+    /// conversion, the bytes are in network order, as they appear in a packet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x05, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read_vec(vec![255, 255, 255, 0]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{eh1::vdm::W5500, Registers};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let subr: [u8; 4] = w5500.subr_bytes()?;
+    /// assert_eq!(subr, [255, 255, 255, 0]);
+    /// # w5500.free().done();
+    /// # Ok::<(), eh1::spi::ErrorKind>(())
+    /// ```
+    fn subr_bytes(&mut self) -> Result<[u8; 4], Self::Error> {
+        let mut subr: [u8; 4] = [0; 4];
+        self.read(Reg::SUBR0.addr(), COMMON_BLOCK_OFFSET, &mut subr)?;
+        Ok(subr)
+    }
+
     /// Set the subnet mask.
     ///
     /// # Example
@@ -615,6 +942,34 @@ pub trait Registers {
         Ok(shar)
     }
 
+    /// Get the source hardware address as raw bytes.
+    ///
+    /// This is the same as [`shar`](Registers::shar) without the
+    /// conversion, the bytes are in network order, as they appear in a packet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x09, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read_vec(vec![0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{eh1::vdm::W5500, Registers};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let shar: [u8; 6] = w5500.shar_bytes()?;
+    /// assert_eq!(shar, [0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC]);
+    /// # w5500.free().done();
+    /// # Ok::<(), eh1::spi::ErrorKind>(())
+    /// ```
+    fn shar_bytes(&mut self) -> Result<[u8; 6], Self::Error> {
+        let mut shar: [u8; 6] = [0; 6];
+        self.read(Reg::SHAR0.addr(), COMMON_BLOCK_OFFSET, &mut shar)?;
+        Ok(shar)
+    }
+
     /// Set the source hardware address.
     ///
     /// # Example
@@ -662,6 +1017,34 @@ pub trait Registers {
         Ok(sipr.into())
     }
 
+    /// Get the source (client) IP address as raw bytes.
+    ///
+    /// This is the same as [`sipr`](Registers::sipr) without the
+    /// conversion, the bytes are in network order, as they appear in a packet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x0F, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read_vec(vec![192, 168, 0, 150]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{eh1::vdm::W5500, Registers};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let sipr: [u8; 4] = w5500.sipr_bytes()?;
+    /// assert_eq!(sipr, [192, 168, 0, 150]);
+    /// # w5500.free().done();
+    /// # Ok::<(), eh1::spi::ErrorKind>(())
+    /// ```
+    fn sipr_bytes(&mut self) -> Result<[u8; 4], Self::Error> {
+        let mut sipr: [u8; 4] = [0; 4];
+        self.read(Reg::SIPR0.addr(), COMMON_BLOCK_OFFSET, &mut sipr)?;
+        Ok(sipr)
+    }
+
     /// Set the source (client) IP address.
     ///
     /// # Example
@@ -684,6 +1067,82 @@ pub trait Registers {
         self.write(Reg::SIPR0.addr(), COMMON_BLOCK_OFFSET, &sipr.octets())
     }
 
+    /// Get the gateway, subnet mask, source hardware address, and source IP
+    /// address.
+    ///
+    /// This is equivalent to [`Registers::gar`], [`Registers::subr`],
+    /// [`Registers::shar`], and [`Registers::sipr`] in a single read
+    /// transaction.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x01, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read_vec(vec![
+    /// #       192, 168, 0, 1, 255, 255, 255, 0, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 192, 168, 0, 150,
+    /// #   ]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{
+    ///     eh1::vdm::W5500,
+    ///     net::{Eui48Addr, Ipv4Addr},
+    ///     NetConfig, Registers,
+    /// };
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let config: NetConfig = w5500.net_config()?;
+    /// assert_eq!(config.ip, Ipv4Addr::new(192, 168, 0, 150));
+    /// # w5500.free().done();
+    /// # Ok::<(), eh1::spi::ErrorKind>(())
+    /// ```
+    fn net_config(&mut self) -> Result<NetConfig, Self::Error> {
+        let mut buf: [u8; NetConfig::LEN] = [0; NetConfig::LEN];
+        self.read(Reg::GAR0.addr(), COMMON_BLOCK_OFFSET, &mut buf)?;
+        Ok(NetConfig::from_bytes(buf))
+    }
+
+    /// Set the gateway, subnet mask, source hardware address, and source IP
+    /// address.
+    ///
+    /// This is equivalent to [`Registers::set_gar`], [`Registers::set_subr`],
+    /// [`Registers::set_shar`], and [`Registers::set_sipr`] in a single write
+    /// transaction.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x01, 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![
+    /// #       192, 168, 0, 1, 255, 255, 255, 0, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 192, 168, 0, 150,
+    /// #   ]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{
+    ///     eh1::vdm::W5500,
+    ///     net::{Eui48Addr, Ipv4Addr},
+    ///     NetConfig, Registers,
+    /// };
+    ///
+    /// let config = NetConfig {
+    ///     gateway: Ipv4Addr::new(192, 168, 0, 1),
+    ///     subnet_mask: Ipv4Addr::new(255, 255, 255, 0),
+    ///     mac: Eui48Addr::new(0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC),
+    ///     ip: Ipv4Addr::new(192, 168, 0, 150),
+    /// };
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// w5500.set_net_config(&config)?;
+    /// # w5500.free().done();
+    /// # Ok::<(), eh1::spi::ErrorKind>(())
+    /// ```
+    fn set_net_config(&mut self, config: &NetConfig) -> Result<(), Self::Error> {
+        self.write(Reg::GAR0.addr(), COMMON_BLOCK_OFFSET, &config.to_bytes())
+    }
+
     /// Get the interrupt low level time.
     ///
     /// INTLEVEL configures the assert wait time (I<sub>AWT</sub>).
@@ -903,6 +1362,61 @@ pub trait Registers {
         Ok(reg[0])
     }
 
+    /// Get and clear the interrupts of all sockets with a bit set in
+    /// [`sir`].
+    ///
+    /// The returned array is indexed by socket number, sockets without a
+    /// pending interrupt are `None`.
+    ///
+    /// This is a convenience method that calls [`sir`], and then
+    /// [`clear_sn_ir_all`] for each socket with a pending interrupt.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x17, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0x02),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x02, 0x28]),
+    /// #   ehm::eh1::spi::Transaction::read(SocketInterrupt::RECV_MASK),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x02, 0x28 | 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(SocketInterrupt::RECV_MASK),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{eh1::vdm::W5500, Registers, Sn, SocketInterrupt, SOCKETS};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let pending = w5500.pending_socket_interrupts()?;
+    /// for (sn, sn_ir) in SOCKETS.iter().zip(pending.iter()) {
+    ///     if let Some(sn_ir) = sn_ir {
+    ///         assert_eq!(*sn, Sn::Sn1);
+    ///         assert!(sn_ir.recv_raised());
+    ///     }
+    /// }
+    /// # w5500.free().done();
+    /// # Ok::<(), eh1::spi::ErrorKind>(())
+    /// ```
+    ///
+    /// [`sir`]: Registers::sir
+    /// [`clear_sn_ir_all`]: Registers::clear_sn_ir_all
+    fn pending_socket_interrupts(
+        &mut self,
+    ) -> Result<[Option<SocketInterrupt>; SOCKETS.len()], Self::Error> {
+        let sir: u8 = self.sir()?;
+        let mut ret: [Option<SocketInterrupt>; SOCKETS.len()] = [None; SOCKETS.len()];
+        for sn in SOCKETS {
+            if sir & sn.bitmask() != 0 {
+                ret[usize::from(sn)] = Some(self.clear_sn_ir_all(sn)?);
+            }
+        }
+        Ok(ret)
+    }
+
     /// Get the socket interrupt mask.
     ///
     /// Each bit of SIMR corresponds to each bit of [`Registers::sir`].
@@ -1378,88 +1892,390 @@ pub trait Registers {
     /// # w5500.free().done();
     /// # Ok::<(), eh1::spi::ErrorKind>(())
     /// ```
-    fn uportr(&mut self) -> Result<u16, Self::Error> {
-        let mut buf: [u8; 2] = [0; 2];
-        self.read(Reg::UPORTR0.addr(), COMMON_BLOCK_OFFSET, &mut buf)?;
-        Ok(u16::from_be_bytes(buf))
+    fn uportr(&mut self) -> Result<u16, Self::Error> {
+        let mut buf: [u8; 2] = [0; 2];
+        self.read(Reg::UPORTR0.addr(), COMMON_BLOCK_OFFSET, &mut buf)?;
+        Ok(u16::from_be_bytes(buf))
+    }
+
+    /// Get the PHY configuration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x2E, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0b10111000),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{eh1::vdm::W5500, PhyCfg, Registers};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let phy_cfg: PhyCfg = w5500.phycfgr()?;
+    /// assert_eq!(phy_cfg, PhyCfg::default());
+    /// # w5500.free().done();
+    /// # Ok::<(), eh1::spi::ErrorKind>(())
+    /// ```
+    fn phycfgr(&mut self) -> Result<PhyCfg, Self::Error> {
+        let mut reg: [u8; 1] = [0];
+        self.read(Reg::PHYCFGR.addr(), COMMON_BLOCK_OFFSET, &mut reg)?;
+        Ok(PhyCfg::from(reg[0]))
+    }
+
+    /// Set the PHY configuration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x2E, 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(0b11111000),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{eh1::vdm::W5500, OperationMode, PhyCfg, Registers};
+    ///
+    /// const PHY_CFG: PhyCfg = PhyCfg::DEFAULT.set_opmdc(OperationMode::Auto);
+    /// let mut w5500 = W5500::new(spi);
+    /// w5500.set_phycfgr(PHY_CFG)?;
+    /// # w5500.free().done();
+    /// # Ok::<(), eh1::spi::ErrorKind>(())
+    /// ```
+    fn set_phycfgr(&mut self, phycfg: PhyCfg) -> Result<(), Self::Error> {
+        self.write(Reg::PHYCFGR.addr(), COMMON_BLOCK_OFFSET, &[phycfg.into()])
+    }
+
+    /// Returns `true` if the ethernet link is up.
+    ///
+    /// This reads the [PHY configuration] register and checks the
+    /// [link status](PhyCfg::lnk).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x2E, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0b10111111),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{eh1::vdm::W5500, Registers};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// assert!(w5500.link_up()?);
+    /// # w5500.free().done();
+    /// # Ok::<(), eh1::spi::ErrorKind>(())
+    /// ```
+    ///
+    /// [PHY configuration]: Registers::phycfgr
+    fn link_up(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.phycfgr()?.lnk() == LinkStatus::Up)
+    }
+
+    /// Poll the link status until the ethernet link is up.
+    ///
+    /// The link status is read once, then up to `retries` more times.
+    /// Returns `true` if the link came up, or `false` if the retry budget was
+    /// exhausted.
+    ///
+    /// Auto-negotiation may take over a second to complete after a reset or
+    /// after the cable is connected.
+    /// There is no delay between reads, the number of retries required
+    /// depends on the SPI clock.
+    /// Use [`link_up`] with a delay between calls if you need a bounded wait
+    /// time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x2E, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0b10111000),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x2E, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0b10111111),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{eh1::vdm::W5500, Registers};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// assert!(w5500.wait_for_link(10_000)?);
+    /// # w5500.free().done();
+    /// # Ok::<(), eh1::spi::ErrorKind>(())
+    /// ```
+    ///
+    /// [`link_up`]: Registers::link_up
+    fn wait_for_link(&mut self, retries: u32) -> Result<bool, Self::Error> {
+        for _ in 0..=retries {
+            if self.link_up()? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Get the version.
+    ///
+    /// The value returned is always `0x04`.
+    ///
+    /// This register is extremely useful as a sanity check to ensure SPI
+    /// communications are working with the W5500.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x39, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0x04),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{eh1::vdm::W5500, Registers};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let version = w5500.version()?;
+    /// assert_eq!(version, 0x04);
+    /// # w5500.free().done();
+    /// # Ok::<(), eh1::spi::ErrorKind>(())
+    /// ```
+    fn version(&mut self) -> Result<u8, Self::Error> {
+        let mut reg: [u8; 1] = [0];
+        self.read(Reg::VERSIONR.addr(), COMMON_BLOCK_OFFSET, &mut reg)?;
+        Ok(reg[0])
+    }
+
+    /// Check that the W5500 is present and responding.
+    ///
+    /// This reads the [version] register, then writes test patterns to the
+    /// [PPP LCP magic number] register and reads them back.
+    /// The original value of the magic number register is restored afterwards.
+    ///
+    /// This should be called once during bring-up, it is not useful when
+    /// PPPoE is active.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x39, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0x04),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x1D, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0x00),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x1D, 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(0x55),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x1D, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0x55),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x1D, 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(0xAA),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x1D, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0xAA),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x1D, 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(0x00),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{eh1::vdm::W5500, ProbeResult, Registers};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let probe: ProbeResult = w5500.probe()?;
+    /// assert!(probe.is_ok());
+    /// # w5500.free().done();
+    /// # Ok::<(), eh1::spi::ErrorKind>(())
+    /// ```
+    ///
+    /// [version]: Registers::version
+    /// [PPP LCP magic number]: Registers::pmagic
+    fn probe(&mut self) -> Result<ProbeResult, Self::Error> {
+        let version: u8 = self.version()?;
+        let original: u8 = self.pmagic()?;
+        let mut scratch: bool = true;
+        for pattern in PROBE_PATTERNS {
+            self.set_pmagic(pattern)?;
+            scratch &= self.pmagic()? == pattern;
+        }
+        self.set_pmagic(original)?;
+        Ok(ProbeResult { version, scratch })
+    }
+
+    /// Check that the W5500 is alive.
+    ///
+    /// Returns `true` if the [version] register reads [`VERSION`].
+    ///
+    /// All registers read back as `0x00` when the W5500 is held in reset, this
+    /// is intended to be called periodically as a watchdog.
+    /// If the W5500 is not alive, wait for it to leave reset, then reset it
+    /// with [`sw_reset`](Registers::sw_reset) and reinitialize it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x39, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0x04),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x39, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0x00),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{eh1::vdm::W5500, Registers};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// assert!(w5500.is_alive()?);
+    /// // held in reset
+    /// assert!(!w5500.is_alive()?);
+    /// # w5500.free().done();
+    /// # Ok::<(), eh1::spi::ErrorKind>(())
+    /// ```
+    ///
+    /// [version]: Registers::version
+    fn is_alive(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.version()? == VERSION)
     }
 
-    /// Get the PHY configuration.
+    /// Reset the W5500 with the software reset bit, and wait for the W5500 to
+    /// respond.
+    ///
+    /// This sets [`Mode::RST_MASK`] in the mode register, then polls the
+    /// [version] register until it reads [`VERSION`].
+    ///
+    /// This is an alternative to a reset with the reset pin for boards that
+    /// do not have the reset pin connected.
+    ///
+    /// The innermost `Result<(), u8>` is [`Err`] with the last value read
+    /// from the version register if the W5500 did not respond after
+    /// 255 attempts.
     ///
     /// # Example
     ///
     /// ```
     /// # let spi = ehm::eh1::spi::Mock::new(&[
     /// #   ehm::eh1::spi::Transaction::transaction_start(),
-    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x2E, 0x00]),
-    /// #   ehm::eh1::spi::Transaction::read(0b10111000),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x00, 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(w5500_ll::Mode::RST_MASK),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x39, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0x00),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x39, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::read(0x04),
     /// #   ehm::eh1::spi::Transaction::transaction_end(),
     /// # ]);
-    /// use w5500_ll::{eh1::vdm::W5500, PhyCfg, Registers};
+    /// use w5500_ll::{eh1::vdm::W5500, Registers};
     ///
     /// let mut w5500 = W5500::new(spi);
-    /// let phy_cfg: PhyCfg = w5500.phycfgr()?;
-    /// assert_eq!(phy_cfg, PhyCfg::default());
+    /// w5500.sw_reset()?.expect("W5500 did not respond after reset");
     /// # w5500.free().done();
     /// # Ok::<(), eh1::spi::ErrorKind>(())
     /// ```
-    fn phycfgr(&mut self) -> Result<PhyCfg, Self::Error> {
-        let mut reg: [u8; 1] = [0];
-        self.read(Reg::PHYCFGR.addr(), COMMON_BLOCK_OFFSET, &mut reg)?;
-        Ok(PhyCfg::from(reg[0]))
+    ///
+    /// [version]: Registers::version
+    /// [`Err`]: https://doc.rust-lang.org/core/result/enum.Result.html#variant.Err
+    fn sw_reset(&mut self) -> Result<Result<(), u8>, Self::Error> {
+        self.set_mr(Mode::DEFAULT.rst())?;
+        let mut version: u8 = 0;
+        for _ in 0..SW_RESET_ATTEMPTS {
+            version = self.version()?;
+            if version == VERSION {
+                return Ok(Ok(()));
+            }
+        }
+        Ok(Err(version))
     }
 
-    /// Set the PHY configuration.
+    /// Get the socket status and buffer pointer registers.
+    ///
+    /// This is equivalent to [`Registers::sn_status_block`],
+    /// [`Registers::sn_tx_ptrs`], and [`Registers::sn_rx_ptrs`].
     ///
     /// # Example
     ///
     /// ```
     /// # let spi = ehm::eh1::spi::Mock::new(&[
     /// #   ehm::eh1::spi::Transaction::transaction_start(),
-    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x2E, 0x04]),
-    /// #   ehm::eh1::spi::Transaction::write(0b11111000),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x00, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read_vec(vec![0x02, 0x00, 0x00, 0x22]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x20, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read_vec(vec![0x08, 0x00, 0x00, 0x00, 0x00, 0x00]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x26, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read_vec(vec![0x00, 0x00, 0x00, 0x00]),
     /// #   ehm::eh1::spi::Transaction::transaction_end(),
     /// # ]);
-    /// use w5500_ll::{eh1::vdm::W5500, OperationMode, PhyCfg, Registers};
+    /// use w5500_ll::{eh1::vdm::W5500, Registers, Sn, SnRegisterDump, SocketStatus};
     ///
-    /// const PHY_CFG: PhyCfg = PhyCfg::DEFAULT.set_opmdc(OperationMode::Auto);
     /// let mut w5500 = W5500::new(spi);
-    /// w5500.set_phycfgr(PHY_CFG)?;
+    /// let dump: SnRegisterDump = w5500.sn_register_dump(Sn::Sn0)?;
+    /// assert_eq!(dump.status.sr, Ok(SocketStatus::Udp));
+    /// assert_eq!(dump.tx.fsr, 2048);
     /// # w5500.free().done();
     /// # Ok::<(), eh1::spi::ErrorKind>(())
     /// ```
-    fn set_phycfgr(&mut self, phycfg: PhyCfg) -> Result<(), Self::Error> {
-        self.write(Reg::PHYCFGR.addr(), COMMON_BLOCK_OFFSET, &[phycfg.into()])
+    fn sn_register_dump(&mut self, sn: Sn) -> Result<SnRegisterDump, Self::Error> {
+        Ok(SnRegisterDump {
+            status: self.sn_status_block(sn)?,
+            tx: self.sn_tx_ptrs(sn)?,
+            rx: self.sn_rx_ptrs(sn)?,
+        })
     }
 
-    /// Get the version.
+    /// Get a snapshot of the common registers and the socket status
+    /// registers.
     ///
-    /// The value returned is always `0x04`.
+    /// The common register block is read in a single transaction, followed
+    /// by [`Registers::sn_register_dump`] for each socket.
+    /// The registers are not read atomically, values may change between
+    /// transactions.
     ///
-    /// This register is extremely useful as a sanity check to ensure SPI
-    /// communications are working with the W5500.
+    /// This is intended for debug logging and issue reports.
     ///
     /// # Example
     ///
-    /// ```
-    /// # let spi = ehm::eh1::spi::Mock::new(&[
-    /// #   ehm::eh1::spi::Transaction::transaction_start(),
-    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x39, 0x00]),
-    /// #   ehm::eh1::spi::Transaction::read(0x04),
-    /// #   ehm::eh1::spi::Transaction::transaction_end(),
-    /// # ]);
-    /// use w5500_ll::{eh1::vdm::W5500, Registers};
+    /// ```no_run
+    /// # let mut w5500 = w5500_ll::eh1::vdm::W5500::new(ehm::eh1::spi::Mock::new(&[]));
+    /// use w5500_ll::{RegisterDump, Registers};
     ///
-    /// let mut w5500 = W5500::new(spi);
-    /// let version = w5500.version()?;
-    /// assert_eq!(version, 0x04);
-    /// # w5500.free().done();
+    /// let dump: RegisterDump = w5500.register_dump()?;
+    /// println!("{dump:?}");
     /// # Ok::<(), eh1::spi::ErrorKind>(())
     /// ```
-    fn version(&mut self) -> Result<u8, Self::Error> {
-        let mut reg: [u8; 1] = [0];
-        self.read(Reg::VERSIONR.addr(), COMMON_BLOCK_OFFSET, &mut reg)?;
-        Ok(reg[0])
+    fn register_dump(&mut self) -> Result<RegisterDump, Self::Error> {
+        let mut common: [u8; COMMON_BLOCK_LEN] = [0; COMMON_BLOCK_LEN];
+        self.read(Reg::MR.addr(), COMMON_BLOCK_OFFSET, &mut common)?;
+        Ok(RegisterDump {
+            common,
+            sn: [
+                self.sn_register_dump(Sn::Sn0)?,
+                self.sn_register_dump(Sn::Sn1)?,
+                self.sn_register_dump(Sn::Sn2)?,
+                self.sn_register_dump(Sn::Sn3)?,
+                self.sn_register_dump(Sn::Sn4)?,
+                self.sn_register_dump(Sn::Sn5)?,
+                self.sn_register_dump(Sn::Sn6)?,
+                self.sn_register_dump(Sn::Sn7)?,
+            ],
+        })
     }
 
     /// Get the socket mode.
@@ -1568,6 +2384,173 @@ pub trait Registers {
         self.write(SnReg::CR.addr(), sn.block(), &[cmd.into()])
     }
 
+    /// Issue the [`SendKeep`] socket command to send a TCP keep-alive packet.
+    ///
+    /// The W5500 ignores this command unless the socket is
+    /// [`Established`](SocketStatus::Established).
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The socket must be an [`Established`](SocketStatus::Established) TCP socket.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x03, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read(0x17),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x01, 0x08 | 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(0x22),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{eh1::vdm::W5500, Registers, Sn};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// w5500.send_keep(Sn::Sn0)?;
+    /// # w5500.free().done();
+    /// # Ok::<(), eh1::spi::ErrorKind>(())
+    /// ```
+    ///
+    /// [`SendKeep`]: SocketCommand::SendKeep
+    fn send_keep(&mut self, sn: Sn) -> Result<(), Self::Error> {
+        debug_assert_eq!(self.sn_sr(sn)?, Ok(SocketStatus::Established));
+        self.set_sn_cr(sn, SocketCommand::SendKeep)
+    }
+
+    /// Issue the [`SendMac`] socket command to send UDP data without an ARP
+    /// request, using the hardware address in [`sn_dhar`](Self::sn_dhar).
+    ///
+    /// The W5500 ignores this command unless the socket is in
+    /// [`Udp`](SocketStatus::Udp) mode.
+    ///
+    /// # Panics
+    ///
+    /// * (debug) The socket must be a [`Udp`](SocketStatus::Udp) socket.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x03, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read(0x22),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x01, 0x08 | 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(0x21),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{eh1::vdm::W5500, Registers, Sn};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// w5500.send_mac(Sn::Sn0)?;
+    /// # w5500.free().done();
+    /// # Ok::<(), eh1::spi::ErrorKind>(())
+    /// ```
+    ///
+    /// [`SendMac`]: SocketCommand::SendMac
+    fn send_mac(&mut self, sn: Sn) -> Result<(), Self::Error> {
+        debug_assert_eq!(self.sn_sr(sn)?, Ok(SocketStatus::Udp));
+        self.set_sn_cr(sn, SocketCommand::SendMac)
+    }
+
+    /// Close the socket, then open it with the given mode and local port,
+    /// waiting for the socket to open.
+    ///
+    /// This performs the following sequence:
+    ///
+    /// 1. Issue the [`Close`](SocketCommand::Close) command and wait for the
+    ///    socket to be [`Closed`](SocketStatus::Closed).
+    /// 2. Set the socket port and mode.
+    /// 3. Issue the [`Open`](SocketCommand::Open) command and wait for the
+    ///    socket status for the protocol in `mode`, [`Init`] for TCP,
+    ///    [`Udp`] for UDP, and [`Macraw`] for MACRAW.
+    ///
+    /// The resulting socket status is returned.
+    /// If the protocol in `mode` is closed or invalid, or MACRAW on a socket
+    /// other than [`Sn::Sn0`], the W5500 does not open the socket, the status
+    /// is read once without waiting.
+    ///
+    /// Registers that must be set before opening the socket, such as the
+    /// multicast destination, must be set before calling this method.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x01, 0x08 | 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(0x10),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x03, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read(0x00),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x04, 0x08 | 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 68]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x00, 0x08 | 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(0x02),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x01, 0x08 | 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(0x01),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x03, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read(0x22),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{eh1::vdm::W5500, Protocol, Registers, Sn, SocketMode, SocketStatus};
+    ///
+    /// const MODE: SocketMode = SocketMode::DEFAULT.set_protocol(Protocol::Udp);
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// assert_eq!(w5500.open_socket(Sn::Sn0, MODE, 68)?, Ok(SocketStatus::Udp));
+    /// # w5500.free().done();
+    /// # Ok::<(), eh1::spi::ErrorKind>(())
+    /// ```
+    ///
+    /// [`Init`]: SocketStatus::Init
+    /// [`Udp`]: SocketStatus::Udp
+    /// [`Macraw`]: SocketStatus::Macraw
+    fn open_socket(
+        &mut self,
+        sn: Sn,
+        mode: SocketMode,
+        port: u16,
+    ) -> Result<Result<SocketStatus, u8>, Self::Error> {
+        self.set_sn_cr(sn, SocketCommand::Close)?;
+        // This will not hang, the socket status will always change to closed
+        // after a close command.
+        // (unless you do something silly like holding the W5500 in reset)
+        let closed: Result<SocketStatus, u8> = open_socket_status(
+            self.wait_for_status(sn, SocketStatus::Closed, u32::MAX),
+            SocketStatus::Closed,
+        )?;
+        if closed != Ok(SocketStatus::Closed) {
+            return Ok(closed);
+        }
+        self.set_sn_port(sn, port)?;
+        self.set_sn_mr(sn, mode)?;
+        self.set_sn_cr(sn, SocketCommand::Open)?;
+        let status: SocketStatus = match mode.protocol() {
+            Ok(Protocol::Tcp) => SocketStatus::Init,
+            Ok(Protocol::Udp) => SocketStatus::Udp,
+            Ok(Protocol::Macraw) if sn == Sn::Sn0 => SocketStatus::Macraw,
+            Ok(Protocol::Macraw) | Ok(Protocol::Closed) | Err(_) => return self.sn_sr(sn),
+        };
+        // This will not hang, the socket status will always change to the
+        // status for the protocol after a open command.
+        // (unless you do something silly like holding the W5500 in reset)
+        open_socket_status(self.wait_for_status(sn, status, u32::MAX), status)
+    }
+
     /// Get the socket interrupt status.
     ///
     /// # Example
@@ -1640,6 +2623,46 @@ pub trait Registers {
         self.write(SnReg::IR.addr(), sn.block(), &[sn_ir.into()])
     }
 
+    /// Clear all raised socket interrupts.
+    ///
+    /// This reads [`sn_ir`], and writes the raised interrupts back to
+    /// [`set_sn_ir`] to clear them.
+    /// The write is skipped if no interrupts are raised.
+    ///
+    /// Returns the interrupts that were cleared.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x02, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read(SocketInterrupt::SENDOK_MASK),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x02, 0x08 | 0x04]),
+    /// #   ehm::eh1::spi::Transaction::write(SocketInterrupt::SENDOK_MASK),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{eh1::vdm::W5500, Registers, Sn, SocketInterrupt};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let cleared: SocketInterrupt = w5500.clear_sn_ir_all(Sn::Sn0)?;
+    /// assert!(cleared.sendok_raised());
+    /// # w5500.free().done();
+    /// # Ok::<(), eh1::spi::ErrorKind>(())
+    /// ```
+    ///
+    /// [`sn_ir`]: Registers::sn_ir
+    /// [`set_sn_ir`]: Registers::set_sn_ir
+    fn clear_sn_ir_all(&mut self, sn: Sn) -> Result<SocketInterrupt, Self::Error> {
+        let sn_ir: SocketInterrupt = self.sn_ir(sn)?;
+        if sn_ir.any_raised() {
+            self.set_sn_ir(sn, sn_ir)?;
+        }
+        Ok(sn_ir)
+    }
+
     /// Get the socket status.
     ///
     /// **Note:** This method returns a nested [`core::result::Result`].
@@ -1681,6 +2704,101 @@ pub trait Registers {
         Ok(SocketStatus::try_from(reg[0]))
     }
 
+    /// Poll the socket status until it reaches `target`.
+    ///
+    /// The socket status is read up to `max_polls` times, and at least once.
+    ///
+    /// Returns [`StatusError::Timeout`] with the last raw value read from the
+    /// [socket status] register if the socket did not reach `target` within
+    /// `max_polls` reads.
+    ///
+    /// The status can pass through intermediate values after a command, for
+    /// example [`SocketStatus::SynSent`] after a connect command.
+    /// There is no delay between reads, the number of polls required depends
+    /// on the SPI clock.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x03, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read(0x00),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x03, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read(0x22),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x03, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read(0x00),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{eh1::vdm::W5500, Registers, Sn, SocketStatus, StatusError};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// w5500.wait_for_status(Sn::Sn0, SocketStatus::Udp, 10)?;
+    /// assert_eq!(
+    ///     w5500.wait_for_status(Sn::Sn0, SocketStatus::Init, 1),
+    ///     Err(StatusError::Timeout(0x00))
+    /// );
+    /// # w5500.free().done();
+    /// # Ok::<(), w5500_ll::StatusError<eh1::spi::ErrorKind>>(())
+    /// ```
+    ///
+    /// [socket status]: Registers::sn_sr
+    fn wait_for_status(
+        &mut self,
+        sn: Sn,
+        target: SocketStatus,
+        max_polls: u32,
+    ) -> Result<(), StatusError<Self::Error>> {
+        let mut sr: u8 = 0;
+        for _ in 0..max_polls.max(1) {
+            match self.sn_sr(sn)? {
+                Ok(status) if status == target => return Ok(()),
+                Ok(status) => sr = status.into(),
+                Err(raw) => sr = raw,
+            }
+        }
+        Err(StatusError::Timeout(sr))
+    }
+
+    /// Get the socket mode, command, interrupt, and status registers.
+    ///
+    /// This is equivalent to [`Registers::sn_mr`], [`Registers::sn_cr`],
+    /// [`Registers::sn_ir`], and [`Registers::sn_sr`] in a single read
+    /// transaction.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x00, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read_vec(vec![0x02, 0x00, 0x04, 0x22]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{eh1::vdm::W5500, Registers, Sn, SnStatusBlock, SocketStatus};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let status: SnStatusBlock = w5500.sn_status_block(Sn::Sn0)?;
+    /// assert_eq!(status.sr, Ok(SocketStatus::Udp));
+    /// assert!(status.ir.recv_raised());
+    /// # w5500.free().done();
+    /// # Ok::<(), eh1::spi::ErrorKind>(())
+    /// ```
+    fn sn_status_block(&mut self, sn: Sn) -> Result<SnStatusBlock, Self::Error> {
+        let mut buf: [u8; 4] = [0; 4];
+        self.read(SnReg::MR.addr(), sn.block(), &mut buf)?;
+        Ok(SnStatusBlock {
+            mr: SocketMode::from(buf[0]),
+            cr: buf[1],
+            ir: SocketInterrupt::from(buf[2]),
+            sr: SocketStatus::try_from(buf[3]),
+        })
+    }
+
     /// Get the socket source port.
     ///
     /// This is only valid in TCP/UDP mode.
@@ -1938,10 +3056,7 @@ pub trait Registers {
     fn sn_dest(&mut self, sn: Sn) -> Result<SocketAddrV4, Self::Error> {
         let mut buf: [u8; 6] = [0; 6];
         self.read(SnReg::DIPR0.addr(), sn.block(), &mut buf)?;
-        Ok(SocketAddrV4::new(
-            Ipv4Addr::new(buf[0], buf[1], buf[2], buf[3]),
-            u16::from_be_bytes([buf[4], buf[5]]),
-        ))
+        Ok(net::socket_addr_from_bytes(buf))
     }
 
     /// Set the socket destination IPv4 and port.
@@ -1971,15 +3086,11 @@ pub trait Registers {
     /// # Ok::<(), eh1::spi::ErrorKind>(())
     /// ```
     fn set_sn_dest(&mut self, sn: Sn, addr: &SocketAddrV4) -> Result<(), Self::Error> {
-        let buf: [u8; 6] = [
-            addr.ip().octets()[0],
-            addr.ip().octets()[1],
-            addr.ip().octets()[2],
-            addr.ip().octets()[3],
-            (addr.port() >> 8) as u8,
-            addr.port() as u8,
-        ];
-        self.write(SnReg::DIPR0.addr(), sn.block(), &buf)
+        self.write(
+            SnReg::DIPR0.addr(),
+            sn.block(),
+            &net::socket_addr_to_bytes(addr),
+        )
     }
 
     /// Get the socket maximum segment size.
@@ -2569,6 +3680,39 @@ pub trait Registers {
         })
     }
 
+    /// Get the socket RX read and write pointers.
+    ///
+    /// This is equivalent to [`Registers::sn_rx_rd`] and
+    /// [`Registers::sn_rx_wr`] in a single read transaction.
+    /// The received size is the difference between the pointers,
+    /// `wr.wrapping_sub(rd)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let spi = ehm::eh1::spi::Mock::new(&[
+    /// #   ehm::eh1::spi::Transaction::transaction_start(),
+    /// #   ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x28, 0x08]),
+    /// #   ehm::eh1::spi::Transaction::read_vec(vec![0xFF, 0xF0, 0x00, 0x10]),
+    /// #   ehm::eh1::spi::Transaction::transaction_end(),
+    /// # ]);
+    /// use w5500_ll::{eh1::vdm::W5500, Registers, Sn};
+    ///
+    /// let mut w5500 = W5500::new(spi);
+    /// let (rd, wr): (u16, u16) = w5500.sn_rx_rd_wr(Sn::Sn0)?;
+    /// assert_eq!(wr.wrapping_sub(rd), 32);
+    /// # w5500.free().done();
+    /// # Ok::<(), eh1::spi::ErrorKind>(())
+    /// ```
+    fn sn_rx_rd_wr(&mut self, sn: Sn) -> Result<(u16, u16), Self::Error> {
+        let mut buf: [u8; 4] = [0; 4];
+        self.read(SnReg::RX_RD0.addr(), sn.block(), &mut buf)?;
+        Ok((
+            u16::from_be_bytes(buf[..2].try_into().unwrap()),
+            u16::from_be_bytes(buf[2..].try_into().unwrap()),
+        ))
+    }
+
     /// Get the socket interrupt mask.
     ///
     /// # Example
@@ -2736,6 +3880,9 @@ pub trait Registers {
 
     /// Write the socket TX buffer.
     ///
+    /// `ptr` is a free-running pointer, see
+    /// [socket buffer pointers](Registers#socket-buffer-pointers).
+    ///
     /// # Example
     ///
     /// ```
@@ -2797,6 +3944,9 @@ pub trait Registers {
 
     /// Read the socket TX buffer.
     ///
+    /// `ptr` is a free-running pointer, see
+    /// [socket buffer pointers](Registers#socket-buffer-pointers).
+    ///
     /// This method is typically unused; there are very few use cases that
     /// require reading the TX buffer.
     ///
@@ -2823,6 +3973,9 @@ pub trait Registers {
 
     /// Read the socket RX buffer.
     ///
+    /// `ptr` is a free-running pointer, see
+    /// [socket buffer pointers](Registers#socket-buffer-pointers).
+    ///
     /// # Example
     ///
     /// ```
@@ -2880,6 +4033,9 @@ pub trait Registers {
 
     /// Write the socket RX buffer.
     ///
+    /// `ptr` is a free-running pointer, see
+    /// [socket buffer pointers](Registers#socket-buffer-pointers).
+    ///
     /// This method is typically unused; there are very few use cases that
     /// require writing the RX buffer.
     ///
@@ -0,0 +1,164 @@
+//! In-memory W5500 register mock.
+//!
+//! [`MockW5500`] implements the [`Registers`] and [`aio::Registers`] traits
+//! with a flat memory for each block, there is no networking and no side
+//! effects from writing registers such as socket commands.
+//!
+//! This is useful for deterministic `no_std` unit tests of code that uses the
+//! [`Registers`] trait.
+//! For a simulation of socket behavior use `w5500-regsim`.
+
+use crate::{aio, Reg, Registers, SnReg, COMMON_BLOCK_OFFSET, SOCKETS, VERSION};
+use core::convert::Infallible;
+
+/// Size of the common register block, up to and including `VERSIONR`.
+const COMMON_LEN: usize = Reg::VERSIONR.addr() as usize + 1;
+/// Size of a socket register block, up to and including `SN_KPALVTR`.
+const SN_LEN: usize = SnReg::KPALVTR.addr() as usize + 1;
+/// Size of each socket buffer.
+///
+/// Buffer addresses wrap at this size, matching the default socket buffer
+/// size of 2 KiB.
+const BUF_LEN: usize = 2048;
+
+const COMMON_RESET: [u8; COMMON_LEN] = {
+    let mut common: [u8; COMMON_LEN] = [0; COMMON_LEN];
+    common[Reg::RTR0.addr() as usize] = 0x07;
+    common[Reg::RTR1.addr() as usize] = 0xD0;
+    common[Reg::RCR.addr() as usize] = 0x08;
+    common[Reg::PTIMER.addr() as usize] = 0x28;
+    common[Reg::PMRU0.addr() as usize] = 0xFF;
+    common[Reg::PMRU1.addr() as usize] = 0xFF;
+    common[Reg::PHYCFGR.addr() as usize] = 0b1011_1111;
+    common[Reg::VERSIONR.addr() as usize] = VERSION;
+    common
+};
+
+const SN_RESET: [u8; SN_LEN] = {
+    let mut sn: [u8; SN_LEN] = [0; SN_LEN];
+    let mut dhar: usize = SnReg::DHAR0.addr() as usize;
+    while dhar <= SnReg::DHAR5.addr() as usize {
+        sn[dhar] = 0xFF;
+        dhar += 1;
+    }
+    sn[SnReg::TTL.addr() as usize] = 0x80;
+    sn[SnReg::RXBUF_SIZE.addr() as usize] = 0x02;
+    sn[SnReg::TXBUF_SIZE.addr() as usize] = 0x02;
+    sn[SnReg::TX_FSR0.addr() as usize] = 0x08;
+    sn[SnReg::IMR.addr() as usize] = 0xFF;
+    sn[SnReg::FRAG0.addr() as usize] = 0x40;
+    sn
+};
+
+/// In-memory W5500 register mock.
+///
+/// All registers start at their reset values.
+/// Reads return the last value written, including read-only registers.
+///
+/// Socket buffer addresses wrap at 2 KiB, regardless of the configured
+/// buffer size.
+///
+/// # Panics
+///
+/// Reads and writes panic if the block select bits are invalid, or if the
+/// address is past the end of a register block.
+///
+/// # Example
+///
+/// ```
+/// use w5500_ll::{mock::MockW5500, net::Ipv4Addr, Registers, Sn, SocketMode, VERSION};
+///
+/// let mut w5500 = MockW5500::new();
+/// assert_eq!(w5500.version()?, VERSION);
+///
+/// w5500.set_sipr(&Ipv4Addr::new(192, 168, 1, 10))?;
+/// assert_eq!(w5500.sipr()?, Ipv4Addr::new(192, 168, 1, 10));
+///
+/// let mode: SocketMode = SocketMode::DEFAULT.enable_nd();
+/// w5500.set_sn_mr(Sn::Sn3, mode)?;
+/// assert_eq!(w5500.sn_mr(Sn::Sn3)?, mode);
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockW5500 {
+    common: [u8; COMMON_LEN],
+    sn: [[u8; SN_LEN]; SOCKETS.len()],
+    tx: [[u8; BUF_LEN]; SOCKETS.len()],
+    rx: [[u8; BUF_LEN]; SOCKETS.len()],
+}
+
+impl MockW5500 {
+    /// Create a new mock with all registers at their reset values.
+    pub const fn new() -> Self {
+        Self {
+            common: COMMON_RESET,
+            sn: [SN_RESET; SOCKETS.len()],
+            tx: [[0; BUF_LEN]; SOCKETS.len()],
+            rx: [[0; BUF_LEN]; SOCKETS.len()],
+        }
+    }
+
+    fn block_mut(&mut self, block: u8) -> &mut [u8] {
+        if block == COMMON_BLOCK_OFFSET {
+            return &mut self.common;
+        }
+        let sn: usize = usize::from(block / 4);
+        if sn >= SOCKETS.len() {
+            panic!("invalid block select bits 0x{block:02X}")
+        }
+        match block % 4 {
+            1 => &mut self.sn[sn],
+            2 => &mut self.tx[sn],
+            3 => &mut self.rx[sn],
+            _ => panic!("invalid block select bits 0x{block:02X}"),
+        }
+    }
+
+    fn access(&mut self, addr: u16, block: u8, len: usize, mut f: impl FnMut(usize, &mut u8)) {
+        let is_buffer: bool = block != COMMON_BLOCK_OFFSET && block % 4 >= 2;
+        let mem: &mut [u8] = self.block_mut(block);
+        (0..len).for_each(|idx| {
+            let offset: usize = if is_buffer {
+                usize::from(addr.wrapping_add(idx as u16)) % BUF_LEN
+            } else {
+                usize::from(addr) + idx
+            };
+            let byte: &mut u8 = mem.get_mut(offset).unwrap_or_else(|| {
+                panic!("address 0x{offset:04X} is outside of block 0x{block:02X}")
+            });
+            f(idx, byte)
+        })
+    }
+}
+
+impl Default for MockW5500 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Registers for MockW5500 {
+    type Error = Infallible;
+
+    fn read(&mut self, addr: u16, block: u8, data: &mut [u8]) -> Result<(), Self::Error> {
+        self.access(addr, block, data.len(), |idx, byte| data[idx] = *byte);
+        Ok(())
+    }
+
+    fn write(&mut self, addr: u16, block: u8, data: &[u8]) -> Result<(), Self::Error> {
+        self.access(addr, block, data.len(), |idx, byte| *byte = data[idx]);
+        Ok(())
+    }
+}
+
+impl aio::Registers for MockW5500 {
+    type Error = Infallible;
+
+    async fn read(&mut self, addr: u16, block: u8, data: &mut [u8]) -> Result<(), Self::Error> {
+        Registers::read(self, addr, block, data)
+    }
+
+    async fn write(&mut self, addr: u16, block: u8, data: &[u8]) -> Result<(), Self::Error> {
+        Registers::write(self, addr, block, data)
+    }
+}
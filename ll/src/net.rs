@@ -1,7 +1,113 @@
 //! Networking data types.
+//!
+//! [`Ipv4Addr`] and [`SocketAddrV4`] are re-exports of the [`core::net`]
+//! types, no conversion is required to share addresses with other
+//! `core::net` based crates.
+//!
+//! ```
+//! use w5500_ll::net::Ipv4Addr;
+//!
+//! let addr: core::net::Ipv4Addr = Ipv4Addr::new(192, 168, 1, 10);
+//! let addr: Ipv4Addr = addr;
+//! # let _ = addr;
+//! ```
+//!
+//! With the `serde` feature the `core::net` types use the `serde`
+//! implementations provided by `serde` itself.
+//!
+//! Address classification, such as [`Ipv4Addr::is_private`],
+//! [`Ipv4Addr::is_loopback`], [`Ipv4Addr::is_broadcast`], and
+//! [`Ipv4Addr::is_multicast`], is also provided by `core::net`.
+//!
+//! The `core::net` types and [`Eui48Addr`] implement `Hash`, `PartialOrd`,
+//! and `Ord`, they can be used as map keys and sorted.
+//!
+//! ```
+//! use std::collections::BTreeSet;
+//! use w5500_ll::net::{Eui48Addr, Ipv4Addr, SocketAddrV4};
+//!
+//! let mut addrs: BTreeSet<SocketAddrV4> = BTreeSet::new();
+//! addrs.insert(SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 2), 80));
+//! addrs.insert(SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 1), 80));
+//! assert_eq!(
+//!     addrs.first(),
+//!     Some(&SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 1), 80))
+//! );
+//!
+//! let mut macs: BTreeSet<Eui48Addr> = BTreeSet::new();
+//! macs.insert(Eui48Addr::new(0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC));
+//! assert!(macs.contains(&Eui48Addr::new(0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC)));
+//! ```
 
 pub use core::net::{Ipv4Addr, SocketAddrV4};
 
+/// Returns `true` if `a` and `b` are in the same subnet.
+///
+/// This can be used to decide if a destination is reachable directly, or if
+/// it must be sent through the gateway.
+///
+/// # Examples
+///
+/// ```
+/// use w5500_ll::net::{same_subnet, Ipv4Addr};
+///
+/// const MASK: Ipv4Addr = Ipv4Addr::new(255, 255, 255, 0);
+/// const ADDR: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 10);
+///
+/// assert!(same_subnet(ADDR, Ipv4Addr::new(192, 168, 1, 1), MASK));
+/// assert!(!same_subnet(ADDR, Ipv4Addr::new(192, 168, 2, 1), MASK));
+/// ```
+pub const fn same_subnet(a: Ipv4Addr, b: Ipv4Addr, mask: Ipv4Addr) -> bool {
+    let a: u32 = u32::from_be_bytes(a.octets());
+    let b: u32 = u32::from_be_bytes(b.octets());
+    let mask: u32 = u32::from_be_bytes(mask.octets());
+    (a ^ b) & mask == 0
+}
+
+/// Serialize a socket address to the W5500 register layout.
+///
+/// This is the layout of the `SN_DIPR` and `SN_DPORT` registers, and the
+/// origin in a UDP header, four octets of the IPv4 address followed by the
+/// port in big endian byte order.
+///
+/// # Example
+///
+/// ```
+/// use w5500_ll::net::{socket_addr_to_bytes, Ipv4Addr, SocketAddrV4};
+///
+/// const ADDR: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 168, 1, 10), 0x1F90);
+/// assert_eq!(
+///     socket_addr_to_bytes(&ADDR),
+///     [192, 168, 1, 10, 0x1F, 0x90]
+/// );
+/// ```
+pub const fn socket_addr_to_bytes(addr: &SocketAddrV4) -> [u8; 6] {
+    let ip: [u8; 4] = addr.ip().octets();
+    let port: [u8; 2] = addr.port().to_be_bytes();
+    [ip[0], ip[1], ip[2], ip[3], port[0], port[1]]
+}
+
+/// Deserialize a socket address from the W5500 register layout.
+///
+/// This is the inverse of [`socket_addr_to_bytes`].
+///
+/// # Example
+///
+/// ```
+/// use w5500_ll::net::{socket_addr_from_bytes, Ipv4Addr, SocketAddrV4};
+///
+/// assert_eq!(
+///     socket_addr_from_bytes([192, 168, 1, 10, 0x1F, 0x90]),
+///     SocketAddrV4::new(Ipv4Addr::new(192, 168, 1, 10), 8080)
+/// );
+/// ```
+pub const fn socket_addr_from_bytes(bytes: [u8; 6]) -> SocketAddrV4 {
+    SocketAddrV4::new(
+        Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]),
+        u16::from_be_bytes([bytes[4], bytes[5]]),
+    )
+}
+
 /// EUI-48 MAC address struct.
 ///
 /// Can be instantiated with [`Eui48Addr::new`].
@@ -47,6 +153,83 @@ impl Eui48Addr {
     /// assert_eq!(addr, Eui48Addr::new(0x00, 0x00, 0x00, 0x00, 0x00, 0x00));
     /// ```
     pub const UNSPECIFIED: Self = Eui48Addr::new(0, 0, 0, 0, 0, 0);
+
+    /// Individual/group (I/G) bit of the first octet, set for multicast
+    /// addresses.
+    const GROUP_MASK: u8 = 0b01;
+    /// Universal/local (U/L) bit of the first octet, set for locally
+    /// administered addresses.
+    const LOCAL_MASK: u8 = 0b10;
+
+    /// Creates a new EUI-48 MAC address from an organizationally unique
+    /// identifier (OUI) and a device identifier.
+    ///
+    /// The OUI is used as-is, the U/L and I/G bits are not modified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use w5500_ll::net::Eui48Addr;
+    ///
+    /// let addr = Eui48Addr::from_oui_and_id([0x00, 0x08, 0xDC], [0x01, 0x02, 0x03]);
+    /// assert_eq!(addr, Eui48Addr::new(0x00, 0x08, 0xDC, 0x01, 0x02, 0x03));
+    /// ```
+    pub const fn from_oui_and_id(oui: [u8; 3], id: [u8; 3]) -> Eui48Addr {
+        Eui48Addr::new(oui[0], oui[1], oui[2], id[0], id[1], id[2])
+    }
+
+    /// Creates a new locally administered unicast EUI-48 MAC address from a
+    /// device identifier, such as a serial number.
+    ///
+    /// The result will represent the EUI-48 MAC address `02:00:`, followed by
+    /// the big-endian octets of `id`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use w5500_ll::net::Eui48Addr;
+    ///
+    /// let addr = Eui48Addr::locally_administered(0x12345678);
+    /// assert_eq!(addr, Eui48Addr::new(0x02, 0x00, 0x12, 0x34, 0x56, 0x78));
+    /// assert!(addr.is_local());
+    /// assert!(addr.is_unicast());
+    /// ```
+    pub const fn locally_administered(id: u32) -> Eui48Addr {
+        let id: [u8; 4] = id.to_be_bytes();
+        Eui48Addr::new(Self::LOCAL_MASK, 0x00, id[0], id[1], id[2], id[3])
+    }
+
+    /// Returns `true` if this is a unicast address.
+    ///
+    /// This checks the individual/group (I/G) bit is cleared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use w5500_ll::net::Eui48Addr;
+    ///
+    /// assert!(Eui48Addr::new(0x00, 0x08, 0xDC, 0x01, 0x02, 0x03).is_unicast());
+    /// assert!(!Eui48Addr::new(0x01, 0x00, 0x5E, 0x00, 0x00, 0xFB).is_unicast());
+    /// ```
+    pub const fn is_unicast(&self) -> bool {
+        self.octets[0] & Self::GROUP_MASK == 0
+    }
+
+    /// Returns `true` if this is a locally administered address.
+    ///
+    /// This checks the universal/local (U/L) bit is set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use w5500_ll::net::Eui48Addr;
+    ///
+    /// assert!(Eui48Addr::new(0x02, 0x00, 0x00, 0x00, 0x00, 0x01).is_local());
+    /// assert!(!Eui48Addr::new(0x00, 0x08, 0xDC, 0x01, 0x02, 0x03).is_local());
+    /// ```
+    pub const fn is_local(&self) -> bool {
+        self.octets[0] & Self::LOCAL_MASK != 0
+    }
 }
 
 impl ::core::fmt::Display for Eui48Addr {
@@ -96,3 +279,86 @@ impl From<[u8; 6]> for Eui48Addr {
         Eui48Addr { octets }
     }
 }
+
+/// An error which can be returned when parsing an [`Eui48Addr`].
+///
+/// [`Ipv4Addr`] and [`SocketAddrV4`] return [`core::net::AddrParseError`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Eui48AddrParseError(());
+
+impl ::core::fmt::Display for Eui48AddrParseError {
+    fn fmt(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        fmt.write_str("invalid EUI-48 address syntax")
+    }
+}
+
+impl ::core::str::FromStr for Eui48Addr {
+    type Err = Eui48AddrParseError;
+
+    /// Parse an `Eui48Addr` from six colon separated hexadecimal octets.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use w5500_ll::net::Eui48Addr;
+    ///
+    /// let addr: Eui48Addr = "12:34:56:78:9a:BC".parse().unwrap();
+    /// assert_eq!(addr, Eui48Addr::new(0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC));
+    /// assert!("12:34:56:78:9A".parse::<Eui48Addr>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut octets: [u8; 6] = [0; 6];
+        let mut parts = s.split(':');
+        for octet in octets.iter_mut() {
+            let part: &str = parts.next().ok_or(Eui48AddrParseError(()))?;
+            if part.len() != 2 || !part.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(Eui48AddrParseError(()));
+            }
+            *octet = u8::from_str_radix(part, 16).map_err(|_| Eui48AddrParseError(()))?;
+        }
+        if parts.next().is_some() {
+            return Err(Eui48AddrParseError(()));
+        }
+        Ok(Eui48Addr { octets })
+    }
+}
+
+/// Serialized as a `"XX:XX:XX:XX:XX:XX"` string for human readable formats,
+/// and as 6 bytes otherwise, matching the `serde` implementations of the
+/// [`core::net`] types.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Eui48Addr {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            self.octets.serialize(serializer)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Eui48Addr {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Eui48AddrVisitor;
+
+        impl serde::de::Visitor<'_> for Eui48AddrVisitor {
+            type Value = Eui48Addr;
+
+            fn expecting(&self, fmt: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                fmt.write_str("an EUI-48 address")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<Self::Value, E> {
+                s.parse().map_err(E::custom)
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(Eui48AddrVisitor)
+        } else {
+            <[u8; 6]>::deserialize(deserializer).map(Eui48Addr::from)
+        }
+    }
+}
@@ -1,6 +1,8 @@
 //! Register structures.
 
-use crate::specifiers::{DuplexStatus, LinkStatus, OperationMode, Protocol, SpeedStatus};
+use crate::specifiers::{
+    DuplexStatus, LinkStatus, OperationMode, Protocol, SocketInterruptKind, SpeedStatus,
+};
 
 macro_rules! impl_boilerplate_for {
     ($REG:ident) => {
@@ -538,12 +540,38 @@ impl PhyCfg {
     pub const LNK_MASK: u8 = 1 << Self::LNK_OFFSET;
 
     /// Set the PHY reset bit to `0`, resetting the PHY.
+    ///
+    /// The PHY is held in reset until the reset bit is set to `1` with
+    /// [`release_rst`](Self::release_rst).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_ll::PhyCfg;
+    ///
+    /// let phy_cfg: PhyCfg = PhyCfg::DEFAULT.rst();
+    /// assert!(phy_cfg.rst_asserted());
+    /// let phy_cfg: PhyCfg = phy_cfg.release_rst();
+    /// assert!(!phy_cfg.rst_asserted());
+    /// ```
     #[must_use = "rst returns a modified PhyCfg"]
     pub const fn rst(mut self) -> Self {
         self.0 &= !Self::RST_MASK;
         self
     }
 
+    /// Set the PHY reset bit to `1`, releasing the PHY from reset.
+    #[must_use = "release_rst returns a modified PhyCfg"]
+    pub const fn release_rst(mut self) -> Self {
+        self.0 |= Self::RST_MASK;
+        self
+    }
+
+    /// Returns `true` if the PHY reset bit is `0`, holding the PHY in reset.
+    pub const fn rst_asserted(&self) -> bool {
+        self.0 & Self::RST_MASK == 0
+    }
+
     /// Get the PHY operation mode.
     ///
     /// * `true` configure PHY with software.
@@ -646,6 +674,129 @@ impl PhyCfg {
         self
     }
 
+    /// Force the PHY link speed, disabling auto-negotiation.
+    ///
+    /// This sets the operation mode with [`set_opmdc`](Self::set_opmdc),
+    /// keeping the duplex of the current operation mode if it is already
+    /// forced, otherwise the duplex is set to half duplex.
+    ///
+    /// The [`spd`](Self::spd) and [`dpx`](Self::dpx) status bits are
+    /// read-only, they reflect the link after the PHY applies the operation
+    /// mode.
+    ///
+    /// # Example
+    ///
+    /// Force a 100 Mbps full duplex link.
+    ///
+    /// ```
+    /// use w5500_ll::{DuplexStatus, OperationMode, PhyCfg, SpeedStatus};
+    ///
+    /// const PHY_CFG: PhyCfg = PhyCfg::DEFAULT
+    ///     .set_speed(SpeedStatus::Mbps100)
+    ///     .set_duplex(DuplexStatus::Full);
+    /// assert_eq!(PHY_CFG.opmdc(), OperationMode::FullDuplex100bt);
+    /// assert_eq!(PHY_CFG.forced_speed(), Some(SpeedStatus::Mbps100));
+    /// assert_eq!(PHY_CFG.forced_duplex(), Some(DuplexStatus::Full));
+    /// assert!(PHY_CFG.opmd());
+    /// ```
+    #[must_use = "set_speed returns a modified PhyCfg"]
+    pub const fn set_speed(self, speed: SpeedStatus) -> Self {
+        let bits: u8 = self.forced_bits() & !Self::FORCED_SPEED_BIT;
+        let bits: u8 = match speed {
+            SpeedStatus::Mbps10 => bits,
+            SpeedStatus::Mbps100 => bits | Self::FORCED_SPEED_BIT,
+        };
+        self.set_opmdc(OperationMode::from_raw(bits))
+    }
+
+    /// Force the PHY duplex, disabling auto-negotiation.
+    ///
+    /// This sets the operation mode with [`set_opmdc`](Self::set_opmdc),
+    /// keeping the speed of the current operation mode if it is already
+    /// forced, otherwise the speed is set to 10 Mbps.
+    ///
+    /// See [`set_speed`](Self::set_speed) for an example.
+    #[must_use = "set_duplex returns a modified PhyCfg"]
+    pub const fn set_duplex(self, duplex: DuplexStatus) -> Self {
+        let bits: u8 = self.forced_bits() & !Self::FORCED_DUPLEX_BIT;
+        let bits: u8 = match duplex {
+            DuplexStatus::Half => bits,
+            DuplexStatus::Full => bits | Self::FORCED_DUPLEX_BIT,
+        };
+        self.set_opmdc(OperationMode::from_raw(bits))
+    }
+
+    /// Get the forced link speed of the operation mode.
+    ///
+    /// Returns `None` if the operation mode does not force the link speed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_ll::{PhyCfg, SpeedStatus};
+    ///
+    /// assert_eq!(PhyCfg::DEFAULT.forced_speed(), None);
+    /// assert_eq!(
+    ///     PhyCfg::DEFAULT.set_speed(SpeedStatus::Mbps10).forced_speed(),
+    ///     Some(SpeedStatus::Mbps10)
+    /// );
+    /// ```
+    pub const fn forced_speed(&self) -> Option<SpeedStatus> {
+        if !self.is_forced() {
+            None
+        } else if self.forced_bits() & Self::FORCED_SPEED_BIT != 0 {
+            Some(SpeedStatus::Mbps100)
+        } else {
+            Some(SpeedStatus::Mbps10)
+        }
+    }
+
+    /// Get the forced duplex of the operation mode.
+    ///
+    /// Returns `None` if the operation mode does not force the duplex.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_ll::{DuplexStatus, PhyCfg};
+    ///
+    /// assert_eq!(PhyCfg::DEFAULT.forced_duplex(), None);
+    /// assert_eq!(
+    ///     PhyCfg::DEFAULT.set_duplex(DuplexStatus::Full).forced_duplex(),
+    ///     Some(DuplexStatus::Full)
+    /// );
+    /// ```
+    pub const fn forced_duplex(&self) -> Option<DuplexStatus> {
+        if !self.is_forced() {
+            None
+        } else if self.forced_bits() & Self::FORCED_DUPLEX_BIT != 0 {
+            Some(DuplexStatus::Full)
+        } else {
+            Some(DuplexStatus::Half)
+        }
+    }
+
+    /// Speed bit of the forced operation modes.
+    const FORCED_SPEED_BIT: u8 = 0b010;
+    /// Duplex bit of the forced operation modes.
+    const FORCED_DUPLEX_BIT: u8 = 0b001;
+
+    /// Returns `true` if the operation mode is configured by software, and
+    /// forces the link speed and duplex.
+    const fn is_forced(&self) -> bool {
+        self.opmd() && (self.opmdc() as u8) <= OperationMode::FullDuplex100bt as u8
+    }
+
+    /// The forced operation mode bits, or `0` if the operation mode is not
+    /// forced.
+    const fn forced_bits(&self) -> u8 {
+        if self.is_forced() {
+            self.opmdc() as u8
+        } else {
+            0
+        }
+    }
+
     /// Get the duplex status.
     ///
     /// # Example
@@ -730,6 +881,26 @@ impl defmt::Format for PhyCfg {
 /// This is used by the [`Registers::sn_mr`] and
 /// [`Registers::set_sn_mr`] methods.
 ///
+/// The methods that set bits take `self` by value and are `const`, they can
+/// be chained to build a value.
+///
+/// # Example
+///
+/// ```
+/// use w5500_ll::{Protocol, SocketMode};
+///
+/// const SN_MR: SocketMode = SocketMode::DEFAULT
+///     .set_protocol(Protocol::Udp)
+///     .enable_multi()
+///     .enable_bcastb()
+///     .enable_ucastb();
+/// assert_eq!(SN_MR.protocol(), Ok(Protocol::Udp));
+/// assert!(SN_MR.multi_enabled());
+/// assert!(SN_MR.bcastb_enabled());
+/// assert!(SN_MR.ucastb_enabled());
+/// assert!(!SN_MR.nd_enabled());
+/// ```
+///
 /// [`Registers::set_sn_mr`]: crate::Registers::set_sn_mr
 /// [`Registers::sn_mr`]: crate::Registers::sn_mr
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -1352,8 +1523,61 @@ impl SocketInterrupt {
     pub const fn any_raised(&self) -> bool {
         self.0 & Self::ALL_MASK != 0
     }
+
+    /// Iterate over the raised interrupts, in bit order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_ll::{SocketInterrupt, SocketInterruptKind};
+    ///
+    /// let sir: SocketInterrupt =
+    ///     SocketInterrupt::from(SocketInterrupt::CON_MASK | SocketInterrupt::RECV_MASK);
+    ///
+    /// for kind in sir.iter_raised() {
+    ///     match kind {
+    ///         SocketInterruptKind::Con => println!("connected"),
+    ///         SocketInterruptKind::Recv => println!("data received"),
+    ///         _ => {}
+    ///     }
+    /// }
+    ///
+    /// let mut iter = sir.iter_raised();
+    /// assert_eq!(iter.next(), Some(SocketInterruptKind::Con));
+    /// assert_eq!(iter.next(), Some(SocketInterruptKind::Recv));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub const fn iter_raised(&self) -> SocketInterruptIter {
+        SocketInterruptIter(self.0 & Self::ALL_MASK)
+    }
 }
 
+/// Iterator over the raised socket interrupts.
+///
+/// Returned by [`SocketInterrupt::iter_raised`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SocketInterruptIter(u8);
+
+impl Iterator for SocketInterruptIter {
+    type Item = SocketInterruptKind;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let kind: SocketInterruptKind = SocketInterruptKind::ALL
+            .into_iter()
+            .find(|kind| self.0 & kind.mask() != 0)?;
+        self.0 &= !kind.mask();
+        Some(kind)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len: usize = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for SocketInterruptIter {}
+
 impl ::core::fmt::Display for SocketInterrupt {
     fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
         f.debug_struct("SocketInterrupt")
@@ -1427,6 +1651,40 @@ impl SocketInterruptMask {
     /// ```
     pub const ALL_MASKED: SocketInterruptMask = SocketInterruptMask(0xE0);
 
+    /// Unmask all socket interrupts.
+    ///
+    /// This is the same as the reset value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_ll::SocketInterruptMask;
+    ///
+    /// assert_eq!(
+    ///     SocketInterruptMask::ALL_UNMASKED,
+    ///     SocketInterruptMask::DEFAULT
+    /// );
+    /// assert!(!SocketInterruptMask::ALL_UNMASKED.con_masked());
+    /// assert!(!SocketInterruptMask::ALL_UNMASKED.sendok_masked());
+    /// ```
+    ///
+    /// Start from [`ALL_MASKED`](Self::ALL_MASKED) to enable only some
+    /// interrupts.
+    ///
+    /// ```
+    /// use w5500_ll::SocketInterruptMask;
+    ///
+    /// const SIMR: SocketInterruptMask = SocketInterruptMask::ALL_MASKED
+    ///     .unmask_recv()
+    ///     .unmask_discon();
+    /// assert!(!SIMR.recv_masked());
+    /// assert!(!SIMR.discon_masked());
+    /// assert!(SIMR.con_masked());
+    /// assert!(SIMR.timeout_masked());
+    /// assert!(SIMR.sendok_masked());
+    /// ```
+    pub const ALL_UNMASKED: SocketInterruptMask = SocketInterruptMask(0xFF);
+
     /// Check if the `CON` interrupt is masked.
     ///
     /// # Example
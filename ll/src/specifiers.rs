@@ -1,5 +1,7 @@
 //! Register specifiers (enumerations).
 
+use crate::SocketInterrupt;
+
 /// Socket status.
 ///
 /// This is used with the [`sn_sr`] method.
@@ -7,6 +9,11 @@
 /// [`sn_sr`]: crate::Registers::sn_sr
 #[derive(Copy, Clone, Eq, PartialEq, Debug, PartialOrd, Ord, Hash, Default)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "u8", try_from = "u8")
+)]
 #[repr(u8)]
 pub enum SocketStatus {
     /// Socket closed, this is the reset state of all sockets.
@@ -137,6 +144,81 @@ pub enum SocketStatus {
     /// [`Open`]: SocketCommand::Open
     Macraw = 0x42,
 }
+impl SocketStatus {
+    /// Returns `true` if the socket is in a TCP closing state.
+    ///
+    /// This is `true` for [`FinWait`], [`Closing`], [`CloseWait`],
+    /// [`TimeWait`], and [`LastAck`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_ll::SocketStatus;
+    ///
+    /// assert!(SocketStatus::CloseWait.is_closing());
+    /// assert!(!SocketStatus::Established.is_closing());
+    /// assert!(!SocketStatus::Closed.is_closing());
+    /// ```
+    ///
+    /// [`FinWait`]: SocketStatus::FinWait
+    /// [`Closing`]: SocketStatus::Closing
+    /// [`CloseWait`]: SocketStatus::CloseWait
+    /// [`TimeWait`]: SocketStatus::TimeWait
+    /// [`LastAck`]: SocketStatus::LastAck
+    pub const fn is_closing(&self) -> bool {
+        matches!(
+            self,
+            Self::FinWait | Self::Closing | Self::CloseWait | Self::TimeWait | Self::LastAck
+        )
+    }
+
+    /// Returns `true` if the socket is open and can transfer data.
+    ///
+    /// This is `true` for [`Established`], [`Udp`], and [`Macraw`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_ll::SocketStatus;
+    ///
+    /// assert!(SocketStatus::Established.is_open());
+    /// assert!(SocketStatus::Udp.is_open());
+    /// assert!(!SocketStatus::Init.is_open());
+    /// ```
+    ///
+    /// [`Established`]: SocketStatus::Established
+    /// [`Udp`]: SocketStatus::Udp
+    /// [`Macraw`]: SocketStatus::Macraw
+    pub const fn is_open(&self) -> bool {
+        matches!(self, Self::Established | Self::Udp | Self::Macraw)
+    }
+
+    /// Returns `true` if the socket is in a TCP connecting state.
+    ///
+    /// This is `true` for [`Init`], [`Listen`], [`SynSent`], and
+    /// [`SynRecv`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_ll::SocketStatus;
+    ///
+    /// assert!(SocketStatus::SynSent.is_connecting());
+    /// assert!(SocketStatus::Listen.is_connecting());
+    /// assert!(!SocketStatus::Established.is_connecting());
+    /// ```
+    ///
+    /// [`Init`]: SocketStatus::Init
+    /// [`Listen`]: SocketStatus::Listen
+    /// [`SynSent`]: SocketStatus::SynSent
+    /// [`SynRecv`]: SocketStatus::SynRecv
+    pub const fn is_connecting(&self) -> bool {
+        matches!(
+            self,
+            Self::Init | Self::Listen | Self::SynSent | Self::SynRecv
+        )
+    }
+}
 impl From<SocketStatus> for u8 {
     fn from(val: SocketStatus) -> u8 {
         val as u8
@@ -180,6 +262,11 @@ impl TryFrom<u8> for SocketStatus {
 /// [`sn_sr`]: crate::Registers::sn_sr
 #[derive(Copy, Clone, Eq, PartialEq, Debug, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "u8", try_from = "u8")
+)]
 #[repr(u8)]
 pub enum SocketCommand {
     /// The command register clears to this state once a command has been
@@ -356,6 +443,11 @@ impl TryFrom<u8> for SocketCommand {
 /// [`sn_mr`]: crate::Registers::sn_mr
 #[derive(Copy, Clone, Eq, PartialEq, Debug, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "u8", try_from = "u8")
+)]
 #[repr(u8)]
 pub enum Protocol {
     /// Closed.
@@ -423,6 +515,11 @@ impl TryFrom<u8> for Protocol {
 /// [`phycfgr`]: crate::Registers::phycfgr
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "u8", try_from = "u8")
+)]
 #[repr(u8)]
 pub enum OperationMode {
     /// 10BT half-duplex. Auto-negotiation disabled.
@@ -496,6 +593,21 @@ impl Default for OperationMode {
         Self::Auto
     }
 }
+impl TryFrom<u8> for OperationMode {
+    type Error = u8;
+    fn try_from(val: u8) -> Result<Self, u8> {
+        match val {
+            x if x == Self::HalfDuplex10bt as u8 => Ok(Self::HalfDuplex10bt),
+            x if x == Self::FullDuplex10bt as u8 => Ok(Self::FullDuplex10bt),
+            x if x == Self::HalfDuplex100bt as u8 => Ok(Self::HalfDuplex100bt),
+            x if x == Self::FullDuplex100bt as u8 => Ok(Self::FullDuplex100bt),
+            x if x == Self::HalfDuplex100btAuto as u8 => Ok(Self::HalfDuplex100btAuto),
+            x if x == Self::PowerDown as u8 => Ok(Self::PowerDown),
+            x if x == Self::Auto as u8 => Ok(Self::Auto),
+            _ => Err(val),
+        }
+    }
+}
 
 /// PHY link status.
 ///
@@ -505,6 +617,11 @@ impl Default for OperationMode {
 /// [`phycfgr`]: crate::Registers::phycfgr
 #[derive(Copy, Clone, Eq, PartialEq, Debug, PartialOrd, Ord, Hash, Default)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "u8", try_from = "u8")
+)]
 #[repr(u8)]
 pub enum LinkStatus {
     /// PHY link down.
@@ -527,6 +644,80 @@ impl From<LinkStatus> for u8 {
         val as u8
     }
 }
+impl TryFrom<u8> for LinkStatus {
+    type Error = u8;
+    fn try_from(val: u8) -> Result<Self, u8> {
+        match val {
+            0 => Ok(Self::Down),
+            1 => Ok(Self::Up),
+            _ => Err(val),
+        }
+    }
+}
+
+/// Socket interrupt kind.
+///
+/// This is yielded by [`SocketInterrupt::iter_raised`] for the [`sn_ir`]
+/// register.
+///
+/// [`SocketInterrupt::iter_raised`]: crate::SocketInterrupt::iter_raised
+/// [`sn_ir`]: crate::Registers::sn_ir
+#[derive(Copy, Clone, Eq, PartialEq, Debug, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SocketInterruptKind {
+    /// `CON` interrupt, see [`SocketInterrupt::con_raised`].
+    ///
+    /// [`SocketInterrupt::con_raised`]: crate::SocketInterrupt::con_raised
+    Con,
+    /// `DISCON` interrupt, see [`SocketInterrupt::discon_raised`].
+    ///
+    /// [`SocketInterrupt::discon_raised`]: crate::SocketInterrupt::discon_raised
+    Discon,
+    /// `RECV` interrupt, see [`SocketInterrupt::recv_raised`].
+    ///
+    /// [`SocketInterrupt::recv_raised`]: crate::SocketInterrupt::recv_raised
+    Recv,
+    /// `TIMEOUT` interrupt, see [`SocketInterrupt::timeout_raised`].
+    ///
+    /// [`SocketInterrupt::timeout_raised`]: crate::SocketInterrupt::timeout_raised
+    Timeout,
+    /// `SENDOK` interrupt, see [`SocketInterrupt::sendok_raised`].
+    ///
+    /// [`SocketInterrupt::sendok_raised`]: crate::SocketInterrupt::sendok_raised
+    SendOk,
+}
+impl SocketInterruptKind {
+    /// All socket interrupt kinds, in bit order.
+    pub const ALL: [Self; 5] = [
+        Self::Con,
+        Self::Discon,
+        Self::Recv,
+        Self::Timeout,
+        Self::SendOk,
+    ];
+
+    /// Bit mask for the interrupt in the [`sn_ir`] and [`sn_imr`] registers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_ll::{SocketInterrupt, SocketInterruptKind};
+    ///
+    /// assert_eq!(SocketInterruptKind::Recv.mask(), SocketInterrupt::RECV_MASK);
+    /// ```
+    ///
+    /// [`sn_ir`]: crate::Registers::sn_ir
+    /// [`sn_imr`]: crate::Registers::sn_imr
+    pub const fn mask(self) -> u8 {
+        match self {
+            Self::Con => SocketInterrupt::CON_MASK,
+            Self::Discon => SocketInterrupt::DISCON_MASK,
+            Self::Recv => SocketInterrupt::RECV_MASK,
+            Self::Timeout => SocketInterrupt::TIMEOUT_MASK,
+            Self::SendOk => SocketInterrupt::SENDOK_MASK,
+        }
+    }
+}
 
 /// PHY speed status.
 ///
@@ -536,6 +727,11 @@ impl From<LinkStatus> for u8 {
 /// [`phycfgr`]: crate::Registers::phycfgr
 #[derive(Copy, Clone, Eq, PartialEq, Debug, PartialOrd, Ord, Hash, Default)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "u8", try_from = "u8")
+)]
 #[repr(u8)]
 pub enum SpeedStatus {
     /// 10 Mbps.
@@ -558,6 +754,16 @@ impl From<SpeedStatus> for u8 {
         val as u8
     }
 }
+impl TryFrom<u8> for SpeedStatus {
+    type Error = u8;
+    fn try_from(val: u8) -> Result<Self, u8> {
+        match val {
+            0 => Ok(Self::Mbps10),
+            1 => Ok(Self::Mbps100),
+            _ => Err(val),
+        }
+    }
+}
 
 /// PHY duplex status.
 ///
@@ -567,6 +773,11 @@ impl From<SpeedStatus> for u8 {
 /// [`phycfgr`]: crate::Registers::phycfgr
 #[derive(Copy, Clone, Eq, PartialEq, Debug, PartialOrd, Ord, Hash, Default)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "u8", try_from = "u8")
+)]
 #[repr(u8)]
 pub enum DuplexStatus {
     /// Half duplex.
@@ -589,6 +800,16 @@ impl From<DuplexStatus> for u8 {
         val as u8
     }
 }
+impl TryFrom<u8> for DuplexStatus {
+    type Error = u8;
+    fn try_from(val: u8) -> Result<Self, u8> {
+        match val {
+            0 => Ok(Self::Half),
+            1 => Ok(Self::Full),
+            _ => Err(val),
+        }
+    }
+}
 
 /// RX and TX buffer sizes.
 ///
@@ -599,6 +820,11 @@ impl From<DuplexStatus> for u8 {
 /// [`Registers::set_sn_rxbuf_size`]: crate::Registers::set_sn_rxbuf_size
 #[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Hash, Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "u8", try_from = "u8")
+)]
 #[repr(u8)]
 #[allow(clippy::upper_case_acronyms)]
 pub enum BufferSize {
@@ -706,4 +932,33 @@ impl BufferSize {
             BufferSize::KB16 => 16384,
         }
     }
+
+    /// Get the buffer size from a size in bytes.
+    ///
+    /// Returns `Err(bytes)` if `bytes` is not a valid buffer size.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_ll::BufferSize;
+    ///
+    /// assert_eq!(BufferSize::from_bytes(0), Ok(BufferSize::KB0));
+    /// assert_eq!(BufferSize::from_bytes(1024), Ok(BufferSize::KB1));
+    /// assert_eq!(BufferSize::from_bytes(2048), Ok(BufferSize::KB2));
+    /// assert_eq!(BufferSize::from_bytes(4096), Ok(BufferSize::KB4));
+    /// assert_eq!(BufferSize::from_bytes(8192), Ok(BufferSize::KB8));
+    /// assert_eq!(BufferSize::from_bytes(16384), Ok(BufferSize::KB16));
+    /// assert_eq!(BufferSize::from_bytes(1000), Err(1000));
+    /// ```
+    pub const fn from_bytes(bytes: usize) -> Result<Self, usize> {
+        match bytes {
+            0 => Ok(BufferSize::KB0),
+            1024 => Ok(BufferSize::KB1),
+            2048 => Ok(BufferSize::KB2),
+            4096 => Ok(BufferSize::KB4),
+            8192 => Ok(BufferSize::KB8),
+            16384 => Ok(BufferSize::KB16),
+            _ => Err(bytes),
+        }
+    }
 }
@@ -0,0 +1,38 @@
+use w5500_ll::aio::Registers;
+use w5500_ll::eh1::fdm::W5500;
+
+#[tokio::test]
+async fn fdm_write_chunk() {
+    const DATA: [u8; 7] = [0x01, 0x23, 0x45, 0x56, 0x78, 0x9A, 0xBC];
+    const ADDRESS: u16 = 0xFFFE;
+    let spi = ehm::eh1::spi::Mock::new(&[
+        ehm::eh1::spi::Transaction::write_vec(vec![0xFF, 0xFE, 0b111]),
+        ehm::eh1::spi::Transaction::write_vec(vec![0x01, 0x23, 0x45, 0x56]),
+        ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x02, 0b110]),
+        ehm::eh1::spi::Transaction::write_vec(vec![0x78, 0x9A]),
+        ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x04, 0b101]),
+        ehm::eh1::spi::Transaction::write_vec(vec![0xBC]),
+    ]);
+    let mut w5500 = W5500::new(spi);
+    w5500.write(ADDRESS, 0, &DATA).await.unwrap();
+    w5500.free().done();
+}
+
+#[tokio::test]
+async fn fdm_read_chunk() {
+    const DATA: [u8; 7] = [0x01, 0x23, 0x45, 0x56, 0x78, 0x9A, 0xBC];
+    const ADDRESS: u16 = 0xFFFA;
+    let spi = ehm::eh1::spi::Mock::new(&[
+        ehm::eh1::spi::Transaction::write_vec(vec![0xFF, 0xFA, 0b11]),
+        ehm::eh1::spi::Transaction::read_vec(vec![0x01, 0x23, 0x45, 0x56]),
+        ehm::eh1::spi::Transaction::write_vec(vec![0xFF, 0xFE, 0b10]),
+        ehm::eh1::spi::Transaction::read_vec(vec![0x78, 0x9A]),
+        ehm::eh1::spi::Transaction::write_vec(vec![0x00, 0x00, 0b01]),
+        ehm::eh1::spi::Transaction::read_vec(vec![0xBC]),
+    ]);
+    let mut w5500 = W5500::new(spi);
+    let mut buf: [u8; 7] = [0; 7];
+    w5500.read(ADDRESS, 0, &mut buf).await.unwrap();
+    assert_eq!(buf, DATA);
+    w5500.free().done();
+}
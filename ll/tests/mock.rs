@@ -0,0 +1,55 @@
+#![cfg(feature = "test-util")]
+
+use w5500_ll::{mock::MockW5500, BufferSize, Registers, Sn, SocketStatus, VERSION};
+
+#[test]
+fn reset_values() {
+    let mut w5500 = MockW5500::new();
+    assert_eq!(w5500.version().unwrap(), VERSION);
+    assert_eq!(w5500.rtr().unwrap(), 0x07D0);
+    assert_eq!(w5500.rcr().unwrap(), 0x08);
+    for sn in Sn::iter() {
+        assert_eq!(w5500.sn_sr(*sn).unwrap(), Ok(SocketStatus::Closed));
+        assert_eq!(w5500.sn_ttl(*sn).unwrap(), 0x80);
+        assert_eq!(w5500.sn_rxbuf_size(*sn).unwrap(), Ok(BufferSize::KB2));
+        assert_eq!(w5500.sn_tx_fsr(*sn).unwrap(), 0x0800);
+        assert_eq!(w5500.sn_frag(*sn).unwrap(), 0x4000);
+    }
+}
+
+#[test]
+fn buffer_wraps() {
+    let mut w5500 = MockW5500::new();
+    w5500.set_sn_tx_buf(Sn::Sn1, 0xFFFE, &[1, 2, 3, 4]).unwrap();
+
+    let mut buf: [u8; 4] = [0; 4];
+    w5500.sn_tx_buf(Sn::Sn1, 0xFFFE, &mut buf).unwrap();
+    assert_eq!(buf, [1, 2, 3, 4]);
+    w5500.sn_tx_buf(Sn::Sn1, 0x0000, &mut buf[..2]).unwrap();
+    assert_eq!(buf[..2], [3, 4]);
+
+    // other sockets and the RX buffer are independent
+    w5500.sn_tx_buf(Sn::Sn0, 0x0000, &mut buf).unwrap();
+    assert_eq!(buf, [0; 4]);
+    w5500.sn_rx_buf(Sn::Sn1, 0x0000, &mut buf).unwrap();
+    assert_eq!(buf, [0; 4]);
+}
+
+#[test]
+#[should_panic]
+fn register_out_of_bounds() {
+    let mut w5500 = MockW5500::new();
+    let mut buf: [u8; 2] = [0; 2];
+    w5500.read(0x0039, 0x00, &mut buf).unwrap();
+}
+
+#[tokio::test]
+async fn aio() {
+    use w5500_ll::aio::Registers;
+
+    let mut w5500 = MockW5500::new();
+    Registers::set_sn_port(&mut w5500, Sn::Sn7, 8080)
+        .await
+        .unwrap();
+    assert_eq!(Registers::sn_port(&mut w5500, Sn::Sn7).await.unwrap(), 8080);
+}
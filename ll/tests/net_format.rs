@@ -8,3 +8,32 @@ fn mac_format() {
     );
     assert_eq!(format!("{}", Eui48Addr::UNSPECIFIED), "00:00:00:00:00:00")
 }
+
+#[test]
+fn mac_parse() {
+    use w5500_ll::net::Eui48AddrParseError;
+
+    const ADDR: Eui48Addr = Eui48Addr::new(0x01, 0x23, 0x45, 0x67, 0x89, 0xAB);
+    assert_eq!("01:23:45:67:89:AB".parse(), Ok(ADDR));
+    assert_eq!("01:23:45:67:89:ab".parse(), Ok(ADDR));
+    assert_eq!(ADDR.to_string().parse(), Ok(ADDR));
+
+    for invalid in [
+        "",
+        "01:23:45:67:89",
+        "01:23:45:67:89:AB:CD",
+        "01:23:45:67:89:A",
+        "01:23:45:67:89:ABC",
+        "01-23-45-67-89-AB",
+        "01:23:45:67:89:+A",
+        "01:23:45:67:89:AB:",
+    ] {
+        assert!(
+            matches!(
+                invalid.parse::<Eui48Addr>(),
+                Err(Eui48AddrParseError { .. })
+            ),
+            "{invalid}"
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use core::convert::Infallible;
+use w5500_ll::{ProbeResult, Reg, Registers, COMMON_BLOCK_OFFSET, VERSION};
+
+/// W5500 with a scratch register that does not retain writes.
+struct MockRegisters {
+    version: u8,
+}
+
+impl Registers for MockRegisters {
+    type Error = Infallible;
+
+    fn read(&mut self, address: u16, block: u8, data: &mut [u8]) -> Result<(), Self::Error> {
+        assert_eq!(block, COMMON_BLOCK_OFFSET);
+        data.fill(if address == Reg::VERSIONR.addr() {
+            self.version
+        } else {
+            0x00
+        });
+        Ok(())
+    }
+
+    fn write(&mut self, _address: u16, block: u8, _data: &[u8]) -> Result<(), Self::Error> {
+        assert_eq!(block, COMMON_BLOCK_OFFSET);
+        Ok(())
+    }
+}
+
+#[test]
+fn probe_scratch_failed() {
+    let mut mock = MockRegisters { version: VERSION };
+    let probe: ProbeResult = mock.probe().unwrap();
+    assert!(probe.version_matches());
+    assert!(!probe.scratch);
+    assert!(!probe.is_ok());
+}
+
+#[test]
+fn probe_version_mismatch() {
+    let mut mock = MockRegisters { version: 0xFF };
+    let probe: ProbeResult = mock.probe().unwrap();
+    assert_eq!(probe.version, 0xFF);
+    assert!(!probe.version_matches());
+    assert!(!probe.is_ok());
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "serde")]
+
+use serde_test::{assert_de_tokens_error, assert_tokens, Configure, Token};
+use w5500_ll::{
+    net::{Eui48Addr, Ipv4Addr},
+    BufferSize, LinkStatus, OperationMode, SocketStatus,
+};
+
+const MAC: Eui48Addr = Eui48Addr::new(0x01, 0x23, 0x45, 0x67, 0x89, 0xAB);
+
+#[test]
+fn mac_compact() {
+    assert_tokens(
+        &MAC.compact(),
+        &[
+            Token::Tuple { len: 6 },
+            Token::U8(0x01),
+            Token::U8(0x23),
+            Token::U8(0x45),
+            Token::U8(0x67),
+            Token::U8(0x89),
+            Token::U8(0xAB),
+            Token::TupleEnd,
+        ],
+    );
+}
+
+#[test]
+fn mac_readable() {
+    assert_tokens(&MAC.readable(), &[Token::Str("01:23:45:67:89:AB")]);
+    assert_de_tokens_error::<serde_test::Readable<Eui48Addr>>(
+        &[Token::Str("01:23:45:67:89")],
+        "invalid EUI-48 address syntax",
+    );
+}
+
+#[test]
+fn ipv4_compact() {
+    assert_tokens(
+        &Ipv4Addr::new(192, 168, 0, 1).compact(),
+        &[
+            Token::Tuple { len: 4 },
+            Token::U8(192),
+            Token::U8(168),
+            Token::U8(0),
+            Token::U8(1),
+            Token::TupleEnd,
+        ],
+    );
+}
+
+#[test]
+fn enums() {
+    assert_tokens(&SocketStatus::Established, &[Token::U8(0x17)]);
+    assert_tokens(&BufferSize::KB16, &[Token::U8(16)]);
+    assert_tokens(&LinkStatus::Up, &[Token::U8(1)]);
+    assert_tokens(&OperationMode::PowerDown, &[Token::U8(0b110)]);
+    assert_de_tokens_error::<SocketStatus>(&[Token::U8(0xFF)], "255");
+    assert_de_tokens_error::<OperationMode>(&[Token::U8(0b101)], "5");
+}
@@ -0,0 +1,57 @@
+use w5500_ll::{Protocol, SocketMode};
+
+const PROTOCOLS: [Protocol; 4] = [
+    Protocol::Closed,
+    Protocol::Tcp,
+    Protocol::Udp,
+    Protocol::Macraw,
+];
+
+#[test]
+fn protocol_round_trip() {
+    for protocol in PROTOCOLS {
+        assert_eq!(Protocol::try_from(u8::from(protocol)), Ok(protocol));
+    }
+
+    (0..=u8::MAX)
+        .filter(|val| !PROTOCOLS.iter().any(|p| u8::from(*p) == *val))
+        .for_each(|val| assert_eq!(Protocol::try_from(val), Err(val)));
+}
+
+#[test]
+fn socket_mode_round_trip() {
+    for val in 0..=u8::MAX {
+        assert_eq!(u8::from(SocketMode::from(val)), val);
+    }
+}
+
+#[test]
+fn socket_mode_protocol_preserves_flags() {
+    for protocol in PROTOCOLS {
+        let sn_mr: SocketMode = SocketMode::from(0xF0).set_protocol(protocol);
+        assert_eq!(sn_mr.protocol(), Ok(protocol));
+        assert_eq!(u8::from(sn_mr) & 0xF0, 0xF0);
+    }
+}
+
+#[test]
+fn socket_mode_flags() {
+    let sn_mr: SocketMode = SocketMode::DEFAULT
+        .set_protocol(Protocol::Udp)
+        .enable_multi()
+        .enable_bcastb()
+        .enable_mmb()
+        .enable_ucastb();
+    assert_eq!(u8::from(sn_mr), 0xF2);
+    assert!(sn_mr.multi_enabled());
+    assert!(sn_mr.bcastb_enabled());
+    assert!(sn_mr.mmb_enabled());
+    assert!(sn_mr.ucastb_enabled());
+
+    let sn_mr: SocketMode = sn_mr
+        .disable_multi()
+        .disable_bcastb()
+        .disable_mmb()
+        .disable_ucastb();
+    assert_eq!(sn_mr, SocketMode::DEFAULT.set_protocol(Protocol::Udp));
+}
@@ -51,6 +51,8 @@
 //! * `log`: Enable logging with `log`.
 //! * `w5500-tls`: Enable MQTT over TLS.
 //! * `p256-cm4`: Passthrough to [`w5500-tls`].
+//! * `x25519`: Passthrough to [`w5500-tls`].
+//! * `chacha20poly1305`: Passthrough to [`w5500-tls`].
 //!
 //! [`w5500-hl`]: https://crates.io/crates/w5500-hl
 //! [`w5500-tls`]: https://crates.io/crates/w5500-tls
@@ -193,7 +193,7 @@ impl<'id, 'hn, 'psk, 'b, const N: usize> Client<'id, 'hn, 'psk, 'b, N> {
                     return Ok(Event::CallAfter(call_after));
                 }
                 Ok(TlsEvent::Disconnect) => return Err(Error::Disconnect),
-                Ok(TlsEvent::None) => break,
+                Ok(TlsEvent::Idle) | Ok(TlsEvent::None) => break,
             }
         }
 
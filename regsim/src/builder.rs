@@ -0,0 +1,137 @@
+//! Builder for a simulated W5500 with preloaded register values.
+
+use crate::W5500;
+use w5500_ll::{
+    net::{Eui48Addr, Ipv4Addr},
+    BufferSize, Sn, SOCKETS,
+};
+
+/// Builder for a [`W5500`] with preloaded register values.
+///
+/// Registers that are not set keep their reset values.
+///
+/// # Example
+///
+/// ```
+/// use w5500_regsim::{
+///     w5500_ll::{
+///         net::{Eui48Addr, Ipv4Addr},
+///         BufferSize, Registers, Sn,
+///     },
+///     W5500Builder, W5500,
+/// };
+///
+/// let mut w5500: W5500 = W5500Builder::new()
+///     .sipr(Ipv4Addr::new(192, 168, 1, 10))
+///     .subr(Ipv4Addr::new(255, 255, 255, 0))
+///     .gar(Ipv4Addr::new(192, 168, 1, 1))
+///     .shar(Eui48Addr::new(0x02, 0x00, 0x11, 0x22, 0x33, 0x44))
+///     .sn_rxbuf_size(Sn::Sn0, BufferSize::KB16)
+///     .build();
+///
+/// assert_eq!(w5500.sipr()?, Ipv4Addr::new(192, 168, 1, 10));
+/// assert_eq!(w5500.sn_rxbuf_size(Sn::Sn0)?, Ok(BufferSize::KB16));
+/// # Ok::<(), std::io::ErrorKind>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct W5500Builder {
+    w5500: W5500,
+}
+
+impl W5500Builder {
+    /// Create a new builder with all registers at their reset values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the source IP address.
+    #[must_use = "sipr returns a modified W5500Builder"]
+    pub fn sipr(mut self, sipr: Ipv4Addr) -> Self {
+        self.w5500.regs.sipr = sipr;
+        self
+    }
+
+    /// Set the source hardware address.
+    #[must_use = "shar returns a modified W5500Builder"]
+    pub fn shar(mut self, shar: Eui48Addr) -> Self {
+        self.w5500.regs.shar = shar;
+        self
+    }
+
+    /// Set the gateway IP address.
+    #[must_use = "gar returns a modified W5500Builder"]
+    pub fn gar(mut self, gar: Ipv4Addr) -> Self {
+        self.w5500.regs.gar = gar;
+        self
+    }
+
+    /// Set the subnet mask.
+    #[must_use = "subr returns a modified W5500Builder"]
+    pub fn subr(mut self, subr: Ipv4Addr) -> Self {
+        self.w5500.regs.subr = subr;
+        self
+    }
+
+    /// Set the RX buffer size of a socket.
+    #[must_use = "sn_rxbuf_size returns a modified W5500Builder"]
+    pub fn sn_rxbuf_size(mut self, sn: Sn, size: BufferSize) -> Self {
+        let socket = self.w5500.socket_mut(sn);
+        socket.regs.rxbuf_size = size;
+        socket.rx_buf.resize(size.size_in_bytes(), 0);
+        self
+    }
+
+    /// Set the TX buffer size of a socket.
+    #[must_use = "sn_txbuf_size returns a modified W5500Builder"]
+    pub fn sn_txbuf_size(mut self, sn: Sn, size: BufferSize) -> Self {
+        let socket = self.w5500.socket_mut(sn);
+        socket.regs.txbuf_size = size;
+        socket.regs.tx_fsr = u16::try_from(size.size_in_bytes()).unwrap_or(u16::MAX);
+        socket.tx_buf.resize(size.size_in_bytes(), 0);
+        self
+    }
+
+    /// Set the RX and TX buffer sizes of all sockets, indexed by socket number.
+    ///
+    /// This is useful to match the buffer layout that firmware configures at
+    /// startup, without the firmware writing the buffer size registers.
+    ///
+    /// The sizes are not checked against the 16 KiB of buffer memory in the
+    /// W5500.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_regsim::{
+    ///     w5500_ll::{BufferSize, Registers, Sn},
+    ///     W5500Builder, W5500,
+    /// };
+    ///
+    /// let mut rx: [BufferSize; 8] = [BufferSize::KB0; 8];
+    /// rx[0] = BufferSize::KB8;
+    /// rx[1] = BufferSize::KB8;
+    /// let tx: [BufferSize; 8] = [BufferSize::KB2; 8];
+    ///
+    /// let mut w5500: W5500 = W5500Builder::new().buffer_sizes(rx, tx).build();
+    /// assert_eq!(w5500.sn_rxbuf_size(Sn::Sn1)?, Ok(BufferSize::KB8));
+    /// assert_eq!(w5500.sn_rxbuf_size(Sn::Sn2)?, Ok(BufferSize::KB0));
+    /// assert_eq!(w5500.sn_txbuf_size(Sn::Sn7)?, Ok(BufferSize::KB2));
+    /// # Ok::<(), std::io::ErrorKind>(())
+    /// ```
+    #[must_use = "buffer_sizes returns a modified W5500Builder"]
+    pub fn buffer_sizes(
+        mut self,
+        rx: [BufferSize; SOCKETS.len()],
+        tx: [BufferSize; SOCKETS.len()],
+    ) -> Self {
+        for ((sn, rx), tx) in SOCKETS.iter().zip(rx).zip(tx) {
+            self = self.sn_rxbuf_size(*sn, rx).sn_txbuf_size(*sn, tx);
+        }
+        self
+    }
+
+    /// Build the simulated W5500.
+    pub fn build(self) -> W5500 {
+        self.w5500
+    }
+}
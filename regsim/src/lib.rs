@@ -13,12 +13,15 @@
 //! ## Not-implemented
 //!
 //! * MR (Mode Register)
-//!     * Wake on LAN
+//!     * Wake on LAN; partial, only magic packets received by a UDP socket
+//!       raise the MP interrupt
 //!     * Ping block
 //!     * PPPoE mode
-//!     * Force ARP
+//!     * Force ARP; partial, there is no ARP exchange, SEND sets SN_DHAR to
+//!       a synthetic address of `02:00:` followed by the destination IP
 //! * INTLEVEL (Interrupt Low Level Timer Register)
 //! * IR (Interrupt Register)
+//!     * Partial; only UNREACH and MP, see UIPR and MR
 //! * IMR (Interrupt Mask Register)
 //! * GAR (Gateway IP Address Register)
 //! * SUBR (Subnet Mask Register)
@@ -30,28 +33,37 @@
 //! * SIR (Socket Interrupt Register)
 //!     * Partial; see SN_IR
 //! * SIMR (Socket Interrupt Mask Register)
+//!     * Partial; only the INTn pin is simulated, see [`W5500::int_pin_asserted`]
 //! * RTR (Retry Time Register)
+//!     * Partial; only used for the TCP CONNECT timeout
 //! * RCR (Retry Count Register)
+//!     * Partial; only used for the TCP CONNECT timeout
 //! * PTIMER (PPP LCP Request Timer Register)
 //! * PMAGIC (PPP LCP Magic Number Register)
 //! * PHAR (PPP Destination MAC Address Register)
 //! * PSID (PPP Session Identification Register)
 //! * PMRU (PPP Maximum Segment Size Register)
 //! * UIPR (Unreachable IP Address Register)
+//!     * Partial; ICMP destination unreachable messages are not visible to
+//!       the host network stack, only UDP datagrams sent to a closed port on
+//!       the local host are detected
 //! * UPORT (Unreachable Port Register)
+//!     * Partial; see UIPR
 //! * PHYCFGR (PHY Configuration Register)
+//!     * Partial; only the link status is simulated, see [`W5500::set_link`]
 //! * SN_MR (Socket n Mode Register)
+//!     * Partial; only the protocol and UDP multicast are implemented
+//!     * The MACRAW filter bits (MFEN, BCASTB, MMB, MIP6B) are not implemented
+//!       because MACRAW sockets are not simulated
 //! * SN_IR (Socket n Interrupt Register)
-//!     * DISCON
-//!     * TIMEOUT
+//!     * TIMEOUT; partial, only raised when a TCP CONNECT fails
 //!     * SENDOK
 //! * SN_SR (Socket n Status Register)
-//!     * SynSent
+//!     * SynSent; partial, only used while a TCP CONNECT is pending
 //!     * SynRecv
 //!     * FinWait
 //!     * Closing
 //!     * TimeWait
-//!     * CloseWait
 //!     * LastAck
 //!     * Macraw
 //! * SN_MSSR (Socket n Maximum Segment Size Register)
@@ -62,6 +74,13 @@
 //!
 //! Believe it or not that is not simply a list of all registers.
 //!
+//! ## Socket Buffers
+//!
+//! Like the W5500 socket buffer addresses wrap at the configured buffer size,
+//! an access past the end of a buffer continues at the start of the buffer.
+//! Accesses to a socket buffer with a size of 0 KiB read zeros and discard
+//! writes.
+//!
 //! [Wiznet W5500]: https://docs.wiznet.io/Product/iEthernet/W5500/overview
 //! [`std::net`]: https://doc.rust-lang.org/std/net/index.html
 //! [`w5500-hl`]: https://crates.io/crates/w5500-hl
@@ -69,25 +88,104 @@
 #![cfg_attr(docsrs, feature(doc_cfg), feature(doc_auto_cfg))]
 
 use std::{
+    collections::VecDeque,
     fs::File,
     io::{self, Read, Write},
-    net::{SocketAddrV4, TcpListener, TcpStream, UdpSocket},
+    net::{Shutdown, SocketAddr, SocketAddrV4, TcpListener, TcpStream, UdpSocket},
+    path::Path,
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+    time::{Duration, Instant},
 };
 
 pub use w5500_ll::{self, Registers};
 
 use w5500_ll::{
-    net::{Eui48Addr, Ipv4Addr},
-    BufferSize, Mode, Protocol, Reg, Sn, SnReg, SocketCommand, SocketInterrupt, SocketMode,
-    SocketStatus, SOCKETS, VERSION,
+    net::{self, Eui48Addr, Ipv4Addr},
+    BufferSize, Interrupt, Mode, PhyCfg, Protocol, Reg, Sn, SnReg, SocketCommand, SocketInterrupt,
+    SocketMode, SocketStatus, SOCKETS, VERSION,
 };
 
+mod builder;
+mod pcap;
+
+pub use builder::W5500Builder;
+use pcap::{Frame, Pcap};
+
 // Socket spacing between blocks.
 const SOCKET_SPACING: u8 = 0x04;
 
 const NUM_SOCKETS: usize = SOCKETS.len();
 const DEFAULT_BUF_SIZE: usize = BufferSize::KB2.size_in_bytes();
 
+/// Protocol, local address, and remote address of captured data.
+type Capture = (Protocol, SocketAddrV4, SocketAddrV4);
+
+fn socket_addr_v4(addr: io::Result<SocketAddr>) -> SocketAddrV4 {
+    match addr {
+        Ok(SocketAddr::V4(addr)) => addr,
+        _ => SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0),
+    }
+}
+
+fn tcp_capture(stream: &TcpStream) -> Capture {
+    (
+        Protocol::Tcp,
+        socket_addr_v4(stream.local_addr()),
+        socket_addr_v4(stream.peer_addr()),
+    )
+}
+
+/// Index of a socket buffer address.
+///
+/// Like the W5500 the address wraps at the buffer size, this returns `None`
+/// for a buffer size of 0 KiB, where reads return zero and writes are
+/// discarded.
+fn sim_buf_idx(buf: &[u8], addr: u16) -> Option<usize> {
+    usize::from(addr).checked_rem(buf.len())
+}
+
+/// Format a socket buffer transfer as hex and ASCII, for example
+/// `48 69 0A |Hi.|`.
+fn hexdump(data: &[u8]) -> String {
+    let hex: Vec<String> = data.iter().map(|byte| format!("{byte:02X}")).collect();
+    let ascii: String = data
+        .iter()
+        .map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                char::from(byte)
+            } else {
+                '.'
+            }
+        })
+        .collect();
+    format!("{} |{ascii}|", hex.join(" "))
+}
+
+/// Returns `true` if `dest` is a closed UDP port on the local host.
+///
+/// Binding to a remote address fails, and binding to a local address fails
+/// if the port is in use, if the bind succeeds no socket is listening.
+fn udp_port_closed(dest: SocketAddrV4) -> bool {
+    let ip: &Ipv4Addr = dest.ip();
+    !ip.is_unspecified()
+        && !ip.is_broadcast()
+        && !ip.is_multicast()
+        && UdpSocket::bind(dest).is_ok()
+}
+
+/// Returns `true` if `payload` contains a wake on LAN magic packet for `mac`.
+///
+/// A magic packet is 6 bytes of `0xFF` followed by 16 repetitions of the
+/// MAC address, anywhere in the payload.
+fn is_magic_packet(payload: &[u8], mac: &Eui48Addr) -> bool {
+    const LEN: usize = 6 + 16 * 6;
+    payload.windows(LEN).any(|window| {
+        let (sync, macs) = window.split_at(6);
+        sync.iter().all(|&b| b == 0xFF) && macs.chunks_exact(6).all(|m| m == mac.octets)
+    })
+}
+
 #[derive(Debug)]
 enum SocketType {
     Udp(UdpSocket),
@@ -256,6 +354,64 @@ impl SocketRegs {
     }
 }
 
+/// Minimal xorshift PRNG, used to make the simulation reproducible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct XorShift(u64);
+
+impl Default for XorShift {
+    fn default() -> Self {
+        Self(0x2545_F491_4F6C_DD1D)
+    }
+}
+
+impl XorShift {
+    fn next_u64(&mut self) -> u64 {
+        let mut x: u64 = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+}
+
+/// Socket buffer log style, set with [`W5500::set_buffer_log_style`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BufferLogStyle {
+    /// Log each byte on a separate line.
+    #[default]
+    PerByte,
+    /// Log each transfer on a single line, with the address range, the data
+    /// in hex, and the data in ASCII.
+    Hexdump,
+}
+
+/// Per-socket traffic statistics, returned by [`W5500::stats`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SocketStats {
+    /// Number of `SEND` and `SEND_MAC` commands issued.
+    pub send_cmds: u64,
+    /// Number of `RECV` commands issued.
+    pub recv_cmds: u64,
+    /// Number of bytes sent to the network.
+    pub tx_bytes: u64,
+    /// Number of bytes received into the socket RX buffer, excluding the UDP
+    /// header added by the W5500.
+    pub rx_bytes: u64,
+    /// Number of UDP datagrams sent.
+    pub tx_datagrams: u64,
+    /// Number of UDP datagrams received into the socket RX buffer.
+    pub rx_datagrams: u64,
+    /// Number of received bytes dropped because the socket RX buffer was
+    /// full, including UDP headers.
+    ///
+    /// UDP datagrams that do not fit in the free space are dropped entirely.
+    pub rx_overflow_bytes: u64,
+    /// Maximum number of bytes held in the socket RX buffer, the high-water
+    /// mark of `Sn_RX_RSR`.
+    pub rx_high_water: u16,
+}
+
 #[derive(Debug)]
 struct Socket {
     regs: SocketRegs,
@@ -263,6 +419,15 @@ struct Socket {
     rx_buf: Vec<u8>,
     inner: Option<SocketType>,
     client: Option<TcpStream>,
+    /// Multicast group and interface joined by a UDP socket.
+    multicast: Option<(Ipv4Addr, Ipv4Addr)>,
+    /// Received data waiting for the simulated latency to elapse.
+    rx_pending: VecDeque<(Instant, Vec<u8>)>,
+    /// Result of a pending TCP connect, from the connecting thread.
+    connecting: Option<Receiver<io::Result<TcpStream>>>,
+    /// Deadline to raise the TIMEOUT interrupt for a failed TCP connect.
+    timeout: Option<Instant>,
+    stats: SocketStats,
 }
 
 impl PartialEq for Socket {
@@ -271,8 +436,13 @@ impl PartialEq for Socket {
             && self.tx_buf == other.tx_buf
             && self.rx_buf == other.rx_buf
             && self.inner == other.inner
+            && self.multicast == other.multicast
+            && self.rx_pending == other.rx_pending
+            && self.timeout == other.timeout
+            && self.stats == other.stats
             && ((self.client.is_some() && other.client.is_some())
                 || (self.client.is_none() && other.client.is_none()))
+            && self.connecting.is_some() == other.connecting.is_some()
     }
 }
 
@@ -284,6 +454,11 @@ impl Default for Socket {
             rx_buf: vec![0; DEFAULT_BUF_SIZE],
             inner: None,
             client: None,
+            multicast: None,
+            rx_pending: VecDeque::new(),
+            connecting: None,
+            timeout: None,
+            stats: SocketStats::default(),
         }
     }
 }
@@ -294,7 +469,19 @@ pub struct W5500 {
     regs: CommonRegs,
     sn: [Socket; NUM_SOCKETS],
     socket_buffer_logging: bool,
+    buffer_log_style: BufferLogStyle,
     corpus: Option<File>,
+    latency: Duration,
+    jitter: Duration,
+    rng: XorShift,
+    /// Remaining operations before a fault, and the fault to return.
+    fault: Option<(usize, io::ErrorKind)>,
+    link: bool,
+    /// Host address for UDP sockets, overriding `SIPR`.
+    udp_bind_ip: Option<Ipv4Addr>,
+    /// Honor writes to the read-only `Sn_RX_WR` registers.
+    rx_wr_writable: bool,
+    pcap: Option<Pcap>,
 }
 
 impl PartialEq for W5500 {
@@ -302,8 +489,17 @@ impl PartialEq for W5500 {
         self.regs == other.regs
             && self.sn == other.sn
             && self.socket_buffer_logging == other.socket_buffer_logging
+            && self.buffer_log_style == other.buffer_log_style
+            && self.latency == other.latency
+            && self.jitter == other.jitter
+            && self.rng == other.rng
+            && self.fault == other.fault
+            && self.link == other.link
+            && self.udp_bind_ip == other.udp_bind_ip
+            && self.rx_wr_writable == other.rx_wr_writable
             && ((self.corpus.is_some() && other.corpus.is_some())
                 || (self.corpus.is_none() && other.corpus.is_none()))
+            && self.pcap.is_some() == other.pcap.is_some()
     }
 }
 
@@ -329,6 +525,45 @@ impl W5500 {
         self.socket_buffer_logging = enable
     }
 
+    /// Set the socket buffer log style.
+    ///
+    /// [`BufferLogStyle::PerByte`] logs every byte on a separate line, this
+    /// is the default.
+    /// [`BufferLogStyle::Hexdump`] logs each transfer on a single line, which
+    /// is easier to read for large transfers.
+    ///
+    /// This has no effect if socket buffer logging is
+    /// [disabled](Self::set_socket_buffer_logging).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_regsim::{BufferLogStyle, W5500};
+    ///
+    /// let mut w5500 = W5500::default();
+    /// w5500.set_buffer_log_style(BufferLogStyle::Hexdump);
+    /// ```
+    pub fn set_buffer_log_style(&mut self, style: BufferLogStyle) {
+        self.buffer_log_style = style
+    }
+
+    /// Returns `true` if socket buffer bytes are logged individually.
+    fn per_byte_buffer_logging(&self) -> bool {
+        self.socket_buffer_logging && self.buffer_log_style == BufferLogStyle::PerByte
+    }
+
+    /// Log a socket buffer transfer in the hexdump style.
+    fn log_buffer_transfer(&self, dir: &str, buf: &str, addr: u16, data: &[u8]) {
+        if self.socket_buffer_logging && self.buffer_log_style == BufferLogStyle::Hexdump {
+            let len: u16 = u16::try_from(data.len()).unwrap_or(u16::MAX);
+            log::trace!(
+                "[{dir}] [{buf}] {addr:04X}..{:04X} {}",
+                addr.wrapping_add(len),
+                hexdump(data)
+            );
+        }
+    }
+
     /// Record the byte value of each read in a file.
     ///
     /// This is useful for generating a corpus for AFL or cargo-fuzz.
@@ -348,6 +583,325 @@ impl W5500 {
         self.corpus.replace(file);
     }
 
+    /// Capture the data sent and received by the sockets in a pcap file.
+    ///
+    /// Each UDP datagram and TCP segment is recorded as an Ethernet frame,
+    /// the file can be opened with Wireshark or tcpdump.
+    ///
+    /// The host network stack does not expose the frames it sends, the
+    /// Ethernet, IP, UDP, and TCP headers are synthesized from the socket
+    /// addresses.
+    /// Checksums are not calculated, and TCP control segments (SYN, FIN,
+    /// ACK) are not recorded.
+    ///
+    /// If writing to the file fails the error is logged and capture is
+    /// disabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// let mut w5500 = w5500_regsim::W5500::default();
+    ///
+    /// w5500.enable_pcap(Path::new("/tmp/w5500.pcap"))?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn enable_pcap(&mut self, path: &Path) -> io::Result<()> {
+        self.pcap.replace(Pcap::new(File::create(path)?)?);
+        Ok(())
+    }
+
+    /// Get the simulated socket status.
+    ///
+    /// Unlike [`Registers::sn_sr`] this does not poll the host sockets, and
+    /// it does not change the simulation state.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_regsim::{w5500_ll::{Sn, SocketStatus}, W5500};
+    ///
+    /// let w5500 = W5500::default();
+    /// assert_eq!(w5500.socket_status(Sn::Sn0), SocketStatus::Closed);
+    /// ```
+    pub fn socket_status(&self, sn: Sn) -> SocketStatus {
+        self.socket(sn).regs.sr
+    }
+
+    /// Get the simulated socket interrupts that are currently raised.
+    ///
+    /// Unlike [`Registers::sn_ir`] this does not poll the host sockets, and
+    /// it does not change the simulation state.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_regsim::{w5500_ll::Sn, W5500};
+    ///
+    /// let w5500 = W5500::default();
+    /// assert!(!w5500.raised_interrupts(Sn::Sn0).any_raised());
+    /// ```
+    pub fn raised_interrupts(&self, sn: Sn) -> SocketInterrupt {
+        self.socket(sn).regs.ir
+    }
+
+    /// Returns `true` if the simulated INTn pin is asserted.
+    ///
+    /// The INTn pin is asserted when an interrupt in `IR` is enabled in
+    /// `IMR`, or a socket interrupt in `SIR` is enabled in `SIMR`.
+    /// This can be polled in place of reading the physical INTn pin.
+    ///
+    /// Like [`W5500::raised_interrupts`] this does not poll the host sockets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_regsim::W5500;
+    ///
+    /// let w5500 = W5500::default();
+    /// assert!(!w5500.int_pin_asserted());
+    /// ```
+    pub fn int_pin_asserted(&self) -> bool {
+        self.regs.ir & self.regs.imr != 0 || self.regs.sir & self.regs.simr != 0
+    }
+
+    /// Get the simulated socket TX buffer.
+    ///
+    /// This is the entire circular buffer, use [`Registers::sn_tx_rd`] and
+    /// [`Registers::sn_tx_wr`] to locate the data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_regsim::{w5500_ll::Sn, W5500};
+    ///
+    /// let w5500 = W5500::default();
+    /// assert_eq!(w5500.tx_buffer(Sn::Sn0).len(), 2048);
+    /// ```
+    pub fn tx_buffer(&self, sn: Sn) -> &[u8] {
+        &self.socket(sn).tx_buf
+    }
+
+    /// Get the simulated socket RX buffer.
+    ///
+    /// This is the entire circular buffer, use [`Registers::sn_rx_rd`] and
+    /// [`Registers::sn_rx_wr`] to locate the data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_regsim::{w5500_ll::Sn, W5500};
+    ///
+    /// let w5500 = W5500::default();
+    /// assert_eq!(w5500.rx_buffer(Sn::Sn0).len(), 2048);
+    /// ```
+    pub fn rx_buffer(&self, sn: Sn) -> &[u8] {
+        &self.socket(sn).rx_buf
+    }
+
+    /// Get the traffic statistics of a socket.
+    ///
+    /// The statistics are cleared when the W5500 is reset, they are kept when
+    /// the socket is closed or opened.
+    ///
+    /// Like [`W5500::socket_status`] this does not poll the host sockets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_regsim::{w5500_ll::Sn, SocketStats, W5500};
+    ///
+    /// let w5500 = W5500::default();
+    /// assert_eq!(w5500.stats(Sn::Sn0), SocketStats::default());
+    /// ```
+    pub fn stats(&self, sn: Sn) -> SocketStats {
+        self.socket(sn).stats
+    }
+
+    /// Returns `true` if received data was dropped because the socket RX
+    /// buffer was full.
+    ///
+    /// The W5500 does not have an interrupt for this, firmware only sees the
+    /// RX buffer stay full.
+    /// This is cleared with the [statistics](W5500::stats) when the W5500 is
+    /// reset, the number of bytes dropped is in
+    /// [`SocketStats::rx_overflow_bytes`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_regsim::{w5500_ll::Sn, W5500};
+    ///
+    /// let w5500 = W5500::default();
+    /// assert!(!w5500.rx_overflowed(Sn::Sn0));
+    /// ```
+    pub fn rx_overflowed(&self, sn: Sn) -> bool {
+        self.socket(sn).stats.rx_overflow_bytes != 0
+    }
+
+    /// Set the simulated network latency.
+    ///
+    /// Data received from the host network stack is not visible in the socket
+    /// RX buffers until the latency has elapsed.
+    ///
+    /// This is zero by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// let mut w5500 = w5500_regsim::W5500::default();
+    /// w5500.set_latency(Duration::from_millis(50));
+    /// ```
+    pub fn set_latency(&mut self, latency: Duration) {
+        self.latency = latency
+    }
+
+    /// Set the simulated network jitter.
+    ///
+    /// A random delay between zero and `jitter` is added to the
+    /// [latency](Self::set_latency) of each received packet.
+    /// Data is always delivered in the order it was received.
+    ///
+    /// This is zero by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// let mut w5500 = w5500_regsim::W5500::default();
+    /// w5500.set_latency(Duration::from_millis(50));
+    /// w5500.set_jitter(Duration::from_millis(10));
+    /// ```
+    pub fn set_jitter(&mut self, jitter: Duration) {
+        self.jitter = jitter
+    }
+
+    /// Return an error from a future register operation.
+    ///
+    /// After `n` successful [`Registers::read`] or [`Registers::write`]
+    /// operations the next operation returns `err` without accessing the
+    /// simulated registers.
+    /// The fault is cleared after it has been returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::io::ErrorKind;
+    /// use w5500_regsim::{Registers, W5500};
+    ///
+    /// let mut w5500 = W5500::default();
+    /// w5500.inject_fault_after_ops(1, ErrorKind::BrokenPipe);
+    /// assert!(w5500.version().is_ok());
+    /// assert_eq!(w5500.version(), Err(ErrorKind::BrokenPipe));
+    /// assert!(w5500.version().is_ok());
+    /// ```
+    pub fn inject_fault_after_ops(&mut self, n: usize, err: io::ErrorKind) {
+        self.fault = Some((n, err))
+    }
+
+    /// Set the simulated ethernet link status.
+    ///
+    /// This is reflected in the `LNK` bit of the PHY configuration register.
+    /// While the link is down data received by the host network stack is
+    /// discarded, and data sent by sockets is dropped, as if the cable was
+    /// unplugged.
+    ///
+    /// The link is up by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_regsim::{w5500_ll::LinkStatus, Registers, W5500};
+    ///
+    /// let mut w5500 = W5500::default();
+    /// assert_eq!(w5500.phycfgr()?.lnk(), LinkStatus::Up);
+    /// w5500.set_link(false);
+    /// assert_eq!(w5500.phycfgr()?.lnk(), LinkStatus::Down);
+    /// # Ok::<(), std::io::ErrorKind>(())
+    /// ```
+    pub fn set_link(&mut self, up: bool) {
+        log::info!("link {}", if up { "up" } else { "down" });
+        self.link = up
+    }
+
+    /// Set the host address that UDP sockets are bound to.
+    ///
+    /// By default UDP sockets are bound to the `SIPR` address and the socket
+    /// port.
+    /// Setting this to [`Ipv4Addr::LOCALHOST`] keeps the simulated sockets
+    /// on the loopback interface, regardless of `SIPR`.
+    /// `None` restores the default.
+    ///
+    /// This takes effect the next time a UDP socket is opened.
+    /// Multicast sockets are always bound to the unspecified address, because
+    /// the host does not deliver multicast datagrams to sockets bound to a
+    /// unicast address.
+    ///
+    /// This is a simulation setting, it is kept when the W5500 is reset.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_regsim::{w5500_ll::net::Ipv4Addr, W5500};
+    ///
+    /// let mut w5500 = W5500::default();
+    /// w5500.set_udp_bind_ip(Some(Ipv4Addr::LOCALHOST));
+    /// ```
+    pub fn set_udp_bind_ip(&mut self, ip: Option<Ipv4Addr>) {
+        self.udp_bind_ip = ip
+    }
+
+    /// Allow writes to the socket RX write pointer registers.
+    ///
+    /// `Sn_RX_WR` is read-only on the W5500, by default writes are logged
+    /// and ignored.
+    /// When enabled writes update the RX write pointer, and the received
+    /// size (`Sn_RX_RSR`) is updated to the distance from the RX read pointer.
+    /// Together with writes to the socket RX buffer this seeds received data
+    /// without a host socket.
+    ///
+    /// This is a simulation setting, it is kept when the W5500 is reset.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_regsim::{
+    ///     w5500_ll::{Registers, Sn, SnReg},
+    ///     W5500,
+    /// };
+    ///
+    /// let mut w5500 = W5500::default();
+    /// w5500.set_rx_wr_writable(true);
+    ///
+    /// w5500.write(0, Sn::Sn0.rx_block(), b"abc")?;
+    /// w5500.write(SnReg::RX_WR0.addr(), Sn::Sn0.block(), &3_u16.to_be_bytes())?;
+    /// assert_eq!(w5500.sn_rx_wr(Sn::Sn0)?, 3);
+    /// assert_eq!(w5500.sn_rx_rsr(Sn::Sn0)?, 3);
+    /// # Ok::<(), std::io::ErrorKind>(())
+    /// ```
+    pub fn set_rx_wr_writable(&mut self, writable: bool) {
+        self.rx_wr_writable = writable
+    }
+
+    fn check_fault(&mut self) -> Result<(), io::ErrorKind> {
+        match self.fault {
+            Some((0, err)) => {
+                log::warn!("injecting fault {err:?}");
+                self.fault = None;
+                Err(err)
+            }
+            Some((ref mut n, _)) => {
+                *n -= 1;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
     fn log_byte(&mut self, byte: u8) {
         if let Some(mut file) = self.corpus.as_ref() {
             file.write_all(&[byte])
@@ -356,11 +910,14 @@ impl W5500 {
     }
 
     fn reset(&mut self) {
-        *self = Self::default();
+        // simulation settings are not part of the W5500 state
+        self.regs = CommonRegs::RESET;
+        self.sn = Default::default();
     }
 
     fn socket_cmd_open(&mut self, sn: Sn) -> io::Result<()> {
         let sipr = self.regs.sipr;
+        let bind_ip: Ipv4Addr = self.udp_bind_ip.unwrap_or(sipr);
         let socket = self.socket_mut(sn);
 
         // These registers are initialized by the OPEN command
@@ -368,6 +925,8 @@ impl W5500 {
         socket.regs.rx_rd = 0;
         socket.regs.tx_rd = 0;
         socket.regs.tx_wr = 0;
+        socket.multicast = None;
+        socket.rx_pending.clear();
 
         let mr = SocketMode::from(socket.regs.mr);
 
@@ -380,7 +939,13 @@ impl W5500 {
                 self.sim_set_sn_sr(sn, SocketStatus::Init);
             }
             Ok(Protocol::Udp) => {
-                let local = SocketAddrV4::new(sipr, socket.regs.port);
+                // multicast datagrams are not delivered to sockets bound to a
+                // unicast address
+                let local = if mr.multi_enabled() {
+                    SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, socket.regs.port)
+                } else {
+                    SocketAddrV4::new(bind_ip, socket.regs.port)
+                };
                 log::info!("[{sn:?}] binding UDP socket to {local}");
 
                 match UdpSocket::bind(local) {
@@ -388,6 +953,15 @@ impl W5500 {
                         log::info!("[{sn:?}] bound to {local}");
                         udp_socket.set_nonblocking(true)?;
                         udp_socket.set_ttl(socket.regs.ttl.into())?;
+                        // the W5500 does not require permission to broadcast
+                        udp_socket.set_broadcast(true)?;
+                        if mr.multi_enabled() {
+                            let group: Ipv4Addr = socket.regs.dipr;
+                            log::info!("[{sn:?}] joining multicast group {group} on {sipr}");
+                            udp_socket.set_multicast_ttl_v4(socket.regs.ttl.into())?;
+                            udp_socket.join_multicast_v4(&group, &sipr)?;
+                            socket.multicast = Some((group, sipr));
+                        }
                         socket.inner = Some(SocketType::Udp(udp_socket));
                         self.sim_set_sn_sr(sn, SocketStatus::Udp);
                     }
@@ -399,7 +973,7 @@ impl W5500 {
             }
             Ok(Protocol::Macraw) => {
                 if sn == Sn::Sn0 {
-                    unimplemented!("MACRAW")
+                    log::error!("[{sn:?}] ignoring OPEN command, MACRAW is not simulated")
                 } else {
                     log::error!("[{sn:?}] ignoring OPEN command, MACRAW can only be used on Sn0")
                 }
@@ -416,31 +990,79 @@ impl W5500 {
         let addr = socket.regs.dest();
         log::info!("[{sn:?}] opening a TCP stream to {addr}");
 
-        match TcpStream::connect(addr) {
+        // std does not have a non-blocking connect, connect from another
+        // thread and poll for the result in check_socket
+        let timeout: Duration = self.retransmission_timeout();
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            // the receiver is dropped if the socket is closed first
+            tx.send(TcpStream::connect_timeout(&addr.into(), timeout))
+                .ok();
+        });
+
+        let socket = self.socket_mut(sn);
+        socket.connecting = Some(rx);
+        socket.timeout = Some(Instant::now() + timeout);
+        self.sim_set_sn_sr(sn, SocketStatus::SynSent);
+
+        Ok(())
+    }
+
+    /// Poll the result of a pending TCP connect.
+    fn check_connect(&mut self, sn: Sn) -> io::Result<()> {
+        let socket = self.socket_mut(sn);
+        let result: io::Result<TcpStream> = match socket.connecting.as_ref().map(Receiver::try_recv)
+        {
+            None | Some(Err(TryRecvError::Empty)) => return Ok(()),
+            Some(Err(TryRecvError::Disconnected)) => {
+                socket.connecting = None;
+                return Ok(());
+            }
+            Some(Ok(result)) => result,
+        };
+        socket.connecting = None;
+
+        let addr = socket.regs.dest();
+        match result {
             Ok(stream) => {
                 log::info!("[{sn:?}] established TCP connection with {addr}");
                 stream.set_nonblocking(true)?;
                 stream.set_ttl(socket.regs.ttl.into())?;
                 socket.inner = Some(SocketType::TcpStream(stream));
+                socket.timeout = None;
                 self.raise_sn_ir(sn, SocketInterrupt::CON_MASK);
                 self.sim_set_sn_sr(sn, SocketStatus::Established);
             }
-            Err(e) => {
+            Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => {
                 log::warn!("[{sn:?}] TCP stream to {addr} failed: {e}");
+                socket.timeout = None;
                 self.raise_sn_ir(sn, SocketInterrupt::DISCON_MASK);
                 self.sim_set_sn_sr(sn, SocketStatus::Closed);
             }
+            // TIMEOUT is raised when the retransmission timeout elapses
+            Err(e) => log::warn!("[{sn:?}] TCP stream to {addr} failed: {e}"),
         }
 
         Ok(())
     }
 
+    /// Time for the W5500 to give up on a TCP connection, as determined by
+    /// the RTR and RCR registers.
+    ///
+    /// This ignores the exponential backoff of TCP retransmissions.
+    fn retransmission_timeout(&self) -> Duration {
+        let rtr: Duration = Duration::from_micros(u64::from(self.regs.rtr) * 100);
+        (rtr * (u32::from(self.regs.rcr) + 1)).max(Duration::from_millis(1))
+    }
+
     fn socket_cmd_listen(&mut self, sn: Sn) -> io::Result<()> {
         let socket = self.socket_mut(sn);
         assert_eq!(socket.regs.sr, SocketStatus::Init);
 
         let addr = std::net::SocketAddrV4::new(std::net::Ipv4Addr::LOCALHOST, socket.regs.port);
         log::info!("[{sn:?}] Opening a TCP listener on port {addr}");
+        // std sets SO_REUSEADDR on listeners for unix targets, a port left in
+        // TIME_WAIT by a previous connection can be bound again immediately
         match TcpListener::bind(addr) {
             Ok(listener) => {
                 log::info!("[{sn:?}] Bound listener on {addr}");
@@ -467,6 +1089,23 @@ impl W5500 {
         &mut self.sn[usize::from(sn)]
     }
 
+    fn sim_capture(&mut self, sn: Sn, capture: Option<Capture>, outgoing: bool, payload: &[u8]) {
+        if let (Some(pcap), Some((protocol, local, remote))) = (self.pcap.as_mut(), capture) {
+            let frame: Frame = Frame {
+                mac: self.regs.shar,
+                protocol,
+                local,
+                remote,
+                outgoing,
+                payload,
+            };
+            if let Err(e) = pcap.record(sn, frame) {
+                log::error!("failed to write to pcap file, disabling capture: {e}");
+                self.pcap = None;
+            }
+        }
+    }
+
     fn sim_set_sn_sr(&mut self, sn: Sn, status: SocketStatus) {
         let socket: &mut Socket = self.socket_mut(sn);
         let old: SocketStatus = socket.regs.sr;
@@ -478,7 +1117,53 @@ impl W5500 {
 
     fn socket_cmd_close(&mut self, sn: Sn) {
         let socket = self.socket_mut(sn);
+        if let Some((group, interface)) = socket.multicast.take() {
+            if let Some(SocketType::Udp(ref udp)) = socket.inner {
+                log::info!("[{sn:?}] leaving multicast group {group}");
+                if let Err(e) = udp.leave_multicast_v4(&group, &interface) {
+                    log::warn!("[{sn:?}] failed to leave multicast group {group}: {e}");
+                }
+            }
+        }
         socket.inner = None;
+        socket.client = None;
+        socket.rx_pending.clear();
+        socket.connecting = None;
+        socket.timeout = None;
+        if let Some(pcap) = self.pcap.as_mut() {
+            pcap.reset(sn);
+        }
+        self.sim_set_sn_sr(sn, SocketStatus::Closed);
+    }
+
+    /// The W5500 sends a FIN, and raises DISCON when the peer responds with
+    /// a FIN of its own.
+    ///
+    /// The host network stack completes the close in the background, this
+    /// raises DISCON immediately if the peer had not already closed.
+    fn socket_cmd_disconnect(&mut self, sn: Sn) {
+        let socket = self.socket_mut(sn);
+        let stream: Option<TcpStream> = match socket.inner.take() {
+            Some(SocketType::TcpStream(stream)) => Some(stream),
+            Some(SocketType::TcpListener(_)) => socket.client.take(),
+            other => {
+                log::error!("[{sn:?}] ignoring DISCON command, socket is not TCP");
+                socket.inner = other;
+                return;
+            }
+        };
+        if let Some(stream) = stream {
+            if let Err(e) = stream.shutdown(Shutdown::Both) {
+                log::warn!("[{sn:?}] TCP shutdown failed: {e}");
+            }
+        }
+        socket.rx_pending.clear();
+        if let Some(pcap) = self.pcap.as_mut() {
+            pcap.reset(sn);
+        }
+        if self.socket(sn).regs.sr == SocketStatus::Established {
+            self.raise_sn_ir(sn, SocketInterrupt::DISCON_MASK);
+        }
         self.sim_set_sn_sr(sn, SocketStatus::Closed);
     }
 
@@ -526,34 +1211,121 @@ impl W5500 {
 
         debug_assert!(!local_tx_buf.is_empty());
 
+        if !self.link {
+            log::warn!("[{sn:?}] link is down, dropping {size} bytes");
+            let socket = self.socket_mut(sn);
+            socket.regs.tx_rd = socket.regs.tx_wr;
+            return Ok(());
+        }
+
+        let capturing: bool = self.pcap.is_some();
+        let mut unreachable: Option<SocketAddrV4> = None;
+        let socket = self.socket_mut(sn);
         let dest = socket.regs.dest();
 
-        match socket.inner {
+        let is_udp: bool = matches!(socket.inner, Some(SocketType::Udp(_)));
+        let capture: Option<Capture> = match socket.inner {
             Some(SocketType::TcpStream(ref mut stream)) => {
                 stream.write_all(&local_tx_buf)?;
                 stream.flush()?;
+                capturing.then(|| tcp_capture(stream))
             }
             Some(SocketType::Udp(ref mut udp)) => {
                 log::info!("[{sn:?}] sending to {dest}");
-                let num: usize = udp.send_to(&local_tx_buf, dest)?;
-                assert_eq!(num, local_tx_buf.len());
+                match udp.send_to(&local_tx_buf, dest) {
+                    Ok(num) => {
+                        assert_eq!(num, local_tx_buf.len());
+                        if udp_port_closed(dest) {
+                            unreachable = Some(dest);
+                        }
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => {
+                        unreachable = Some(dest);
+                    }
+                    Err(e) => return Err(e),
+                }
+                capturing.then(|| (Protocol::Udp, socket_addr_v4(udp.local_addr()), dest))
             }
             Some(SocketType::TcpListener(_)) => {
                 if let Some(ref mut stream) = socket.client {
                     stream.write_all(&local_tx_buf)?;
                     stream.flush()?;
+                    capturing.then(|| tcp_capture(stream))
+                } else {
+                    None
                 }
             }
             None => {
-                panic!("[{sn:?}] Unable to send data, socket is closed")
+                log::error!("[{sn:?}] ignoring SEND command, socket is closed");
+                return Ok(());
             }
-        }
+        };
 
         socket.regs.tx_rd = socket.regs.tx_wr;
+        socket.stats.tx_bytes += u64::try_from(local_tx_buf.len()).unwrap_or(u64::MAX);
+        if is_udp {
+            socket.stats.tx_datagrams += 1;
+        }
+
+        self.sim_capture(sn, capture, true, &local_tx_buf);
+
+        if let Some(addr) = unreachable {
+            self.sim_unreachable(sn, addr);
+        }
 
         Ok(())
     }
 
+    /// The W5500 raises the UNREACH interrupt and records the destination
+    /// when it receives an ICMP destination unreachable message.
+    fn sim_unreachable(&mut self, sn: Sn, addr: SocketAddrV4) {
+        log::warn!("[{sn:?}] {addr} is unreachable");
+        self.regs.uipr = *addr.ip();
+        self.regs.uportr = addr.port();
+        self.regs.ir |= Interrupt::UNREACH_MASK;
+    }
+
+    /// With force ARP enabled the W5500 sends an ARP request before each
+    /// SEND, and updates `sn_dhar` with the response.
+    ///
+    /// The host network stack does not expose the ARP table, instead this
+    /// sets `sn_dhar` to a synthetic locally administered address derived
+    /// from the destination IP.
+    fn sim_force_arp(&mut self, sn: Sn) {
+        if !Mode::from(self.regs.mr).farp_enabled() || !self.link {
+            return;
+        }
+        let socket = self.socket_mut(sn);
+        let dipr: Ipv4Addr = socket.regs.dipr;
+        if dipr.is_broadcast() || dipr.is_multicast() {
+            return;
+        }
+        let dhar: Eui48Addr = Eui48Addr::locally_administered(u32::from(dipr));
+        log::info!("[{sn:?}] force ARP resolved {dipr} to {dhar}");
+        socket.regs.dhar = dhar;
+    }
+
+    /// The SEND_MAC command is identical to SEND for UDP sockets, except the
+    /// destination hardware address is taken from `sn_dhar` instead of ARP.
+    ///
+    /// The host network stack does not allow routing by hardware address, so
+    /// this logs and ignores `sn_dhar`.
+    fn socket_cmd_send_mac(&mut self, sn: Sn) -> io::Result<()> {
+        let socket = self.socket(sn);
+        if socket.regs.sr != SocketStatus::Udp {
+            log::error!(
+                "[{sn:?}] ignoring SEND_MAC command, socket is not UDP: {:?}",
+                socket.regs.sr
+            );
+            return Ok(());
+        }
+        log::warn!(
+            "[{sn:?}] SEND_MAC destination hardware address {} ignored",
+            socket.regs.dhar
+        );
+        self.socket_cmd_send(sn)
+    }
+
     /// The RECV command is used to indicate that the microcontroller has read
     /// an amount of data from the W5500, as indicated by the `sn_rx_rd`
     /// pointer.
@@ -571,20 +1343,80 @@ impl W5500 {
         Ok(())
     }
 
-    fn sim_set_sn_rx_buf(&mut self, sn: Sn, data: &[u8]) {
+    /// Write received data to the socket RX buffer.
+    ///
+    /// Returns the number of bytes written, data that does not fit is dropped.
+    /// UDP datagrams are written completely or not at all.
+    fn sim_set_sn_rx_buf(&mut self, sn: Sn, data: &[u8]) -> usize {
         let socket = self.socket_mut(sn);
         let buf_len: usize = socket.rx_buf.len();
+        let free: usize = buf_len.saturating_sub(usize::from(socket.regs.rx_rsr));
+
+        // a 0 KiB buffer has no free space, everything received is dropped
+        let written: usize =
+            if matches!(socket.inner, Some(SocketType::Udp(_))) && data.len() > free {
+                0
+            } else {
+                data.len().min(free)
+            };
+
+        let dropped: usize = data.len() - written;
+        if dropped != 0 {
+            log::error!("[{sn:?}] RX buffer overflow, dropping {dropped} bytes");
+            socket.stats.rx_overflow_bytes += u64::try_from(dropped).unwrap_or(u64::MAX);
+        }
 
-        for byte in data.iter() {
+        for byte in &data[..written] {
             let buf_idx: usize = usize::from(socket.regs.rx_wr) % buf_len;
-            if usize::from(socket.regs.rx_rsr).saturating_add(1) > buf_len {
-                log::error!("[{sn:?}] RX buffer overflow");
-                return;
-            }
             socket.rx_buf[buf_idx] = *byte;
             socket.regs.rx_wr = socket.regs.rx_wr.wrapping_add(1);
             socket.regs.rx_rsr = socket.regs.rx_rsr.checked_add(1).unwrap();
         }
+        socket.stats.rx_high_water = socket.stats.rx_high_water.max(socket.regs.rx_rsr);
+
+        written
+    }
+
+    /// Queue received data, delivering it to the RX buffer after the
+    /// simulated latency has elapsed.
+    fn sim_queue_rx(&mut self, sn: Sn, data: Vec<u8>) {
+        let mut delay: Duration = self.latency;
+        if !self.jitter.is_zero() {
+            let jitter_ns: u64 = u64::try_from(self.jitter.as_nanos()).unwrap_or(u64::MAX);
+            delay += Duration::from_nanos(self.rng.next_u64() % jitter_ns.saturating_add(1));
+        }
+        self.socket_mut(sn)
+            .rx_pending
+            .push_back((Instant::now() + delay, data));
+    }
+
+    /// Deliver queued data that has reached the end of the simulated latency.
+    fn sim_deliver_rx(&mut self, sn: Sn) {
+        let now: Instant = Instant::now();
+        while let Some((deadline, _)) = self.socket(sn).rx_pending.front() {
+            if *deadline > now {
+                break;
+            }
+            if let Some((_, data)) = self.socket_mut(sn).rx_pending.pop_front() {
+                if !self.link {
+                    log::warn!("[{sn:?}] link is down, dropping {} bytes", data.len());
+                    continue;
+                }
+                let written: usize = self.sim_set_sn_rx_buf(sn, &data);
+                if written == 0 {
+                    continue;
+                }
+                self.raise_sn_ir(sn, SocketInterrupt::RECV_MASK);
+                let socket = self.socket_mut(sn);
+                let mut len: u64 = u64::try_from(written).unwrap_or(u64::MAX);
+                if matches!(socket.inner, Some(SocketType::Udp(_))) {
+                    // exclude the header added to each datagram
+                    len = len.saturating_sub(8);
+                    socket.stats.rx_datagrams += 1;
+                }
+                socket.stats.rx_bytes += len;
+            }
+        }
     }
 
     fn raise_sn_ir(&mut self, sn: Sn, int: u8) {
@@ -594,6 +1426,22 @@ impl W5500 {
     }
 
     fn check_socket(&mut self, sn: Sn) -> io::Result<()> {
+        self.check_connect(sn)?;
+        if let Some(deadline) = self.socket(sn).timeout {
+            if Instant::now() >= deadline {
+                log::warn!("[{sn:?}] TCP connect timed out");
+                let socket = self.socket_mut(sn);
+                socket.connecting = None;
+                socket.timeout = None;
+                self.raise_sn_ir(sn, SocketInterrupt::TIMEOUT_MASK);
+                self.sim_set_sn_sr(sn, SocketStatus::Closed);
+            }
+        }
+
+        let capturing: bool = self.pcap.is_some();
+        let wol_mac: Option<Eui48Addr> =
+            (self.regs.mr & Mode::WOL_MASK != 0).then_some(self.regs.shar);
+        let mut peer_closed: bool = false;
         let socket = self.socket_mut(sn);
         let bufsize: usize = socket.regs.rxbuf_size.size_in_bytes();
         let mut buf: Vec<u8> = vec![0; bufsize];
@@ -608,14 +1456,22 @@ impl W5500 {
                         }
                     };
                     log::info!("[{sn:?}] recv datagram of len {num} from {origin}");
+                    let wake: bool = wol_mac.is_some_and(|mac| is_magic_packet(&buf[..num], &mac));
+                    let capture: Option<Capture> = capturing
+                        .then(|| (Protocol::Udp, socket_addr_v4(udp.local_addr()), origin));
+                    self.sim_capture(sn, capture, false, &buf[..num]);
                     let num: u16 = u16::try_from(num).unwrap_or(u16::MAX);
+                    let mut data: Vec<u8> = Vec::with_capacity(usize::from(num) + 8);
                     // write out the header
-                    self.sim_set_sn_rx_buf(sn, &origin.ip().octets());
-                    self.sim_set_sn_rx_buf(sn, &origin.port().to_be_bytes());
-                    self.sim_set_sn_rx_buf(sn, &num.to_be_bytes());
+                    data.extend_from_slice(&net::socket_addr_to_bytes(&origin));
+                    data.extend_from_slice(&num.to_be_bytes());
                     // write the rest of the data
-                    self.sim_set_sn_rx_buf(sn, &buf[..usize::from(num)]);
-                    self.raise_sn_ir(sn, SocketInterrupt::RECV_MASK);
+                    data.extend_from_slice(&buf[..usize::from(num)]);
+                    self.sim_queue_rx(sn, data);
+                    if wake {
+                        log::info!("[{sn:?}] recv magic packet");
+                        self.regs.ir |= Interrupt::MP_MASK;
+                    }
                 }
                 Err(e) => match e.kind() {
                     io::ErrorKind::WouldBlock => {}
@@ -625,28 +1481,32 @@ impl W5500 {
             Some(SocketType::TcpStream(ref mut stream)) => match stream.read(&mut buf) {
                 Ok(num @ 1..=usize::MAX) => {
                     log::info!("[{sn:?}] recv {num} bytes");
-                    self.sim_set_sn_rx_buf(sn, &buf[..num]);
-                    self.raise_sn_ir(sn, SocketInterrupt::RECV_MASK);
+                    buf.truncate(num);
+                    let capture: Option<Capture> = capturing.then(|| tcp_capture(stream));
+                    self.sim_capture(sn, capture, false, &buf);
+                    self.sim_queue_rx(sn, buf);
                 }
+                Ok(_) => peer_closed = true,
                 Err(e) => match e.kind() {
                     io::ErrorKind::WouldBlock => {}
                     _ => return Err(e),
                 },
-                _ => {}
             },
             Some(SocketType::TcpListener(ref mut listener)) => {
                 if let Some(ref mut stream) = socket.client {
                     match stream.read(&mut buf) {
                         Ok(num @ 1..=usize::MAX) => {
                             log::info!("[{sn:?}] recv {num} bytes");
-                            self.sim_set_sn_rx_buf(sn, &buf[..num]);
-                            self.raise_sn_ir(sn, SocketInterrupt::RECV_MASK);
+                            buf.truncate(num);
+                            let capture: Option<Capture> = capturing.then(|| tcp_capture(stream));
+                            self.sim_capture(sn, capture, false, &buf);
+                            self.sim_queue_rx(sn, buf);
                         }
+                        Ok(_) => peer_closed = true,
                         Err(e) => match e.kind() {
                             io::ErrorKind::WouldBlock => {}
                             _ => return Err(e),
                         },
-                        _ => {}
                     }
                 } else {
                     match listener.accept() {
@@ -666,6 +1526,12 @@ impl W5500 {
             }
             None => {}
         };
+        if peer_closed && self.socket(sn).regs.sr == SocketStatus::Established {
+            log::info!("[{sn:?}] peer closed the TCP connection");
+            self.raise_sn_ir(sn, SocketInterrupt::DISCON_MASK);
+            self.sim_set_sn_sr(sn, SocketStatus::CloseWait);
+        }
+        self.sim_deliver_rx(sn);
         Ok(())
     }
 
@@ -719,7 +1585,14 @@ impl W5500 {
             Ok(Reg::UIPR3) => self.regs.uipr.octets()[3],
             Ok(Reg::UPORTR0) => self.regs.uportr.to_be_bytes()[0],
             Ok(Reg::UPORTR1) => self.regs.uportr.to_be_bytes()[1],
-            Ok(Reg::PHYCFGR) => self.regs.phycfgr,
+            Ok(Reg::PHYCFGR) => {
+                let lnk: u8 = 1 << PhyCfg::LNK_OFFSET;
+                if self.link {
+                    self.regs.phycfgr | lnk
+                } else {
+                    self.regs.phycfgr & !lnk
+                }
+            }
             Ok(Reg::VERSIONR) => self.regs.versionr,
             Err(_) => 0x00,
         };
@@ -744,7 +1617,7 @@ impl W5500 {
                 }
                 let mode: Mode = byte.into();
                 if mode.wol_enabled() != Mode::default().wol_enabled() {
-                    log::warn!("[W] [COM] MR wake on lan bit unimplemented");
+                    log::warn!("[W] [COM] MR wake on lan bit partially implemented");
                 }
                 if mode.pb_enabled() != Mode::default().pb_enabled() {
                     log::warn!("[W] [COM] MR ping block bit unimplemented");
@@ -753,7 +1626,7 @@ impl W5500 {
                     log::warn!("[W] [COM] MR PPPoE bit unimplemented");
                 }
                 if mode.farp_enabled() != Mode::default().farp_enabled() {
-                    log::warn!("[W] [COM] MR force ARP bit unimplemented");
+                    log::warn!("[W] [COM] MR force ARP bit partially implemented");
                 }
             }
             Ok(Reg::GAR0) => {
@@ -818,7 +1691,7 @@ impl W5500 {
                 self.regs.intlevel &= 0xFF00;
                 self.regs.intlevel |= u16::from(byte);
             }
-            Ok(Reg::IR) => self.regs.ir = byte,
+            Ok(Reg::IR) => self.regs.ir &= !byte,
             Ok(Reg::IMR) => self.regs.imr = byte,
             Ok(Reg::SIR) => self.regs.sir = byte,
             Ok(Reg::SIMR) => self.regs.simr = byte,
@@ -945,6 +1818,7 @@ impl W5500 {
 
     fn socket_reg_wr(&mut self, addr: u16, byte: u8, sn: Sn) -> io::Result<()> {
         let decoded = SnReg::try_from(addr);
+        let rx_wr_writable: bool = self.rx_wr_writable;
         let socket: &mut Socket = self.socket_mut(sn);
 
         match decoded {
@@ -955,10 +1829,28 @@ impl W5500 {
                 Ok(SocketCommand::Open) => self.socket_cmd_open(sn)?,
                 Ok(SocketCommand::Connect) => self.socket_cmd_connect(sn)?,
                 Ok(SocketCommand::Close) => self.socket_cmd_close(sn),
-                Ok(SocketCommand::Send) => self.socket_cmd_send(sn)?,
-                Ok(SocketCommand::Recv) => self.socket_cmd_recv(sn)?,
+                Ok(SocketCommand::Disconnect) => self.socket_cmd_disconnect(sn),
+                Ok(SocketCommand::Send) => {
+                    socket.stats.send_cmds += 1;
+                    self.socket_cmd_send(sn)?;
+                    self.sim_force_arp(sn);
+                }
+                Ok(SocketCommand::SendMac) => {
+                    socket.stats.send_cmds += 1;
+                    self.socket_cmd_send_mac(sn)?
+                }
+                Ok(SocketCommand::Recv) => {
+                    socket.stats.recv_cmds += 1;
+                    self.socket_cmd_recv(sn)?
+                }
                 Ok(SocketCommand::Listen) => self.socket_cmd_listen(sn)?,
-                cmd => unimplemented!("[W] [{sn:?}] command {cmd:?}"),
+                Ok(SocketCommand::SendKeep) => {
+                    log::warn!("[{sn:?}] SEND_KEEP ignored, keep-alive is left to the host")
+                }
+                Ok(SocketCommand::Accepted) => {
+                    log::error!("[{sn:?}] ignoring command 0x00, this is not a command")
+                }
+                Err(cmd) => log::error!("[{sn:?}] ignoring invalid command {cmd:#04X}"),
             },
             Ok(SnReg::IR) => {
                 let ir: SocketInterrupt = byte.into();
@@ -1031,9 +1923,20 @@ impl W5500 {
                 socket.regs.dport &= 0xFF00;
                 socket.regs.dport |= u16::from(byte);
             }
-            Ok(SnReg::MSSR0) => todo!(),
-            Ok(SnReg::MSSR1) => todo!(),
-            Ok(SnReg::TOS) => todo!(),
+            Ok(SnReg::MSSR0) => {
+                log::warn!("[{sn:?}] MSSR is not simulated");
+                socket.regs.mssr &= 0x00FF;
+                socket.regs.mssr |= u16::from(byte) << 8;
+            }
+            Ok(SnReg::MSSR1) => {
+                log::warn!("[{sn:?}] MSSR is not simulated");
+                socket.regs.mssr &= 0xFF00;
+                socket.regs.mssr |= u16::from(byte);
+            }
+            Ok(SnReg::TOS) => {
+                log::warn!("[{sn:?}] TOS is not simulated");
+                socket.regs.tos = byte;
+            }
             Ok(SnReg::TTL) => socket.regs.ttl = byte,
             Ok(SnReg::RXBUF_SIZE) => {
                 socket.regs.rxbuf_size = match BufferSize::try_from(byte) {
@@ -1041,7 +1944,10 @@ impl W5500 {
                         socket.rx_buf.resize(bs.size_in_bytes(), 0);
                         bs
                     }
-                    Err(e) => panic!("RX buffer size of {e:#02X} is invalid"),
+                    Err(e) => {
+                        log::error!("[{sn:?}] ignoring invalid RX buffer size {e:#04X}");
+                        socket.regs.rxbuf_size
+                    }
                 }
             }
             Ok(SnReg::TXBUF_SIZE) => {
@@ -1050,7 +1956,10 @@ impl W5500 {
                         socket.tx_buf.resize(bs.size_in_bytes(), 0);
                         bs
                     }
-                    Err(e) => panic!("TX buffer size of {e:#02X} is invalid"),
+                    Err(e) => {
+                        log::error!("[{sn:?}] ignoring invalid TX buffer size {e:#04X}");
+                        socket.regs.txbuf_size
+                    }
                 }
             }
             Ok(SnReg::TX_FSR0) => (),
@@ -1075,18 +1984,38 @@ impl W5500 {
                 socket.regs.rx_rd &= 0xFF00;
                 socket.regs.rx_rd |= u16::from(byte);
             }
-            Ok(SnReg::RX_WR0) => todo!(),
-            Ok(SnReg::RX_WR1) => todo!(),
+            Ok(SnReg::RX_WR0) if rx_wr_writable => {
+                socket.regs.rx_wr &= 0x00FF;
+                socket.regs.rx_wr |= u16::from(byte) << 8;
+                socket.regs.rx_rsr = socket.regs.rx_wr.wrapping_sub(socket.regs.rx_rd);
+            }
+            Ok(SnReg::RX_WR1) if rx_wr_writable => {
+                socket.regs.rx_wr &= 0xFF00;
+                socket.regs.rx_wr |= u16::from(byte);
+                socket.regs.rx_rsr = socket.regs.rx_wr.wrapping_sub(socket.regs.rx_rd);
+            }
+            Ok(SnReg::RX_WR0) => (),
+            Ok(SnReg::RX_WR1) => (),
             Ok(SnReg::IMR) => socket.regs.imr = byte,
-            Ok(SnReg::FRAG0) => todo!(),
-            Ok(SnReg::FRAG1) => todo!(),
-            Ok(SnReg::KPALVTR) => todo!(),
+            Ok(SnReg::FRAG0) => {
+                log::warn!("[{sn:?}] FRAG is not simulated");
+                socket.regs.frag &= 0x00FF;
+                socket.regs.frag |= u16::from(byte) << 8;
+            }
+            Ok(SnReg::FRAG1) => {
+                log::warn!("[{sn:?}] FRAG is not simulated");
+                socket.regs.frag &= 0xFF00;
+                socket.regs.frag |= u16::from(byte);
+            }
+            Ok(SnReg::KPALVTR) => socket.regs.kpalvtr = byte,
             Err(_) => (),
         }
 
         let (name, level): (String, log::Level) = match decoded {
             Ok(reg) => {
-                if reg.is_ro() {
+                if rx_wr_writable && matches!(reg, SnReg::RX_WR0 | SnReg::RX_WR1) {
+                    (format!("{reg:?}"), log::Level::Trace)
+                } else if reg.is_ro() {
                     (format!("{reg:?} is read-only"), log::Level::Error)
                 } else {
                     (format!("{reg:?}"), log::Level::Trace)
@@ -1107,7 +2036,16 @@ impl Default for W5500 {
             regs: CommonRegs::RESET,
             sn: Default::default(),
             socket_buffer_logging: true,
+            buffer_log_style: BufferLogStyle::PerByte,
             corpus: None,
+            latency: Duration::ZERO,
+            jitter: Duration::ZERO,
+            rng: XorShift::default(),
+            fault: None,
+            link: true,
+            udp_bind_ip: None,
+            rx_wr_writable: false,
+            pcap: None,
         }
     }
 }
@@ -1116,6 +2054,7 @@ impl Registers for W5500 {
     type Error = std::io::ErrorKind;
 
     fn read(&mut self, addr: u16, block: u8, data: &mut [u8]) -> Result<(), Self::Error> {
+        self.check_fault()?;
         let mut addr: u16 = addr;
 
         match block_type(block) {
@@ -1136,27 +2075,35 @@ impl Registers for W5500 {
                 Ok(())
             }
             BlockType::Rx(sn) => {
+                let start: u16 = addr;
+                let per_byte: bool = self.per_byte_buffer_logging();
                 data.iter_mut().for_each(|byte| {
-                    let buf_size: usize = self.sn[usize::from(sn)].rx_buf.len();
-                    *byte = self.sn[usize::from(sn)].rx_buf[usize::from(addr) % buf_size];
+                    *byte = sim_buf_idx(&self.sn[usize::from(sn)].rx_buf, addr)
+                        .map(|idx| self.sn[usize::from(sn)].rx_buf[idx])
+                        .unwrap_or(0);
                     self.log_byte(*byte);
-                    if self.socket_buffer_logging {
+                    if per_byte {
                         log::trace!("[R] [RXB] {addr:04X} -> {:02X}", *byte);
                     }
                     addr = addr.wrapping_add(1);
                 });
+                self.log_buffer_transfer("R", "RXB", start, data);
                 Ok(())
             }
             BlockType::Tx(sn) => {
+                let start: u16 = addr;
+                let per_byte: bool = self.per_byte_buffer_logging();
                 data.iter_mut().for_each(|byte| {
-                    let buf_size: usize = self.sn[usize::from(sn)].tx_buf.len();
-                    *byte = self.sn[usize::from(sn)].tx_buf[usize::from(addr) % buf_size];
+                    *byte = sim_buf_idx(&self.sn[usize::from(sn)].tx_buf, addr)
+                        .map(|idx| self.sn[usize::from(sn)].tx_buf[idx])
+                        .unwrap_or(0);
                     self.log_byte(*byte);
-                    if self.socket_buffer_logging {
+                    if per_byte {
                         log::trace!("[R] [TXB] {addr:04X} -> {:02X}", *byte);
                     }
                     addr = addr.wrapping_add(1);
                 });
+                self.log_buffer_transfer("R", "TXB", start, data);
                 Ok(())
             }
         }
@@ -1164,6 +2111,7 @@ impl Registers for W5500 {
 
     /// Write to the W5500.
     fn write(&mut self, addr: u16, block: u8, data: &[u8]) -> Result<(), Self::Error> {
+        self.check_fault()?;
         let mut addr: u16 = addr;
 
         match block_type(block) {
@@ -1182,23 +2130,29 @@ impl Registers for W5500 {
                 Ok(())
             }
             BlockType::Rx(sn) => {
+                self.log_buffer_transfer("W", "RXB", addr, data);
+                let per_byte: bool = self.per_byte_buffer_logging();
                 data.iter().for_each(|byte| {
-                    if self.socket_buffer_logging {
+                    if per_byte {
                         log::trace!("[W] [RXB] {addr:04X} <- {:02X}", *byte);
                     }
-                    let buf_size: usize = self.sn[usize::from(sn)].rx_buf.len();
-                    self.sn[usize::from(sn)].rx_buf[usize::from(addr) % buf_size] = *byte;
+                    if let Some(idx) = sim_buf_idx(&self.sn[usize::from(sn)].rx_buf, addr) {
+                        self.sn[usize::from(sn)].rx_buf[idx] = *byte;
+                    }
                     addr = addr.wrapping_add(1);
                 });
                 Ok(())
             }
             BlockType::Tx(sn) => {
+                self.log_buffer_transfer("W", "TXB", addr, data);
+                let per_byte: bool = self.per_byte_buffer_logging();
                 data.iter().for_each(|byte| {
-                    if self.socket_buffer_logging {
+                    if per_byte {
                         log::trace!("[W] [TXB] {addr:04X} <- {:02X}", *byte);
                     }
-                    let buf_size: usize = self.sn[usize::from(sn)].tx_buf.len();
-                    self.sn[usize::from(sn)].tx_buf[usize::from(addr) % buf_size] = *byte;
+                    if let Some(idx) = sim_buf_idx(&self.sn[usize::from(sn)].tx_buf, addr) {
+                        self.sn[usize::from(sn)].tx_buf[idx] = *byte;
+                    }
                     addr = addr.wrapping_add(1);
                 });
                 Ok(())
@@ -1218,3 +2172,18 @@ impl w5500_ll::aio::Registers for W5500 {
         w5500_ll::Registers::write(self, address, block, data)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::hexdump;
+
+    #[test]
+    fn hexdump_ascii() {
+        assert_eq!(
+            hexdump(b"Hi there\n"),
+            "48 69 20 74 68 65 72 65 0A |Hi there.|"
+        );
+        assert_eq!(hexdump(&[0x00, 0x7F, 0xFF]), "00 7F FF |...|");
+        assert_eq!(hexdump(&[]), " ||");
+    }
+}
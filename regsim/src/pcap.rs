@@ -0,0 +1,197 @@
+//! Packet capture in the pcap file format.
+//!
+//! The host network stack does not expose the frames it sends, these are
+//! synthesized from the socket addresses and payloads.
+//! Ethernet and IP headers are approximate, TCP sequence numbers start at
+//! zero for each connection and there are no SYN, FIN, or ACK only segments.
+//!
+//! # References
+//!
+//! * [PCAP Capture File Format](https://datatracker.ietf.org/doc/draft-ietf-opsawg-pcap/)
+
+use std::{
+    fs::File,
+    io::{self, Write},
+    net::SocketAddrV4,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use w5500_ll::{net::Eui48Addr, Protocol, Sn, SOCKETS};
+
+const MAGIC: u32 = 0xA1B2_C3D4;
+const VERSION_MAJOR: u16 = 2;
+const VERSION_MINOR: u16 = 4;
+const SNAPLEN: u32 = u16::MAX as u32;
+const LINKTYPE_ETHERNET: u32 = 1;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERNET_HEADER_LEN: usize = 14;
+const IPV4_HEADER_LEN: usize = 20;
+const UDP_HEADER_LEN: usize = 8;
+const TCP_HEADER_LEN: usize = 20;
+
+const IPV4_TTL: u8 = 64;
+const IPV4_DONT_FRAGMENT: u16 = 0x4000;
+const IP_PROTOCOL_TCP: u8 = 6;
+const IP_PROTOCOL_UDP: u8 = 17;
+
+const TCP_PSH_ACK: u8 = 0x18;
+const TCP_WINDOW: u16 = u16::MAX;
+
+/// Payload sent or received by a socket.
+#[derive(Debug)]
+pub(crate) struct Frame<'a> {
+    /// Local MAC address.
+    pub mac: Eui48Addr,
+    /// [`Protocol::Tcp`] or [`Protocol::Udp`].
+    pub protocol: Protocol,
+    pub local: SocketAddrV4,
+    pub remote: SocketAddrV4,
+    /// `true` if the payload was sent by the socket.
+    pub outgoing: bool,
+    pub payload: &'a [u8],
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct TcpSeq {
+    tx: u32,
+    rx: u32,
+}
+
+#[derive(Debug)]
+pub(crate) struct Pcap {
+    file: File,
+    tcp: [TcpSeq; SOCKETS.len()],
+}
+
+impl Pcap {
+    /// Write the pcap global header to `file`.
+    pub(crate) fn new(mut file: File) -> io::Result<Self> {
+        let mut header: Vec<u8> = Vec::with_capacity(24);
+        header.extend_from_slice(&MAGIC.to_le_bytes());
+        header.extend_from_slice(&VERSION_MAJOR.to_le_bytes());
+        header.extend_from_slice(&VERSION_MINOR.to_le_bytes());
+        // timezone offset and timestamp accuracy, always zero
+        header.extend_from_slice(&[0; 8]);
+        header.extend_from_slice(&SNAPLEN.to_le_bytes());
+        header.extend_from_slice(&LINKTYPE_ETHERNET.to_le_bytes());
+        file.write_all(&header)?;
+        Ok(Self {
+            file,
+            tcp: Default::default(),
+        })
+    }
+
+    /// Reset the TCP sequence numbers for a socket.
+    pub(crate) fn reset(&mut self, sn: Sn) {
+        self.tcp[usize::from(sn)] = TcpSeq::default();
+    }
+
+    pub(crate) fn record(&mut self, sn: Sn, frame: Frame) -> io::Result<()> {
+        let (src, dst) = if frame.outgoing {
+            (frame.local, frame.remote)
+        } else {
+            (frame.remote, frame.local)
+        };
+        let (src_mac, dst_mac) = if frame.outgoing {
+            (frame.mac, Eui48Addr::UNSPECIFIED)
+        } else {
+            (Eui48Addr::UNSPECIFIED, frame.mac)
+        };
+
+        let (ip_protocol, transport_len): (u8, usize) = match frame.protocol {
+            Protocol::Tcp => (IP_PROTOCOL_TCP, TCP_HEADER_LEN),
+            _ => (IP_PROTOCOL_UDP, UDP_HEADER_LEN),
+        };
+        let ip_len: usize = IPV4_HEADER_LEN + transport_len + frame.payload.len();
+        let ip_len_u16: u16 = u16::try_from(ip_len).unwrap_or(u16::MAX);
+
+        let mut buf: Vec<u8> = Vec::with_capacity(ETHERNET_HEADER_LEN + ip_len);
+
+        buf.extend_from_slice(&dst_mac.octets);
+        buf.extend_from_slice(&src_mac.octets);
+        buf.extend_from_slice(&ETHERTYPE_IPV4.to_be_bytes());
+
+        let ip_start: usize = buf.len();
+        buf.push(0x45); // version 4, 5 word header
+        buf.push(0x00); // DSCP and ECN
+        buf.extend_from_slice(&ip_len_u16.to_be_bytes());
+        buf.extend_from_slice(&[0x00, 0x00]); // identification
+        buf.extend_from_slice(&IPV4_DONT_FRAGMENT.to_be_bytes());
+        buf.push(IPV4_TTL);
+        buf.push(ip_protocol);
+        buf.extend_from_slice(&[0x00, 0x00]); // checksum
+        buf.extend_from_slice(&src.ip().octets());
+        buf.extend_from_slice(&dst.ip().octets());
+        let checksum: u16 = ipv4_checksum(&buf[ip_start..]);
+        buf[ip_start + 10..ip_start + 12].copy_from_slice(&checksum.to_be_bytes());
+
+        buf.extend_from_slice(&src.port().to_be_bytes());
+        buf.extend_from_slice(&dst.port().to_be_bytes());
+        if ip_protocol == IP_PROTOCOL_TCP {
+            let seq: &mut TcpSeq = &mut self.tcp[usize::from(sn)];
+            let len: u32 = u32::try_from(frame.payload.len()).unwrap_or(u32::MAX);
+            let (seq_num, ack_num): (u32, u32) = if frame.outgoing {
+                let ret = (seq.tx, seq.rx);
+                seq.tx = seq.tx.wrapping_add(len);
+                ret
+            } else {
+                let ret = (seq.rx, seq.tx);
+                seq.rx = seq.rx.wrapping_add(len);
+                ret
+            };
+            buf.extend_from_slice(&seq_num.to_be_bytes());
+            buf.extend_from_slice(&ack_num.to_be_bytes());
+            buf.push(0x50); // 5 word header
+            buf.push(TCP_PSH_ACK);
+            buf.extend_from_slice(&TCP_WINDOW.to_be_bytes());
+            // checksum and urgent pointer, the checksum is not calculated
+            buf.extend_from_slice(&[0x00; 4]);
+        } else {
+            let udp_len: u16 = ip_len_u16.saturating_sub(IPV4_HEADER_LEN as u16);
+            buf.extend_from_slice(&udp_len.to_be_bytes());
+            // a checksum of zero indicates the checksum was not calculated
+            buf.extend_from_slice(&[0x00, 0x00]);
+        }
+        buf.extend_from_slice(frame.payload);
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let captured_len: u32 = u32::try_from(buf.len()).unwrap_or(u32::MAX);
+        let mut record: Vec<u8> = Vec::with_capacity(16 + buf.len());
+        record.extend_from_slice(
+            &u32::try_from(now.as_secs())
+                .unwrap_or(u32::MAX)
+                .to_le_bytes(),
+        );
+        record.extend_from_slice(&now.subsec_micros().to_le_bytes());
+        record.extend_from_slice(&captured_len.min(SNAPLEN).to_le_bytes());
+        record.extend_from_slice(&captured_len.to_le_bytes());
+        record.extend_from_slice(&buf[..buf.len().min(SNAPLEN as usize)]);
+        self.file.write_all(&record)
+    }
+}
+
+fn ipv4_checksum(header: &[u8]) -> u16 {
+    let sum: u32 = header
+        .chunks(2)
+        .map(|word| u32::from(u16::from_be_bytes([word[0], *word.get(1).unwrap_or(&0)])))
+        .sum();
+    let sum: u32 = (sum & 0xFFFF) + (sum >> 16);
+    !(((sum & 0xFFFF) + (sum >> 16)) as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ipv4_checksum;
+
+    #[test]
+    fn checksum() {
+        // https://en.wikipedia.org/wiki/Internet_checksum
+        let header: [u8; 20] = [
+            0x45, 0x00, 0x00, 0x73, 0x00, 0x00, 0x40, 0x00, 0x40, 0x11, 0x00, 0x00, 0xC0, 0xA8,
+            0x00, 0x01, 0xC0, 0xA8, 0x00, 0xC7,
+        ];
+        assert_eq!(ipv4_checksum(&header), 0xB861);
+    }
+}
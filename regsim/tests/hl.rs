@@ -0,0 +1,272 @@
+//! Exercises the `w5500-hl` socket traits against the simulated W5500.
+//!
+//! The unit tests in `w5500-hl` use hand-written register mocks, these tests
+//! check that the pointer arithmetic in `w5500-hl` agrees with the circular
+//! buffer model of the simulator, including when the pointers wrap.
+
+use std::{
+    io::{Read as _, Write as _},
+    net::{TcpStream, UdpSocket},
+    time::{Duration, Instant},
+};
+use w5500_hl::{Error, StdTcpStream, Tcp, Udp, UdpHeader};
+use w5500_ll::{
+    net::{Ipv4Addr, SocketAddrV4},
+    Registers, Sn, SocketStatus,
+};
+use w5500_regsim::W5500;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+/// Length of the header the W5500 prepends to each UDP datagram.
+const HEADER_LEN: u16 = 8;
+
+/// Deterministic payload that does not repeat on power-of-two boundaries.
+fn pattern(len: usize, seed: usize) -> Vec<u8> {
+    (0..len).map(|i| ((i + seed) % 251) as u8).collect()
+}
+
+/// Bind a host UDP socket, returning the socket and its address.
+fn udp_peer() -> (UdpSocket, SocketAddrV4) {
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = SocketAddrV4::new(Ipv4Addr::LOCALHOST, peer.local_addr().unwrap().port());
+    (peer, addr)
+}
+
+/// Poll the simulator until the socket has at least `n` bytes to read.
+fn wait_for_rsr(w5500: &mut W5500, sn: Sn, n: u16) {
+    let start: Instant = Instant::now();
+    while w5500.sn_rx_rsr(sn).unwrap() < n {
+        assert!(start.elapsed() < TIMEOUT, "timeout waiting for {n} bytes");
+    }
+}
+
+/// Listen on `port` with `sn` and connect a host TCP stream to it.
+fn tcp_pair(w5500: &mut W5500, sn: Sn, port: u16) -> TcpStream {
+    w5500.tcp_listen(sn, port).unwrap();
+    let client = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    client.set_read_timeout(Some(TIMEOUT)).unwrap();
+
+    let start: Instant = Instant::now();
+    while w5500.sn_sr(sn).unwrap() != Ok(SocketStatus::Established) {
+        assert!(start.elapsed() < TIMEOUT, "timeout waiting for CON");
+    }
+    client
+}
+
+#[test]
+fn udp_recv_from() {
+    const PORT: u16 = 50143;
+    const LOCAL: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::LOCALHOST, PORT);
+
+    let (peer, peer_addr) = udp_peer();
+
+    let mut w5500 = W5500::default();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    w5500.udp_bind(Sn::Sn1, PORT).unwrap();
+
+    let mut buf: [u8; 32] = [0; 32];
+    assert_eq!(
+        w5500.udp_recv_from(Sn::Sn1, &mut buf),
+        Err(Error::WouldBlock)
+    );
+
+    peer.send_to(b"hello", LOCAL).unwrap();
+    wait_for_rsr(&mut w5500, Sn::Sn1, HEADER_LEN + 5);
+
+    assert_eq!(
+        w5500.udp_recv_from(Sn::Sn1, &mut buf).unwrap(),
+        (5, peer_addr)
+    );
+    assert_eq!(&buf[..5], b"hello");
+    assert_eq!(w5500.sn_rx_rsr(Sn::Sn1).unwrap(), 0);
+}
+
+#[test]
+fn udp_peek_then_recv() {
+    const PORT: u16 = 50144;
+    const LOCAL: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::LOCALHOST, PORT);
+
+    let (peer, peer_addr) = udp_peer();
+
+    let mut w5500 = W5500::default();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    w5500.udp_bind(Sn::Sn2, PORT).unwrap();
+
+    peer.send_to(b"first", LOCAL).unwrap();
+    peer.send_to(b"second", LOCAL).unwrap();
+    wait_for_rsr(&mut w5500, Sn::Sn2, 2 * HEADER_LEN + 11);
+
+    let header: UdpHeader = w5500.udp_peek_from_header(Sn::Sn2).unwrap();
+    assert_eq!(header.origin, peer_addr);
+    assert_eq!(header.len, 5);
+
+    // peeking does not consume the datagram
+    let mut buf: [u8; 8] = [0; 8];
+    assert_eq!(w5500.udp_peek_from(Sn::Sn2, &mut buf).unwrap(), (5, header));
+    assert_eq!(
+        w5500.udp_recv_from(Sn::Sn2, &mut buf).unwrap(),
+        (5, peer_addr)
+    );
+    assert_eq!(&buf[..5], b"first");
+
+    // a short buffer truncates the datagram, the remainder is discarded
+    let mut short: [u8; 3] = [0; 3];
+    assert_eq!(
+        w5500.udp_recv_from(Sn::Sn2, &mut short).unwrap(),
+        (3, peer_addr)
+    );
+    assert_eq!(&short, b"sec");
+    assert_eq!(
+        w5500.udp_recv_from(Sn::Sn2, &mut buf),
+        Err(Error::WouldBlock)
+    );
+}
+
+#[test]
+fn udp_recv_from_wraps() {
+    const PORT: u16 = 50145;
+    const LOCAL: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::LOCALHOST, PORT);
+    // not a divisor of the 2 KiB buffer, headers and payloads straddle the end
+    const LEN: usize = 173;
+
+    let (peer, peer_addr) = udp_peer();
+
+    let mut w5500 = W5500::default();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    w5500.udp_bind(Sn::Sn3, PORT).unwrap();
+
+    let mut buf: [u8; LEN] = [0; LEN];
+    for seed in 0..64 {
+        let expected: Vec<u8> = pattern(LEN, seed);
+        peer.send_to(&expected, LOCAL).unwrap();
+        wait_for_rsr(&mut w5500, Sn::Sn3, HEADER_LEN + LEN as u16);
+
+        assert_eq!(
+            w5500.udp_recv_from(Sn::Sn3, &mut buf).unwrap(),
+            (LEN as u16, peer_addr),
+            "datagram {seed}"
+        );
+        assert_eq!(buf.as_slice(), expected.as_slice(), "datagram {seed}");
+    }
+}
+
+#[test]
+fn udp_send_to_wraps() {
+    const PORT: u16 = 50146;
+    const LEN: usize = 301;
+
+    let (peer, peer_addr) = udp_peer();
+    peer.set_read_timeout(Some(TIMEOUT)).unwrap();
+
+    let mut w5500 = W5500::default();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    w5500.udp_bind(Sn::Sn4, PORT).unwrap();
+
+    let mut buf: [u8; LEN] = [0; LEN];
+    for seed in 0..32 {
+        let expected: Vec<u8> = pattern(LEN, seed);
+        assert_eq!(
+            w5500.udp_send_to(Sn::Sn4, &expected, &peer_addr).unwrap(),
+            LEN as u16
+        );
+        let (n, origin) = peer.recv_from(&mut buf).unwrap();
+        assert_eq!(origin.port(), PORT, "datagram {seed}");
+        assert_eq!(&buf[..n], expected.as_slice(), "datagram {seed}");
+    }
+}
+
+#[test]
+fn udp_send_to_vectored_wraps() {
+    const PORT: u16 = 50154;
+    const LEN: usize = 301;
+    const HEADER: [u8; 5] = [0xDE, 0xAD, 0xBE, 0xEF, 0x00];
+
+    let (peer, peer_addr) = udp_peer();
+    peer.set_read_timeout(Some(TIMEOUT)).unwrap();
+
+    let mut w5500 = W5500::default();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    w5500.udp_bind(Sn::Sn4, PORT).unwrap();
+
+    let mut buf: [u8; HEADER.len() + LEN] = [0; HEADER.len() + LEN];
+    for seed in 0..32 {
+        let payload: Vec<u8> = pattern(LEN, seed);
+        assert_eq!(
+            w5500
+                .udp_send_to_vectored(Sn::Sn4, &[&HEADER, &[], &payload], &peer_addr)
+                .unwrap(),
+            (HEADER.len() + LEN) as u16
+        );
+        let (n, origin) = peer.recv_from(&mut buf).unwrap();
+        assert_eq!(origin.port(), PORT, "datagram {seed}");
+        assert_eq!(n, HEADER.len() + LEN, "datagram {seed}");
+        assert_eq!(buf[..HEADER.len()], HEADER, "datagram {seed}");
+        assert_eq!(&buf[HEADER.len()..], payload.as_slice(), "datagram {seed}");
+    }
+}
+
+#[test]
+fn tcp_read_wraps() {
+    const PORT: u16 = 50147;
+    const LEN: usize = 389;
+
+    let mut w5500 = W5500::default();
+    let mut client: TcpStream = tcp_pair(&mut w5500, Sn::Sn5, PORT);
+
+    let mut buf: [u8; LEN] = [0; LEN];
+    for seed in 0..32 {
+        let expected: Vec<u8> = pattern(LEN, seed);
+        client.write_all(&expected).unwrap();
+        wait_for_rsr(&mut w5500, Sn::Sn5, LEN as u16);
+
+        assert_eq!(
+            w5500.tcp_read(Sn::Sn5, &mut buf).unwrap(),
+            LEN as u16,
+            "chunk {seed}"
+        );
+        assert_eq!(buf.as_slice(), expected.as_slice(), "chunk {seed}");
+    }
+    assert_eq!(w5500.tcp_read(Sn::Sn5, &mut buf).unwrap(), 0);
+}
+
+#[test]
+fn tcp_write_all_wraps() {
+    const PORT: u16 = 50148;
+    // larger than the 2 KiB transmit buffer
+    const LEN: usize = 5000;
+
+    let mut w5500 = W5500::default();
+    let mut client: TcpStream = tcp_pair(&mut w5500, Sn::Sn6, PORT);
+
+    let expected: Vec<u8> = pattern(LEN, 7);
+    w5500.tcp_write_all(Sn::Sn6, &expected).unwrap();
+
+    let mut buf: Vec<u8> = vec![0; LEN];
+    client.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, expected);
+}
+
+#[test]
+fn std_tcp_stream() {
+    const PORT: u16 = 50153;
+    const LEN: usize = 5000;
+
+    let mut w5500 = W5500::default();
+    let mut client: TcpStream = tcp_pair(&mut w5500, Sn::Sn7, PORT);
+
+    let mut stream = StdTcpStream::new(&mut w5500, Sn::Sn7);
+
+    let expected: Vec<u8> = pattern(LEN, 3);
+    stream.write_all(&expected).unwrap();
+    let mut buf: Vec<u8> = vec![0; LEN];
+    client.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, expected);
+
+    // the simulator drops data that does not fit in the 2 KiB RX buffer
+    let expected: Vec<u8> = pattern(1500, 5);
+    client.write_all(&expected).unwrap();
+    client.shutdown(std::net::Shutdown::Both).unwrap();
+    let mut received: Vec<u8> = Vec::new();
+    stream.read_to_end(&mut received).unwrap();
+    assert_eq!(received, expected);
+}
@@ -48,3 +48,1216 @@ fn remove_me() {
     w5500.set_sn_dest(Sn::Sn0, &ADDR).unwrap();
     assert_eq!(ADDR, w5500.sn_dest(Sn::Sn0).unwrap())
 }
+
+#[test]
+fn udp_send_mac() {
+    use std::net::UdpSocket;
+    use w5500_ll::{
+        net::{Ipv4Addr, SocketAddrV4},
+        Protocol, SocketCommand, SocketMode, SocketStatus,
+    };
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let peer_port: u16 = peer.local_addr().unwrap().port();
+
+    let mut w5500 = W5500::default();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    w5500.set_sn_port(Sn::Sn0, 0).unwrap();
+    w5500
+        .set_sn_mr(Sn::Sn0, SocketMode::DEFAULT.set_protocol(Protocol::Udp))
+        .unwrap();
+    w5500.set_sn_cr(Sn::Sn0, SocketCommand::Open).unwrap();
+    assert_eq!(w5500.sn_sr(Sn::Sn0).unwrap(), Ok(SocketStatus::Udp));
+
+    const DATA: [u8; 4] = [0xDE, 0xAD, 0xBE, 0xEF];
+    w5500
+        .set_sn_dest(Sn::Sn0, &SocketAddrV4::new(Ipv4Addr::LOCALHOST, peer_port))
+        .unwrap();
+    let ptr: u16 = w5500.sn_tx_wr(Sn::Sn0).unwrap();
+    w5500.set_sn_tx_buf(Sn::Sn0, ptr, &DATA).unwrap();
+    w5500
+        .set_sn_tx_wr(Sn::Sn0, ptr.wrapping_add(DATA.len() as u16))
+        .unwrap();
+    w5500.set_sn_cr(Sn::Sn0, SocketCommand::SendMac).unwrap();
+
+    let mut buf: [u8; 8] = [0; 8];
+    let n: usize = peer.recv(&mut buf).unwrap();
+    assert_eq!(&buf[..n], &DATA);
+}
+
+#[test]
+fn udp_multicast() {
+    use std::net::UdpSocket;
+    use w5500_ll::{
+        net::{Ipv4Addr, SocketAddrV4},
+        Protocol, SocketCommand, SocketMode, SocketStatus,
+    };
+
+    const GROUP: Ipv4Addr = Ipv4Addr::new(239, 255, 0, 1);
+    const PORT: u16 = 50123;
+
+    let mut w5500 = W5500::default();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    w5500.set_sn_port(Sn::Sn0, PORT).unwrap();
+    w5500
+        .set_sn_dest(Sn::Sn0, &SocketAddrV4::new(GROUP, PORT))
+        .unwrap();
+    w5500
+        .set_sn_mr(
+            Sn::Sn0,
+            SocketMode::DEFAULT
+                .set_protocol(Protocol::Udp)
+                .enable_multi(),
+        )
+        .unwrap();
+    w5500.set_sn_cr(Sn::Sn0, SocketCommand::Open).unwrap();
+    assert_eq!(w5500.sn_sr(Sn::Sn0).unwrap(), Ok(SocketStatus::Udp));
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    peer.set_multicast_loop_v4(true).unwrap();
+    peer.send_to(&[1, 2, 3], SocketAddrV4::new(GROUP, PORT))
+        .unwrap();
+
+    let mut rsr: u16 = 0;
+    for _ in 0..100 {
+        rsr = w5500.sn_rx_rsr(Sn::Sn0).unwrap();
+        if rsr != 0 {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    // 8 byte UDP header + 3 bytes of data
+    assert_eq!(rsr, 11);
+
+    w5500.set_sn_cr(Sn::Sn0, SocketCommand::Close).unwrap();
+    assert_eq!(w5500.sn_sr(Sn::Sn0).unwrap(), Ok(SocketStatus::Closed));
+}
+
+#[test]
+fn open_socket_macraw_not_sn0() {
+    use w5500_ll::{Protocol, SocketMode, SocketStatus};
+
+    const MODE: SocketMode = SocketMode::DEFAULT.set_protocol(Protocol::Macraw);
+
+    let mut w5500 = W5500::default();
+    // only Sn0 supports MACRAW, the status is returned without waiting
+    assert_eq!(
+        w5500.open_socket(Sn::Sn1, MODE, 0).unwrap(),
+        Ok(SocketStatus::Closed)
+    );
+}
+
+#[test]
+fn probe() {
+    let mut w5500 = W5500::default();
+    w5500.set_pmagic(0x12).unwrap();
+    let probe = w5500.probe().unwrap();
+    assert_eq!(probe.version, VERSION);
+    assert!(probe.scratch);
+    assert!(probe.is_ok());
+    assert_eq!(w5500.pmagic().unwrap(), 0x12);
+}
+
+#[test]
+fn hl_udp_multicast_bind() {
+    use std::net::UdpSocket;
+    use w5500_hl::{block, Udp};
+    use w5500_ll::net::{Eui48Addr, Ipv4Addr, SocketAddrV4};
+
+    const GROUP: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(239, 255, 0, 2), 50124);
+
+    let mut w5500 = W5500::default();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    w5500
+        .udp_multicast_bind(Sn::Sn1, GROUP.port(), &GROUP)
+        .unwrap();
+    assert_eq!(
+        w5500.sn_dhar(Sn::Sn1).unwrap(),
+        Eui48Addr::new(0x01, 0x00, 0x5E, 0x7F, 0x00, 0x02)
+    );
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    peer.set_multicast_loop_v4(true).unwrap();
+    peer.send_to(b"hello", GROUP).unwrap();
+
+    let mut buf: [u8; 16] = [0; 16];
+    let (n, origin) = block!(w5500.udp_recv_from(Sn::Sn1, &mut buf)).unwrap();
+    assert_eq!(&buf[..n.into()], b"hello");
+    assert_eq!(origin.port(), peer.local_addr().unwrap().port());
+}
+
+#[test]
+fn inspect_udp_send() {
+    use std::net::UdpSocket;
+    use w5500_hl::Udp;
+    use w5500_ll::{
+        net::{Ipv4Addr, SocketAddrV4},
+        SocketStatus,
+    };
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let dest = SocketAddrV4::new(Ipv4Addr::LOCALHOST, peer.local_addr().unwrap().port());
+
+    let mut w5500 = W5500::default();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    assert_eq!(w5500.socket_status(Sn::Sn2), SocketStatus::Closed);
+    w5500.udp_bind(Sn::Sn2, 0).unwrap();
+    assert_eq!(w5500.socket_status(Sn::Sn2), SocketStatus::Udp);
+
+    w5500.udp_send_to(Sn::Sn2, b"abc", &dest).unwrap();
+    assert_eq!(&w5500.tx_buffer(Sn::Sn2)[..3], b"abc");
+    assert!(!w5500.raised_interrupts(Sn::Sn2).any_raised());
+}
+
+#[test]
+fn udp_latency() {
+    use std::{
+        net::UdpSocket,
+        time::{Duration, Instant},
+    };
+    use w5500_hl::{block, Udp};
+    use w5500_ll::net::{Ipv4Addr, SocketAddrV4};
+
+    const LATENCY: Duration = Duration::from_millis(100);
+    const PORT: u16 = 50125;
+
+    let mut w5500 = W5500::default();
+    w5500.set_latency(LATENCY);
+    w5500.set_jitter(Duration::from_millis(10));
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    w5500.udp_bind(Sn::Sn0, PORT).unwrap();
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let start: Instant = Instant::now();
+    peer.send_to(b"ping", SocketAddrV4::new(Ipv4Addr::LOCALHOST, PORT))
+        .unwrap();
+
+    let mut buf: [u8; 4] = [0; 4];
+    let (n, _) = block!(w5500.udp_recv_from(Sn::Sn0, &mut buf)).unwrap();
+    assert!(start.elapsed() >= LATENCY);
+    assert_eq!(&buf[..n.into()], b"ping");
+}
+
+#[test]
+fn fault_propagates_through_hl() {
+    use std::io::ErrorKind;
+    use w5500_hl::{Error, Udp};
+
+    let mut w5500 = W5500::default();
+    w5500.inject_fault_after_ops(0, ErrorKind::TimedOut);
+    assert_eq!(
+        w5500.udp_recv_from(Sn::Sn0, &mut []),
+        Err(Error::Other(ErrorKind::TimedOut))
+    );
+    assert_eq!(w5500.version(), Ok(VERSION));
+}
+
+#[test]
+fn sw_reset() {
+    let mut w5500 = W5500::default();
+    w5500.set_pmagic(0x12).unwrap();
+    assert_eq!(w5500.sw_reset(), Ok(Ok(())));
+    assert_eq!(w5500.pmagic().unwrap(), 0x00);
+}
+
+#[test]
+fn hl_udp_connect() {
+    use std::net::UdpSocket;
+    use w5500_hl::{block, Udp};
+    use w5500_ll::net::{Ipv4Addr, SocketAddrV4};
+
+    const PORT: u16 = 50126;
+    const LOCAL: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::LOCALHOST, PORT);
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let other = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let peer_addr = SocketAddrV4::new(Ipv4Addr::LOCALHOST, peer.local_addr().unwrap().port());
+
+    let mut w5500 = W5500::default();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    w5500.udp_connect(Sn::Sn3, PORT, &peer_addr).unwrap();
+
+    w5500.udp_send(Sn::Sn3, b"ping").unwrap();
+    let mut buf: [u8; 16] = [0; 16];
+    let (n, origin) = peer.recv_from(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"ping");
+    assert_eq!(origin.port(), PORT);
+
+    other.send_to(b"spam", LOCAL).unwrap();
+    peer.send_to(b"pong", LOCAL).unwrap();
+
+    let n: u16 = block!(w5500.udp_recv(Sn::Sn3, &mut buf)).unwrap();
+    assert_eq!(&buf[..n.into()], b"pong");
+}
+
+#[test]
+fn hl_socket() {
+    use std::net::UdpSocket;
+    use w5500_hl::{block, Socket};
+    use w5500_ll::net::{Ipv4Addr, SocketAddrV4};
+
+    const PORT: u16 = 50127;
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let peer_addr = SocketAddrV4::new(Ipv4Addr::LOCALHOST, peer.local_addr().unwrap().port());
+
+    let mut w5500 = W5500::default();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+
+    let mut socket = Socket::new(&mut w5500, Sn::Sn4);
+    assert_eq!(socket.sn(), Sn::Sn4);
+    socket.bind(PORT).unwrap();
+    assert_eq!(
+        socket.local_addr().unwrap(),
+        SocketAddrV4::new(Ipv4Addr::LOCALHOST, PORT)
+    );
+    socket.send_to(b"ping", &peer_addr).unwrap();
+
+    let mut buf: [u8; 16] = [0; 16];
+    let (n, origin) = peer.recv_from(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"ping");
+    peer.send_to(b"pong", origin).unwrap();
+
+    let (n, origin) = block!(socket.recv_from(&mut buf)).unwrap();
+    assert_eq!(&buf[..n.into()], b"pong");
+    assert_eq!(origin, peer_addr);
+    socket.close().unwrap();
+}
+
+#[test]
+fn link_status() {
+    let mut w5500 = W5500::default();
+    assert_eq!(w5500.link_up(), Ok(true));
+    assert_eq!(w5500.wait_for_link(0), Ok(true));
+
+    w5500.set_link(false);
+    assert_eq!(w5500.link_up(), Ok(false));
+    assert_eq!(w5500.wait_for_link(3), Ok(false));
+
+    // the link status is read-only
+    w5500.set_phycfgr(w5500_ll::PhyCfg::DEFAULT).unwrap();
+    assert_eq!(w5500.link_up(), Ok(false));
+    w5500.set_link(true);
+    assert_eq!(w5500.link_up(), Ok(true));
+}
+
+#[test]
+fn udp_link_down() {
+    use std::{net::UdpSocket, time::Duration};
+    use w5500_hl::{block, Error, Udp};
+    use w5500_ll::net::{Ipv4Addr, SocketAddrV4};
+
+    const PORT: u16 = 50128;
+
+    let mut w5500 = W5500::default();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    w5500.udp_bind(Sn::Sn0, PORT).unwrap();
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    peer.set_nonblocking(true).unwrap();
+    let dest = SocketAddrV4::new(Ipv4Addr::LOCALHOST, peer.local_addr().unwrap().port());
+    let w5500_addr = SocketAddrV4::new(Ipv4Addr::LOCALHOST, PORT);
+
+    w5500.set_link(false);
+    w5500.udp_send_to(Sn::Sn0, b"lost", &dest).unwrap();
+    peer.send_to(b"lost", w5500_addr).unwrap();
+    std::thread::sleep(Duration::from_millis(50));
+
+    let mut buf: [u8; 4] = [0; 4];
+    assert_eq!(
+        peer.recv_from(&mut buf).unwrap_err().kind(),
+        std::io::ErrorKind::WouldBlock
+    );
+    assert_eq!(
+        w5500.udp_recv_from(Sn::Sn0, &mut buf),
+        Err(Error::WouldBlock)
+    );
+
+    w5500.set_link(true);
+    peer.send_to(b"ping", w5500_addr).unwrap();
+    let (n, _) = block!(w5500.udp_recv_from(Sn::Sn0, &mut buf)).unwrap();
+    assert_eq!(&buf[..n.into()], b"ping");
+}
+
+#[test]
+fn register_dump() {
+    use w5500_hl::Udp;
+    use w5500_ll::{RegisterDump, SocketStatus};
+
+    let mut w5500 = W5500::default();
+    w5500.udp_bind(Sn::Sn3, 0).unwrap();
+
+    let dump: RegisterDump = w5500.register_dump().unwrap();
+    assert_eq!(dump.common[usize::from(Reg::VERSIONR.addr())], VERSION);
+    assert_eq!(dump.common[usize::from(Reg::RTR0.addr())], 0x07);
+    assert_eq!(dump.sn[3].status.sr, Ok(SocketStatus::Udp));
+    assert_eq!(dump.sn[0].status.sr, Ok(SocketStatus::Closed));
+    assert_eq!(dump.sn[3].tx.fsr, 2048);
+}
+
+#[test]
+fn hl_udp_send_recv() {
+    use std::net::UdpSocket;
+    use w5500_hl::{Error, Udp};
+    use w5500_ll::net::{Ipv4Addr, SocketAddrV4};
+
+    const PORT: u16 = 50129;
+    const LOCAL: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::LOCALHOST, PORT);
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let other = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let peer_addr = SocketAddrV4::new(Ipv4Addr::LOCALHOST, peer.local_addr().unwrap().port());
+
+    let mut w5500 = W5500::default();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    w5500.udp_bind(Sn::Sn5, PORT).unwrap();
+
+    let mut buf: [u8; 16] = [0; 16];
+    assert_eq!(
+        w5500.udp_send_recv(Sn::Sn5, b"ping", &peer_addr, &mut buf, 0, 10),
+        Err(Error::WouldBlock)
+    );
+    let (n, _) = peer.recv_from(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"ping");
+
+    // drop the first request, respond to the retransmission
+    let responder = std::thread::spawn(move || {
+        let mut buf: [u8; 16] = [0; 16];
+        peer.recv_from(&mut buf).unwrap();
+        let (n, origin) = peer.recv_from(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"ping");
+        other.send_to(b"spam", LOCAL).unwrap();
+        peer.send_to(b"pong", origin).unwrap();
+    });
+
+    let n: u16 = w5500
+        .udp_send_recv(Sn::Sn5, b"ping", &peer_addr, &mut buf, 10, 10_000)
+        .unwrap();
+    assert_eq!(&buf[..n.into()], b"pong");
+    responder.join().unwrap();
+
+    // payloads larger than the 2 KiB transmit buffer are never sent
+    assert_eq!(
+        w5500.udp_send_recv(Sn::Sn5, &[0; 2049], &peer_addr, &mut buf, 0, 10),
+        Err(Error::OutOfMemory)
+    );
+}
+
+#[test]
+fn hl_configure_buffers() {
+    use w5500_hl::{BufferConfigError, Common};
+    use w5500_ll::BufferSize;
+
+    let mut w5500 = W5500::default();
+
+    let mut rx: [BufferSize; 8] = [BufferSize::KB0; 8];
+    rx[0] = BufferSize::KB8;
+    rx[1] = BufferSize::KB4;
+    rx[2] = BufferSize::KB4;
+    let tx: [BufferSize; 8] = [BufferSize::KB2; 8];
+    w5500.configure_buffers(rx, tx).unwrap();
+    assert_eq!(w5500.sn_rxbuf_size(Sn::Sn0).unwrap(), Ok(BufferSize::KB8));
+    assert_eq!(w5500.sn_rxbuf_size(Sn::Sn3).unwrap(), Ok(BufferSize::KB0));
+    assert_eq!(w5500.sn_txbuf_size(Sn::Sn7).unwrap(), Ok(BufferSize::KB2));
+
+    // nothing is written when the budget is exceeded
+    let mut tx_over: [BufferSize; 8] = [BufferSize::KB0; 8];
+    tx_over[0] = BufferSize::KB16;
+    tx_over[1] = BufferSize::KB1;
+    assert_eq!(
+        w5500.configure_buffers([BufferSize::KB1; 8], tx_over),
+        Err(BufferConfigError::TxExceeded)
+    );
+    assert_eq!(
+        w5500.configure_buffers([BufferSize::KB4; 8], tx),
+        Err(BufferConfigError::RxExceeded)
+    );
+    assert_eq!(w5500.sn_rxbuf_size(Sn::Sn0).unwrap(), Ok(BufferSize::KB8));
+    assert_eq!(w5500.sn_txbuf_size(Sn::Sn0).unwrap(), Ok(BufferSize::KB2));
+}
+
+#[test]
+fn udp_pcap() {
+    use std::net::UdpSocket;
+    use w5500_hl::{block, Udp};
+    use w5500_ll::net::{Ipv4Addr, SocketAddrV4};
+
+    const PORT: u16 = 50130;
+
+    let path = std::env::temp_dir().join(format!("w5500-regsim-{}.pcap", std::process::id()));
+
+    let mut w5500 = W5500::default();
+    w5500.enable_pcap(&path).unwrap();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    w5500.udp_bind(Sn::Sn0, PORT).unwrap();
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let dest = SocketAddrV4::new(Ipv4Addr::LOCALHOST, peer.local_addr().unwrap().port());
+
+    w5500.udp_send_to(Sn::Sn0, b"ping", &dest).unwrap();
+    let mut buf: [u8; 4] = [0; 4];
+    peer.recv_from(&mut buf).unwrap();
+    peer.send_to(b"pong", SocketAddrV4::new(Ipv4Addr::LOCALHOST, PORT))
+        .unwrap();
+    block!(w5500.udp_recv_from(Sn::Sn0, &mut buf)).unwrap();
+
+    let pcap: Vec<u8> = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    // global header, then two records of 16 byte record header, 14 byte
+    // Ethernet header, 20 byte IPv4 header, 8 byte UDP header, 4 byte payload
+    const RECORD_LEN: usize = 16 + 14 + 20 + 8 + 4;
+    assert_eq!(pcap.len(), 24 + 2 * RECORD_LEN);
+    assert_eq!(pcap[..4], 0xA1B2_C3D4_u32.to_le_bytes());
+
+    let sent: &[u8] = &pcap[24..24 + RECORD_LEN];
+    assert_eq!(sent[16 + 14 + 20..16 + 14 + 22], PORT.to_be_bytes());
+    assert_eq!(&sent[RECORD_LEN - 4..], b"ping");
+
+    let received: &[u8] = &pcap[24 + RECORD_LEN..];
+    assert_eq!(received[16 + 14 + 22..16 + 14 + 24], PORT.to_be_bytes());
+    assert_eq!(&received[RECORD_LEN - 4..], b"pong");
+}
+
+#[test]
+fn int_pin() {
+    use std::{net::UdpSocket, time::Instant};
+    use w5500_hl::Udp;
+    use w5500_ll::{
+        net::{Ipv4Addr, SocketAddrV4},
+        SocketInterrupt,
+    };
+
+    const PORT: u16 = 50131;
+
+    let mut w5500 = W5500::default();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    w5500.udp_bind(Sn::Sn1, PORT).unwrap();
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    peer.send_to(b"ping", SocketAddrV4::new(Ipv4Addr::LOCALHOST, PORT))
+        .unwrap();
+
+    let start: Instant = Instant::now();
+    while !w5500.sn_ir(Sn::Sn1).unwrap().recv_raised() {
+        assert!(start.elapsed().as_secs() < 5, "timeout waiting for RECV");
+    }
+
+    // socket interrupts are masked in SIMR by default
+    assert!(!w5500.int_pin_asserted());
+    w5500.set_simr(Sn::Sn1.bitmask()).unwrap();
+    assert!(w5500.int_pin_asserted());
+
+    w5500
+        .set_sn_ir(Sn::Sn1, SocketInterrupt::RECV_MASK)
+        .unwrap();
+    assert!(!w5500.int_pin_asserted());
+}
+
+#[test]
+fn pending_socket_interrupts() {
+    use std::{net::UdpSocket, time::Instant};
+    use w5500_hl::Udp;
+    use w5500_ll::{
+        net::{Ipv4Addr, SocketAddrV4},
+        SocketInterrupt,
+    };
+
+    const PORT: u16 = 50132;
+
+    let mut w5500 = W5500::default();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    w5500.udp_bind(Sn::Sn2, PORT).unwrap();
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    peer.send_to(b"ping", SocketAddrV4::new(Ipv4Addr::LOCALHOST, PORT))
+        .unwrap();
+
+    let start: Instant = Instant::now();
+    // SIR does not poll the host sockets
+    while !w5500.sn_ir(Sn::Sn2).unwrap().recv_raised() {
+        assert!(start.elapsed().as_secs() < 5, "timeout waiting for RECV");
+    }
+
+    let pending: [Option<SocketInterrupt>; 8] = w5500.pending_socket_interrupts().unwrap();
+    for (idx, sn_ir) in pending.iter().enumerate() {
+        if idx == usize::from(Sn::Sn2) {
+            assert!(sn_ir.unwrap().recv_raised());
+        } else {
+            assert_eq!(*sn_ir, None);
+        }
+    }
+
+    assert!(!w5500.sn_ir(Sn::Sn2).unwrap().any_raised());
+    assert_eq!(w5500.sir().unwrap(), 0);
+    assert!(!w5500.clear_sn_ir_all(Sn::Sn2).unwrap().any_raised());
+}
+
+#[test]
+fn hl_tcp_writer_fmt() {
+    use core::fmt::Write as _;
+    use std::{io::Read, net::TcpStream, time::Instant};
+    use w5500_hl::{
+        io::{Seek, Write},
+        Tcp, TcpWriter,
+    };
+    use w5500_ll::SocketStatus;
+
+    const PORT: u16 = 50133;
+
+    let mut w5500 = W5500::default();
+    w5500.tcp_listen(Sn::Sn0, PORT).unwrap();
+
+    let mut client = TcpStream::connect(("127.0.0.1", PORT)).unwrap();
+
+    let start: Instant = Instant::now();
+    while w5500.sn_sr(Sn::Sn0).unwrap() != Ok(SocketStatus::Established) {
+        assert!(start.elapsed().as_secs() < 5, "timeout waiting for CON");
+    }
+
+    let code: u16 = 200;
+    let mut writer: TcpWriter<_> = w5500.tcp_writer(Sn::Sn0).unwrap();
+    write!(writer, "HTTP/1.1 {code} OK\r\n").unwrap();
+    assert_eq!(writer.stream_position(), 17);
+    writer.send().unwrap();
+
+    let mut buf: [u8; 17] = [0; 17];
+    client.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"HTTP/1.1 200 OK\r\n");
+}
+
+#[test]
+fn hl_tcp_reader_peek() {
+    use std::{io::Write as _, net::TcpStream, time::Instant};
+    use w5500_hl::{
+        io::{Read, Seek},
+        Tcp, TcpReader,
+    };
+    use w5500_ll::SocketStatus;
+
+    const PORT: u16 = 50134;
+
+    let mut w5500 = W5500::default();
+    w5500.tcp_listen(Sn::Sn0, PORT).unwrap();
+
+    let mut client = TcpStream::connect(("127.0.0.1", PORT)).unwrap();
+
+    let start: Instant = Instant::now();
+    while w5500.sn_sr(Sn::Sn0).unwrap() != Ok(SocketStatus::Established) {
+        assert!(start.elapsed().as_secs() < 5, "timeout waiting for CON");
+    }
+
+    client.write_all(&[0x03, b'a', b'b', b'c']).unwrap();
+
+    while w5500.sn_rx_rsr(Sn::Sn0).unwrap() < 4 {
+        assert!(start.elapsed().as_secs() < 5, "timeout waiting for RECV");
+    }
+
+    let mut reader: TcpReader<_> = w5500.tcp_reader(Sn::Sn0).unwrap();
+    let mut header: [u8; 1] = [0];
+    assert_eq!(reader.peek(&mut header).unwrap(), 1);
+    assert_eq!(header, [0x03]);
+    assert_eq!(reader.stream_position(), 0);
+
+    let mut buf: [u8; 8] = [0; 8];
+    assert_eq!(reader.peek(&mut buf).unwrap(), 4);
+    assert_eq!(&buf[..4], &[0x03, b'a', b'b', b'c']);
+
+    let len: usize = 1 + usize::from(header[0]);
+    reader.read_exact(&mut buf[..len]).unwrap();
+    assert_eq!(reader.peek(&mut buf).unwrap(), 0);
+    reader.done().unwrap();
+    assert_eq!(w5500.sn_rx_rsr(Sn::Sn0).unwrap(), 0);
+}
+
+#[test]
+fn udp_force_arp() {
+    use std::net::UdpSocket;
+    use w5500_hl::Udp;
+    use w5500_ll::{
+        net::{Eui48Addr, Ipv4Addr, SocketAddrV4},
+        Mode,
+    };
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let dest = SocketAddrV4::new(Ipv4Addr::LOCALHOST, peer.local_addr().unwrap().port());
+
+    let mut w5500 = W5500::default();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    w5500.udp_bind(Sn::Sn0, 0).unwrap();
+
+    // without force ARP SN_DHAR is unchanged
+    w5500.udp_send_to(Sn::Sn0, b"ping", &dest).unwrap();
+    assert_eq!(
+        w5500.sn_dhar(Sn::Sn0).unwrap(),
+        Eui48Addr::new(0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF)
+    );
+
+    w5500.set_mr(Mode::DEFAULT.enable_farp()).unwrap();
+    w5500.udp_send_to(Sn::Sn0, b"ping", &dest).unwrap();
+    assert_eq!(
+        w5500.sn_dhar(Sn::Sn0).unwrap(),
+        Eui48Addr::new(0x02, 0x00, 127, 0, 0, 1)
+    );
+
+    let mut buf: [u8; 4] = [0; 4];
+    for _ in 0..2 {
+        assert_eq!(peer.recv(&mut buf).unwrap(), 4);
+    }
+}
+
+#[test]
+fn tcp_connect_timeout() {
+    use std::{
+        io,
+        net::{TcpListener, TcpStream},
+        time::{Duration, Instant},
+    };
+    use w5500_hl::Tcp;
+    use w5500_ll::{
+        net::{Ipv4Addr, SocketAddrV4},
+        SocketStatus,
+    };
+
+    // Linux drops SYN packets to a listener with a full accept queue, fill
+    // the queue of a listener that never accepts to get a local address
+    // that does not respond
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut queued: Vec<TcpStream> = Vec::new();
+    loop {
+        match TcpStream::connect_timeout(&addr, Duration::from_millis(100)) {
+            Ok(stream) => queued.push(stream),
+            Err(e) if e.kind() == io::ErrorKind::TimedOut => break,
+            Err(e) => panic!("unexpected error filling the accept queue: {e}"),
+        }
+        assert!(queued.len() < 4096, "accept queue did not fill");
+    }
+    let unreachable = SocketAddrV4::new(Ipv4Addr::LOCALHOST, addr.port());
+
+    let mut w5500 = W5500::default();
+    // 100 ms retry time, 2 attempts
+    w5500.set_rtr(1000).unwrap();
+    w5500.set_rcr(1).unwrap();
+
+    // the CONNECT command does not block
+    let start: Instant = Instant::now();
+    w5500.tcp_connect(Sn::Sn0, 50135, &unreachable).unwrap();
+    assert!(start.elapsed() < Duration::from_millis(200));
+    assert_eq!(w5500.sn_sr(Sn::Sn0).unwrap(), Ok(SocketStatus::SynSent));
+
+    while !w5500.sn_ir(Sn::Sn0).unwrap().timeout_raised() {
+        assert!(start.elapsed().as_secs() < 5, "timeout waiting for TIMEOUT");
+    }
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    assert!(!w5500.sn_ir(Sn::Sn0).unwrap().con_raised());
+    assert_eq!(w5500.sn_sr(Sn::Sn0).unwrap(), Ok(SocketStatus::Closed));
+}
+
+#[test]
+fn hl_tcp_set_keepalive() {
+    use w5500_hl::Tcp;
+
+    let mut w5500 = W5500::default();
+    for (secs, kpalvtr) in [
+        (None, 0),
+        (Some(0), 1),
+        (Some(5), 1),
+        (Some(60), 12),
+        (Some(62), 12),
+        (Some(63), 13),
+        (Some(u8::MAX), 51),
+    ] {
+        w5500.tcp_set_keepalive(Sn::Sn0, secs).unwrap();
+        assert_eq!(w5500.sn_kpalvtr(Sn::Sn0).unwrap(), kpalvtr, "{secs:?}");
+    }
+}
+
+#[test]
+fn hl_udp_subnet_broadcast() {
+    use std::net::UdpSocket;
+    use w5500_hl::Udp;
+    use w5500_ll::net::Ipv4Addr;
+
+    const PORT: u16 = 50136;
+
+    let peer = UdpSocket::bind("0.0.0.0:0").unwrap();
+    let peer_port: u16 = peer.local_addr().unwrap().port();
+
+    let mut w5500 = W5500::default();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    w5500.set_subr(&Ipv4Addr::new(255, 0, 0, 0)).unwrap();
+    w5500.udp_bind(Sn::Sn0, PORT).unwrap();
+
+    assert_eq!(
+        w5500
+            .udp_subnet_broadcast(Sn::Sn0, b"hello", peer_port)
+            .unwrap(),
+        5
+    );
+    assert_eq!(
+        w5500.sn_dest(Sn::Sn0).unwrap(),
+        w5500_ll::net::SocketAddrV4::new(Ipv4Addr::new(127, 255, 255, 255), peer_port)
+    );
+
+    let mut buf: [u8; 5] = [0; 5];
+    peer.recv_from(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+}
+
+#[test]
+fn udp_unreachable() {
+    use std::net::UdpSocket;
+    use w5500_hl::Udp;
+    use w5500_ll::{
+        net::{Ipv4Addr, SocketAddrV4},
+        Interrupt,
+    };
+
+    const PORT: u16 = 50137;
+
+    // find a closed port by binding and dropping a socket
+    let closed: SocketAddrV4 = {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        SocketAddrV4::new(Ipv4Addr::LOCALHOST, socket.local_addr().unwrap().port())
+    };
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let open: SocketAddrV4 =
+        SocketAddrV4::new(Ipv4Addr::LOCALHOST, peer.local_addr().unwrap().port());
+
+    let mut w5500 = W5500::default();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    w5500.udp_bind(Sn::Sn0, PORT).unwrap();
+
+    w5500.udp_send_to(Sn::Sn0, b"hello", &open).unwrap();
+    assert!(!w5500.ir().unwrap().unreach());
+
+    w5500.udp_send_to(Sn::Sn0, b"hello", &closed).unwrap();
+    assert!(w5500.ir().unwrap().unreach());
+    assert_eq!(w5500.uipr().unwrap(), *closed.ip());
+    assert_eq!(w5500.uportr().unwrap(), closed.port());
+
+    w5500.set_ir(Interrupt::DEFAULT.set_unreach()).unwrap();
+    assert!(!w5500.ir().unwrap().unreach());
+}
+
+#[test]
+fn tcp_peer_close() {
+    use std::{io::Write as _, net::TcpStream, time::Instant};
+    use w5500_hl::Tcp;
+    use w5500_ll::SocketStatus;
+
+    const PORT: u16 = 50138;
+
+    let mut w5500 = W5500::default();
+    w5500.tcp_listen(Sn::Sn0, PORT).unwrap();
+
+    let mut client = TcpStream::connect(("127.0.0.1", PORT)).unwrap();
+
+    let start: Instant = Instant::now();
+    while w5500.sn_sr(Sn::Sn0).unwrap() != Ok(SocketStatus::Established) {
+        assert!(start.elapsed().as_secs() < 5, "timeout waiting for CON");
+    }
+
+    client.write_all(b"bye").unwrap();
+    drop(client);
+
+    while w5500.sn_sr(Sn::Sn0).unwrap() != Ok(SocketStatus::CloseWait) {
+        assert!(start.elapsed().as_secs() < 5, "timeout waiting for FIN");
+    }
+    assert!(w5500.sn_ir(Sn::Sn0).unwrap().discon_raised());
+
+    // data received before the FIN can still be read
+    let mut buf: [u8; 8] = [0; 8];
+    assert_eq!(w5500.tcp_read(Sn::Sn0, &mut buf).unwrap(), 3);
+    assert_eq!(&buf[..3], b"bye");
+
+    w5500.tcp_disconnect(Sn::Sn0).unwrap();
+    assert_eq!(w5500.sn_sr(Sn::Sn0).unwrap(), Ok(SocketStatus::Closed));
+}
+
+#[test]
+fn builder() {
+    use w5500_ll::{
+        net::{Eui48Addr, Ipv4Addr},
+        BufferSize,
+    };
+    use w5500_regsim::W5500Builder;
+
+    const SIPR: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 10);
+    const SUBR: Ipv4Addr = Ipv4Addr::new(255, 255, 255, 0);
+    const GAR: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 1);
+    const SHAR: Eui48Addr = Eui48Addr::new(0x02, 0x00, 0x11, 0x22, 0x33, 0x44);
+
+    let mut w5500: W5500 = W5500Builder::new()
+        .sipr(SIPR)
+        .subr(SUBR)
+        .gar(GAR)
+        .shar(SHAR)
+        .sn_rxbuf_size(Sn::Sn1, BufferSize::KB8)
+        .sn_txbuf_size(Sn::Sn1, BufferSize::KB4)
+        .build();
+
+    assert_eq!(w5500.sipr().unwrap(), SIPR);
+    assert_eq!(w5500.subr().unwrap(), SUBR);
+    assert_eq!(w5500.gar().unwrap(), GAR);
+    assert_eq!(w5500.shar().unwrap(), SHAR);
+    assert_eq!(w5500.sn_rxbuf_size(Sn::Sn1).unwrap(), Ok(BufferSize::KB8));
+    assert_eq!(w5500.sn_txbuf_size(Sn::Sn1).unwrap(), Ok(BufferSize::KB4));
+    assert_eq!(w5500.sn_tx_fsr(Sn::Sn1).unwrap(), 4096);
+    assert_eq!(w5500.sn_rxbuf_size(Sn::Sn0).unwrap(), Ok(BufferSize::KB2));
+}
+
+#[test]
+fn builder_buffer_sizes() {
+    use w5500_hl::Udp;
+    use w5500_ll::BufferSize;
+    use w5500_regsim::W5500Builder;
+
+    let mut rx: [BufferSize; 8] = [BufferSize::KB1; 8];
+    rx[0] = BufferSize::KB16;
+    let mut tx: [BufferSize; 8] = [BufferSize::KB2; 8];
+    tx[7] = BufferSize::KB0;
+
+    let mut w5500: W5500 = W5500Builder::new().buffer_sizes(rx, tx).build();
+
+    for sn in w5500_ll::SOCKETS {
+        let idx: usize = usize::from(sn);
+        assert_eq!(w5500.sn_rxbuf_size(sn).unwrap(), Ok(rx[idx]));
+        assert_eq!(w5500.sn_txbuf_size(sn).unwrap(), Ok(tx[idx]));
+        assert_eq!(w5500.rx_buffer(sn).len(), rx[idx].size_in_bytes());
+        assert_eq!(w5500.tx_buffer(sn).len(), tx[idx].size_in_bytes());
+    }
+    assert_eq!(w5500.sn_tx_fsr(Sn::Sn7).unwrap(), 0);
+
+    // sockets can be used without writing the buffer size registers
+    w5500.udp_bind(Sn::Sn0, 50149).unwrap();
+    assert_eq!(w5500.sn_tx_fsr(Sn::Sn0).unwrap(), 2048);
+}
+
+#[test]
+fn tcp_listen_rebind() {
+    use std::{io::Read as _, net::TcpStream, time::Instant};
+    use w5500_hl::Tcp;
+    use w5500_ll::SocketStatus;
+
+    const PORT: u16 = 50139;
+
+    let mut w5500 = W5500::default();
+
+    for _ in 0..3 {
+        w5500.tcp_listen(Sn::Sn0, PORT).unwrap();
+        assert_eq!(w5500.sn_sr(Sn::Sn0).unwrap(), Ok(SocketStatus::Listen));
+
+        let mut client = TcpStream::connect(("127.0.0.1", PORT)).unwrap();
+
+        let start: Instant = Instant::now();
+        while w5500.sn_sr(Sn::Sn0).unwrap() != Ok(SocketStatus::Established) {
+            assert!(start.elapsed().as_secs() < 5, "timeout waiting for CON");
+        }
+
+        // the simulated W5500 closes first, leaving the port in TIME_WAIT
+        w5500.tcp_disconnect(Sn::Sn0).unwrap();
+        let mut buf: [u8; 1] = [0];
+        assert_eq!(client.read(&mut buf).unwrap(), 0);
+        drop(client);
+    }
+}
+
+#[test]
+fn wake_on_lan() {
+    use std::net::UdpSocket;
+    use w5500_hl::{block, Udp};
+    use w5500_ll::{net::Eui48Addr, Interrupt, Mode};
+
+    const PORT: u16 = 50142;
+    const MAC: Eui48Addr = Eui48Addr::new(0x02, 0x00, 0x11, 0x22, 0x33, 0x44);
+    const OTHER_MAC: Eui48Addr = Eui48Addr::new(0x02, 0x00, 0x55, 0x66, 0x77, 0x88);
+
+    fn magic_packet(mac: &Eui48Addr) -> Vec<u8> {
+        let mut packet: Vec<u8> = vec![0xFF; 6];
+        (0..16).for_each(|_| packet.extend_from_slice(&mac.octets));
+        packet
+    }
+
+    let mut w5500 = W5500::default();
+    w5500.set_shar(&MAC).unwrap();
+    w5500.set_mr(Mode::DEFAULT.enable_wol()).unwrap();
+    w5500.udp_bind(Sn::Sn0, PORT).unwrap();
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let mut buf: [u8; 128] = [0; 128];
+
+    for (payload, expected) in [
+        (b"hello".to_vec(), false),
+        (magic_packet(&OTHER_MAC), false),
+        ([b"wake".as_slice(), &magic_packet(&MAC)].concat(), true),
+    ] {
+        peer.send_to(&payload, ("127.0.0.1", PORT)).unwrap();
+        let (n, _) = block!(w5500.udp_recv_from(Sn::Sn0, &mut buf)).unwrap();
+        assert_eq!(usize::from(n), payload.len());
+        assert_eq!(w5500.ir().unwrap().mp(), expected);
+    }
+
+    w5500.set_ir(Interrupt::DEFAULT.set_mp()).unwrap();
+    assert!(!w5500.ir().unwrap().mp());
+
+    // magic packets are ignored with wake on LAN disabled
+    w5500.set_mr(Mode::DEFAULT).unwrap();
+    peer.send_to(&magic_packet(&MAC), ("127.0.0.1", PORT))
+        .unwrap();
+    block!(w5500.udp_recv_from(Sn::Sn0, &mut buf)).unwrap();
+    assert!(!w5500.ir().unwrap().mp());
+}
+
+#[test]
+fn socket_buffer_wrap() {
+    use w5500_ll::BufferSize;
+
+    let mut w5500 = W5500::default();
+
+    // 2 KiB buffers wrap at 0x0800
+    w5500.set_sn_tx_buf(Sn::Sn0, 0xFFFE, &[1, 2, 3, 4]).unwrap();
+    let mut buf: [u8; 4] = [0; 4];
+    w5500.sn_tx_buf(Sn::Sn0, 0x07FE, &mut buf).unwrap();
+    assert_eq!(buf, [1, 2, 3, 4]);
+    w5500.sn_tx_buf(Sn::Sn0, 0x0000, &mut buf[..2]).unwrap();
+    assert_eq!(buf[..2], [3, 4]);
+
+    // 0 KiB buffers read zeros and discard writes
+    w5500.set_sn_rxbuf_size(Sn::Sn1, BufferSize::KB0).unwrap();
+    w5500.set_sn_rx_buf(Sn::Sn1, 0x1234, &[0xAB; 4]).unwrap();
+    let mut buf: [u8; 4] = [0xFF; 4];
+    w5500.sn_rx_buf(Sn::Sn1, 0x1234, &mut buf).unwrap();
+    assert_eq!(buf, [0; 4]);
+}
+
+#[test]
+fn unexpected_writes_do_not_panic() {
+    use w5500_ll::{BufferSize, SocketStatus};
+
+    let mut w5500 = W5500::default();
+
+    // invalid and no-op commands are ignored
+    w5500
+        .write(SnReg::CR.addr(), Sn::Sn0.block(), &[0xFF])
+        .unwrap();
+    w5500
+        .write(SnReg::CR.addr(), Sn::Sn0.block(), &[0x00])
+        .unwrap();
+    assert_eq!(w5500.sn_sr(Sn::Sn0).unwrap(), Ok(SocketStatus::Closed));
+
+    // SEND on a closed socket is ignored
+    w5500.set_sn_tx_buf(Sn::Sn0, 0, b"hello").unwrap();
+    w5500.set_sn_tx_wr(Sn::Sn0, 5).unwrap();
+    w5500
+        .set_sn_cr(Sn::Sn0, w5500_ll::SocketCommand::Send)
+        .unwrap();
+
+    // invalid buffer sizes are ignored
+    w5500
+        .write(SnReg::RXBUF_SIZE.addr(), Sn::Sn0.block(), &[0x03])
+        .unwrap();
+    assert_eq!(w5500.sn_rxbuf_size(Sn::Sn0).unwrap(), Ok(BufferSize::KB2));
+
+    // registers that are not simulated keep the written value
+    w5500.set_sn_mssr(Sn::Sn0, 1460).unwrap();
+    assert_eq!(w5500.sn_mssr(Sn::Sn0).unwrap(), 1460);
+    w5500.set_sn_tos(Sn::Sn0, 0x10).unwrap();
+    assert_eq!(w5500.sn_tos(Sn::Sn0).unwrap(), 0x10);
+    w5500.set_sn_frag(Sn::Sn0, 0x1234).unwrap();
+    assert_eq!(w5500.sn_frag(Sn::Sn0).unwrap(), 0x1234);
+}
+
+#[test]
+fn socket_stats() {
+    use std::{net::UdpSocket, time::Instant};
+    use w5500_hl::Udp;
+    use w5500_ll::net::{Ipv4Addr, SocketAddrV4};
+    use w5500_regsim::SocketStats;
+
+    const PORT: u16 = 50150;
+    const LOCAL: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::LOCALHOST, PORT);
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let peer_addr = SocketAddrV4::new(Ipv4Addr::LOCALHOST, peer.local_addr().unwrap().port());
+
+    let mut w5500 = W5500::default();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    w5500.udp_bind(Sn::Sn1, PORT).unwrap();
+
+    w5500.udp_send_to(Sn::Sn1, b"abc", &peer_addr).unwrap();
+    w5500.udp_send_to(Sn::Sn1, b"defgh", &peer_addr).unwrap();
+
+    peer.send_to(b"ping", LOCAL).unwrap();
+    let start: Instant = Instant::now();
+    while w5500.sn_rx_rsr(Sn::Sn1).unwrap() < 12 {
+        assert!(start.elapsed().as_secs() < 5, "timeout waiting for RECV");
+    }
+    let mut buf: [u8; 8] = [0; 8];
+    w5500.udp_recv_from(Sn::Sn1, &mut buf).unwrap();
+
+    assert_eq!(
+        w5500.stats(Sn::Sn1),
+        SocketStats {
+            send_cmds: 2,
+            recv_cmds: 1,
+            tx_bytes: 8,
+            rx_bytes: 4,
+            tx_datagrams: 2,
+            rx_datagrams: 1,
+            rx_overflow_bytes: 0,
+            rx_high_water: 12,
+        }
+    );
+    assert_eq!(w5500.stats(Sn::Sn0), SocketStats::default());
+}
+
+#[test]
+fn udp_bind_ip() {
+    use std::{net::UdpSocket, time::Instant};
+    use w5500_hl::Udp;
+    use w5500_ll::net::{Ipv4Addr, SocketAddrV4};
+
+    const PORT: u16 = 50151;
+    const LOCAL: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::LOCALHOST, PORT);
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let peer_addr = SocketAddrV4::new(Ipv4Addr::LOCALHOST, peer.local_addr().unwrap().port());
+
+    let mut w5500 = W5500::default();
+    // documentation address, not assigned to a host interface
+    w5500.set_sipr(&Ipv4Addr::new(192, 0, 2, 1)).unwrap();
+    w5500.set_udp_bind_ip(Some(Ipv4Addr::LOCALHOST));
+    w5500.udp_bind(Sn::Sn2, PORT).unwrap();
+
+    peer.send_to(b"ping", LOCAL).unwrap();
+    let start: Instant = Instant::now();
+    while w5500.sn_rx_rsr(Sn::Sn2).unwrap() < 12 {
+        assert!(start.elapsed().as_secs() < 5, "timeout waiting for RECV");
+    }
+    let mut buf: [u8; 8] = [0; 8];
+    assert_eq!(
+        w5500.udp_recv_from(Sn::Sn2, &mut buf).unwrap(),
+        (4, peer_addr)
+    );
+    assert_eq!(&buf[..4], b"ping");
+}
+
+#[test]
+fn rx_overflow() {
+    use std::{net::UdpSocket, time::Instant};
+    use w5500_hl::Udp;
+    use w5500_ll::net::{Ipv4Addr, SocketAddrV4};
+
+    const PORT: u16 = 50152;
+    const LOCAL: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::LOCALHOST, PORT);
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+    let mut w5500 = W5500::default();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    w5500.udp_bind(Sn::Sn3, PORT).unwrap();
+
+    // two datagrams with headers fill 2016 bytes of the 2048 byte buffer
+    for _ in 0..3 {
+        peer.send_to(&[0xAB; 1000], LOCAL).unwrap();
+    }
+
+    let start: Instant = Instant::now();
+    while !w5500.rx_overflowed(Sn::Sn3) {
+        w5500.sn_rx_rsr(Sn::Sn3).unwrap();
+        assert!(
+            start.elapsed().as_secs() < 5,
+            "timeout waiting for overflow"
+        );
+    }
+
+    // the third datagram does not fit and is dropped entirely
+    assert_eq!(w5500.sn_rx_rsr(Sn::Sn3).unwrap(), 2016);
+    let stats = w5500.stats(Sn::Sn3);
+    assert_eq!(stats.rx_overflow_bytes, 1008);
+    assert_eq!(stats.rx_bytes, 2000);
+    assert_eq!(stats.rx_datagrams, 2);
+    assert_eq!(stats.rx_high_water, 2016);
+    assert!(!w5500.rx_overflowed(Sn::Sn0));
+
+    // the datagrams in the buffer are intact
+    let mut buf: [u8; 1000] = [0; 1000];
+    for _ in 0..2 {
+        let (len, _) = w5500.udp_recv_from(Sn::Sn3, &mut buf).unwrap();
+        assert_eq!(len, 1000);
+        assert_eq!(buf, [0xAB; 1000]);
+    }
+    assert_eq!(w5500.sn_rx_rsr(Sn::Sn3).unwrap(), 0);
+}
+
+#[test]
+fn rx_overflow_kb0() {
+    use std::{net::UdpSocket, time::Instant};
+    use w5500_hl::Udp;
+    use w5500_ll::{
+        net::{Ipv4Addr, SocketAddrV4},
+        BufferSize,
+    };
+
+    const PORT: u16 = 50153;
+    const LOCAL: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::LOCALHOST, PORT);
+
+    let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+    let mut w5500 = W5500::default();
+    w5500.set_sipr(&Ipv4Addr::LOCALHOST).unwrap();
+    w5500.set_sn_rxbuf_size(Sn::Sn3, BufferSize::KB0).unwrap();
+    w5500.udp_bind(Sn::Sn3, PORT).unwrap();
+
+    peer.send_to(b"ping", LOCAL).unwrap();
+
+    let start: Instant = Instant::now();
+    while !w5500.rx_overflowed(Sn::Sn3) {
+        w5500.sn_rx_rsr(Sn::Sn3).unwrap();
+        assert!(
+            start.elapsed().as_secs() < 5,
+            "timeout waiting for overflow"
+        );
+    }
+
+    assert_eq!(w5500.sn_rx_rsr(Sn::Sn3).unwrap(), 0);
+    // datagrams are received into a buffer the size of the socket buffer,
+    // only the 8 byte header remains to be dropped
+    assert_eq!(w5500.stats(Sn::Sn3).rx_overflow_bytes, 8);
+}
+
+#[test]
+fn rx_wr_writable() {
+    use w5500_hl::Tcp;
+
+    let mut w5500 = W5500::default();
+
+    // read-only by default, the write is ignored
+    w5500
+        .write(SnReg::RX_WR0.addr(), Sn::Sn2.block(), &[0x00, 0x05])
+        .unwrap();
+    assert_eq!(w5500.sn_rx_wr(Sn::Sn2).unwrap(), 0);
+    assert_eq!(w5500.sn_rx_rsr(Sn::Sn2).unwrap(), 0);
+
+    w5500.set_rx_wr_writable(true);
+    w5500.write(0, Sn::Sn2.rx_block(), b"hello").unwrap();
+    w5500
+        .write(SnReg::RX_WR0.addr(), Sn::Sn2.block(), &[0x00, 0x05])
+        .unwrap();
+    assert_eq!(w5500.sn_rx_wr(Sn::Sn2).unwrap(), 5);
+    assert_eq!(w5500.sn_rx_rsr(Sn::Sn2).unwrap(), 5);
+
+    let mut buf: [u8; 8] = [0; 8];
+    assert_eq!(w5500.tcp_read(Sn::Sn2, &mut buf).unwrap(), 5);
+    assert_eq!(&buf[..5], b"hello");
+    assert_eq!(w5500.sn_rx_rsr(Sn::Sn2).unwrap(), 0);
+
+    // the setting is kept across a reset
+    w5500.sw_reset().unwrap().unwrap();
+    w5500
+        .write(SnReg::RX_WR0.addr(), Sn::Sn2.block(), &[0x00, 0x02])
+        .unwrap();
+    assert_eq!(w5500.sn_rx_rsr(Sn::Sn2).unwrap(), 2);
+}
@@ -22,7 +22,6 @@ impl Timestamp {
     }
 
     #[must_use]
-    #[cfg(any(feature = "chrono", feature = "time"))]
     fn secs(&self) -> i64 {
         let seconds_bits: u32 = (self.bits >> 32) as u32;
         // If bit 0 is set, the UTC time is in the range 1968-2036
@@ -42,6 +41,27 @@ impl Timestamp {
         ((self.bits & 0xFFFF_FFFF) * 1_000_000_000 / u64::from(u32::MAX)) as u32
     }
 
+    /// Seconds since the Unix epoch, 1970-01-01 00:00:00 UTC.
+    ///
+    /// The fractional part of the timestamp is truncated.
+    ///
+    /// This does not require the `chrono` or `time` features, and is
+    /// sufficient for checking certificate validity periods or setting a
+    /// real-time clock.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_sntp::Timestamp;
+    ///
+    /// assert_eq!(Timestamp::default().unix_secs(), 2_085_978_496);
+    /// ```
+    #[must_use]
+    pub fn unix_secs(&self) -> i64 {
+        const UNIX_EPOCH_NTP_SECS: i64 = 2_208_988_800;
+        self.secs() - UNIX_EPOCH_NTP_SECS
+    }
+
     /// Raw bits of the timestamp value.
     #[must_use]
     pub const fn to_bits(self) -> u64 {
@@ -172,6 +192,20 @@ mod tests {
         core::assert_eq!(pdt.second(), 48);
     }
 
+    #[test]
+    fn unix_secs() {
+        let timestamp: Timestamp = Timestamp {
+            bits: 0xe5_fd_82_24_23_ec_4b_12,
+        };
+        // 2022-04-10T16:19:48Z
+        core::assert_eq!(timestamp.unix_secs(), 1_649_607_588);
+
+        let timestamp: Timestamp = Timestamp {
+            bits: 0x83_AA_7E_80_00_00_00_00,
+        };
+        core::assert_eq!(timestamp.unix_secs(), 0);
+    }
+
     #[test]
     fn chrono_zero() {
         let timestamp: Timestamp = Timestamp { bits: 0 };
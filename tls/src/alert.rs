@@ -39,6 +39,30 @@ impl TryFrom<u8> for AlertLevel {
     }
 }
 
+impl AlertLevel {
+    /// Name of the alert level in RFC 8446.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_tls::AlertLevel;
+    ///
+    /// assert_eq!(AlertLevel::Fatal.as_str(), "fatal");
+    /// ```
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Warning => "warning",
+            Self::Fatal => "fatal",
+        }
+    }
+}
+
+impl core::fmt::Display for AlertLevel {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Alert description.
 ///
 /// # References
@@ -256,21 +280,70 @@ impl TryFrom<u8> for AlertDescription {
 }
 
 impl AlertDescription {
+    /// Name of the alert description in RFC 8446.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_tls::AlertDescription;
+    ///
+    /// assert_eq!(
+    ///     AlertDescription::HandshakeFailure.as_str(),
+    ///     "handshake_failure"
+    /// );
+    /// ```
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::CloseNotify => "close_notify",
+            Self::UnexpectedMessage => "unexpected_message",
+            Self::BadRecordMac => "bad_record_mac",
+            Self::RecordOverflow => "record_overflow",
+            Self::HandshakeFailure => "handshake_failure",
+            Self::BadCertificate => "bad_certificate",
+            Self::UnsupportedCertificate => "unsupported_certificate",
+            Self::CertificateRevoked => "certificate_revoked",
+            Self::CertificateExpired => "certificate_expired",
+            Self::CertificateUnknown => "certificate_unknown",
+            Self::IllegalParameter => "illegal_parameter",
+            Self::UnknownCa => "unknown_ca",
+            Self::AccessDenied => "access_denied",
+            Self::DecodeError => "decode_error",
+            Self::DecryptError => "decrypt_error",
+            Self::ProtocolVersion => "protocol_version",
+            Self::InsufficientSecurity => "insufficient_security",
+            Self::InternalError => "internal_error",
+            Self::InappropriateFallback => "inappropriate_fallback",
+            Self::UserCanceled => "user_canceled",
+            Self::MissingExtension => "missing_extension",
+            Self::UnsupportedExtension => "unsupported_extension",
+            Self::UnrecognizedName => "unrecognized_name",
+            Self::BadCertificateStatusResponse => "bad_certificate_status_response",
+            Self::UnknownPskIdentity => "unknown_psk_identity",
+            Self::CertificateRequired => "certificate_required",
+            Self::NoApplicationProtocol => "no_application_protocol",
+        }
+    }
+
     pub(crate) fn map_w5500<E>(e: w5500_hl::Error<E>) -> Self {
         match e {
             w5500_hl::Error::UnexpectedEof => AlertDescription::DecodeError,
-            w5500_hl::Error::OutOfMemory => AlertDescription::InternalError,
-            w5500_hl::Error::Other(_) => AlertDescription::InternalError,
             // technically unreachable, but this can occur if there is
             // a bit flip on the SPI bus
             w5500_hl::Error::WouldBlock => {
                 error!("W5500 unexpectedly blocked");
                 AlertDescription::InternalError
             }
+            _ => AlertDescription::InternalError,
         }
     }
 }
 
+impl core::fmt::Display for AlertDescription {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// TLS Alert.
 ///
 /// See [`AlertLevel`] and [`AlertDescription`].
@@ -298,3 +371,22 @@ impl Alert {
         }
     }
 }
+
+/// Formats the alert as the level and description.
+///
+/// # Example
+///
+/// ```
+/// use w5500_tls::{Alert, AlertDescription, AlertLevel};
+///
+/// let alert = Alert {
+///     level: AlertLevel::Fatal,
+///     description: AlertDescription::HandshakeFailure,
+/// };
+/// assert_eq!(alert.to_string(), "fatal: handshake_failure");
+/// ```
+impl core::fmt::Display for Alert {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}: {}", self.level, self.description)
+    }
+}
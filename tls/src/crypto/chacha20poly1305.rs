@@ -0,0 +1,140 @@
+use chacha20::{
+    cipher::{KeyIvInit, StreamCipher, StreamCipherSeek},
+    ChaCha20,
+};
+use poly1305::{
+    universal_hash::{KeyInit, UniversalHash},
+    Poly1305,
+};
+
+/// Streaming ChaCha20-Poly1305.
+///
+/// Data must be supplied in 16 byte blocks, only the last block may be
+/// shorter.
+///
+/// # References
+///
+/// * [RFC 8439 Section 2.8](https://datatracker.ietf.org/doc/html/rfc8439#section-2.8)
+pub struct ChaCha20Poly1305 {
+    cipher: ChaCha20,
+    mac: Poly1305,
+
+    data_len: usize,
+}
+
+const AAD_LEN: usize = crate::RecordHeader::LEN;
+
+impl ChaCha20Poly1305 {
+    pub fn new(key: &[u8; 32], nonce: &[u8; 12], aad: &[u8; AAD_LEN]) -> Self {
+        let mut cipher: ChaCha20 = ChaCha20::new(key.into(), nonce.into());
+
+        // the one-time Poly1305 key is generated from block 0
+        let mut mac_key: [u8; 32] = [0; 32];
+        cipher.apply_keystream(&mut mac_key);
+        let mut mac: Poly1305 = Poly1305::new(&mac_key.into());
+
+        // encryption starts at block 1
+        cipher.seek(64_u64);
+
+        mac.update_padded(aad);
+
+        Self {
+            cipher,
+            mac,
+            data_len: 0,
+        }
+    }
+
+    pub fn encrypt_block_inplace(&mut self, block: &mut [u8; 16]) {
+        self.cipher.apply_keystream(block);
+        self.mac.update_padded(block);
+        self.data_len += block.len();
+    }
+
+    pub fn encrypt_remainder_inplace(&mut self, padded_block: &mut [u8; 16], len: usize) {
+        debug_assert!(len <= 16, "len should be less than 1 block not {}", len);
+        self.cipher.apply_keystream(&mut padded_block[..len]);
+
+        padded_block[len..].iter_mut().for_each(|b| *b = 0);
+        self.mac.update_padded(&padded_block[..len]);
+
+        self.data_len += len;
+    }
+
+    pub fn decrypt_inplace(&mut self, data: &mut [u8]) {
+        self.mac.update_padded(data);
+        self.cipher.apply_keystream(data);
+        self.data_len += data.len();
+    }
+
+    pub fn finish(mut self) -> [u8; 16] {
+        let mut block: [u8; 16] = [0; 16];
+        block[..8].copy_from_slice(&(AAD_LEN as u64).to_le_bytes());
+        block[8..].copy_from_slice(&(self.data_len as u64).to_le_bytes());
+        self.mac.update_padded(&block);
+
+        self.mac.finalize().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChaCha20Poly1305;
+
+    const KEY: [u8; 32] = [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29, 30, 31,
+    ];
+    const NONCE: [u8; 12] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+    const AAD: [u8; 5] = [0x17, 0x03, 0x03, 0x00, 0x25];
+    const PT: [u8; 21] = [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20,
+    ];
+    const CT: [u8; 21] = [
+        137, 250, 10, 3, 45, 18, 163, 71, 191, 138, 53, 248, 148, 16, 0, 108, 217, 97, 160, 244, 69,
+    ];
+    const TAG: [u8; 16] = [
+        150, 3, 119, 203, 203, 108, 133, 0, 106, 234, 132, 174, 91, 31, 153, 128,
+    ];
+
+    #[test]
+    fn encrypt_no_data() {
+        const TAG: [u8; 16] = [
+            109, 132, 12, 96, 197, 98, 164, 76, 36, 221, 238, 35, 100, 188, 54, 50,
+        ];
+
+        let cipher = ChaCha20Poly1305::new(&KEY, &NONCE, &AAD);
+        let tag: [u8; 16] = cipher.finish();
+
+        assert_eq!(tag, TAG);
+    }
+
+    #[test]
+    fn encrypt() {
+        let mut block: [u8; 16] = PT[..16].try_into().unwrap();
+        let mut padded_block: [u8; 16] = [0; 16];
+        padded_block[..5].copy_from_slice(&PT[16..]);
+
+        let mut cipher = ChaCha20Poly1305::new(&KEY, &NONCE, &AAD);
+        cipher.encrypt_block_inplace(&mut block);
+        cipher.encrypt_remainder_inplace(&mut padded_block, 5);
+        let tag: [u8; 16] = cipher.finish();
+
+        assert_eq!(block, CT[..16]);
+        assert_eq!(padded_block[..5], CT[16..]);
+        assert_eq!(tag, TAG);
+    }
+
+    #[test]
+    fn decrypt() {
+        let mut data: [u8; 21] = CT;
+
+        let mut cipher = ChaCha20Poly1305::new(&KEY, &NONCE, &AAD);
+        cipher.decrypt_inplace(&mut data[..16]);
+        cipher.decrypt_inplace(&mut data[16..]);
+        let tag: [u8; 16] = cipher.finish();
+
+        assert_eq!(data, PT);
+        assert_eq!(tag, TAG);
+    }
+}
@@ -0,0 +1,68 @@
+//! ECDSA signature verification with the `secp256r1` curve.
+//!
+//! This is only used to verify server signatures.
+//!
+//! # References
+//!
+//! * [SEC 1 Section 4.1.4](https://www.secg.org/sec1-v2.pdf)
+
+use p256::ecdsa::{signature::hazmat::PrehashVerifier, Signature};
+
+pub use p256::ecdsa::VerifyingKey;
+
+pub fn verifying_key_from_sec1_bytes(bytes: &[u8; 65]) -> Option<VerifyingKey> {
+    VerifyingKey::from_sec1_bytes(bytes).ok()
+}
+
+/// Verify the signature `(r, s)` of a SHA-256 digest.
+pub fn verify_prehash(key: &VerifyingKey, digest: &[u8; 32], r: &[u8; 32], s: &[u8; 32]) -> bool {
+    match Signature::from_scalars(*r, *s) {
+        Ok(signature) => key.verify_prehash(digest, &signature).is_ok(),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{verify_prehash, verifying_key_from_sec1_bytes, VerifyingKey};
+
+    const PUBLIC_KEY: [u8; 65] = [
+        0x04, 0x9F, 0xAD, 0x84, 0xAE, 0xAE, 0x08, 0xBB, 0xEF, 0x7F, 0x01, 0x00, 0x14, 0xD8, 0x2C,
+        0xEF, 0x6A, 0x09, 0xDE, 0x2B, 0x0C, 0xF8, 0x71, 0xB5, 0xCE, 0x0C, 0x4F, 0x1D, 0x13, 0xA5,
+        0x9A, 0x59, 0x34, 0x07, 0xCB, 0x45, 0x76, 0x9F, 0x10, 0x70, 0xE2, 0xC2, 0x47, 0x0F, 0xE5,
+        0xB1, 0xBF, 0xE6, 0x31, 0x33, 0xC0, 0xB0, 0xCD, 0xC6, 0x4E, 0xA4, 0xBF, 0x37, 0x91, 0xA8,
+        0xEC, 0x2A, 0x07, 0xFD, 0x4F,
+    ];
+
+    const DIGEST: [u8; 32] = [
+        0x92, 0x2D, 0xFF, 0x4B, 0x3F, 0xD9, 0x4F, 0x97, 0xF1, 0x85, 0x33, 0x61, 0x08, 0x40, 0xED,
+        0xDC, 0x20, 0xFF, 0x77, 0x87, 0x39, 0x72, 0xCC, 0xA7, 0xAC, 0xE1, 0x4B, 0xB8, 0x50, 0x9C,
+        0x09, 0x78,
+    ];
+
+    const R: [u8; 32] = [
+        0xEE, 0x77, 0x80, 0xD4, 0x9E, 0x94, 0xFE, 0x2C, 0xD4, 0x9E, 0xA2, 0xCA, 0x1A, 0xD9, 0x57,
+        0x0E, 0xCF, 0xA4, 0x6A, 0xF4, 0xB4, 0xB8, 0x15, 0xE3, 0x5A, 0xA7, 0xBD, 0xC8, 0xB1, 0x52,
+        0xAB, 0xC5,
+    ];
+
+    const S: [u8; 32] = [
+        0x19, 0x2C, 0xED, 0xE7, 0xF7, 0x10, 0xF2, 0x4F, 0x45, 0xF6, 0x45, 0x72, 0x1A, 0x58, 0xDB,
+        0xE6, 0xF6, 0x12, 0x64, 0xA8, 0xE0, 0x74, 0x27, 0xBE, 0x12, 0x21, 0xDC, 0x12, 0x2A, 0xF7,
+        0x8B, 0xE4,
+    ];
+
+    #[test]
+    fn verify() {
+        let key: VerifyingKey = verifying_key_from_sec1_bytes(&PUBLIC_KEY).unwrap();
+        assert!(verify_prehash(&key, &DIGEST, &R, &S));
+
+        let mut digest: [u8; 32] = DIGEST;
+        digest[31] ^= 1;
+        assert!(!verify_prehash(&key, &digest, &R, &S));
+
+        assert!(!verify_prehash(&key, &DIGEST, &S, &R));
+        assert!(!verify_prehash(&key, &DIGEST, &[0; 32], &S));
+        assert!(!verify_prehash(&key, &DIGEST, &R, &[0xFF; 32]));
+    }
+}
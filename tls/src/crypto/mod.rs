@@ -1,6 +1,79 @@
+#[cfg(any(not(feature = "rustcrypto-aes"), test))]
 mod aes;
+#[cfg(feature = "chacha20poly1305")]
+mod chacha20poly1305;
+pub mod ecdsa;
 pub mod p256;
 mod record;
+#[cfg(feature = "rustcrypto-aes")]
+mod rustcrypto_aes;
+#[cfg(feature = "x25519")]
+pub mod x25519;
 
+#[cfg(not(feature = "rustcrypto-aes"))]
 pub use aes::Aes128Gcm;
+#[cfg(feature = "chacha20poly1305")]
+pub use chacha20poly1305::ChaCha20Poly1305;
 pub use record::{decrypt_record_inplace, encrypt_record_inplace};
+#[cfg(feature = "rustcrypto-aes")]
+pub use rustcrypto_aes::Aes128Gcm;
+
+/// Server public key, sent in the ServerHello key share.
+pub enum PublicKey {
+    P256(p256::PublicKey),
+    #[cfg(feature = "x25519")]
+    X25519(x25519::PublicKey),
+}
+
+/// Client public keys, sent in the ClientHello key share.
+pub struct KeyShares {
+    pub p256: [u8; 65],
+    #[cfg(feature = "x25519")]
+    pub x25519: [u8; 32],
+}
+
+/// Record protection for the negotiated cipher suite.
+///
+/// Data must be supplied in 16 byte blocks, only the last block may be
+/// shorter.
+// The cipher only lives on the stack for the duration of a single record.
+#[allow(clippy::large_enum_variant)]
+pub enum Cipher {
+    Aes128Gcm(Aes128Gcm),
+    #[cfg(feature = "chacha20poly1305")]
+    ChaCha20Poly1305(ChaCha20Poly1305),
+}
+
+impl Cipher {
+    pub fn encrypt_block_inplace(&mut self, block: &mut [u8; 16]) {
+        match self {
+            Self::Aes128Gcm(cipher) => cipher.encrypt_block_inplace(block),
+            #[cfg(feature = "chacha20poly1305")]
+            Self::ChaCha20Poly1305(cipher) => cipher.encrypt_block_inplace(block),
+        }
+    }
+
+    pub fn encrypt_remainder_inplace(&mut self, padded_block: &mut [u8; 16], len: usize) {
+        match self {
+            Self::Aes128Gcm(cipher) => cipher.encrypt_remainder_inplace(padded_block, len),
+            #[cfg(feature = "chacha20poly1305")]
+            Self::ChaCha20Poly1305(cipher) => cipher.encrypt_remainder_inplace(padded_block, len),
+        }
+    }
+
+    pub fn decrypt_inplace(&mut self, data: &mut [u8]) {
+        match self {
+            Self::Aes128Gcm(cipher) => cipher.decrypt_inplace(data),
+            #[cfg(feature = "chacha20poly1305")]
+            Self::ChaCha20Poly1305(cipher) => cipher.decrypt_inplace(data),
+        }
+    }
+
+    pub fn finish(self) -> [u8; 16] {
+        match self {
+            Self::Aes128Gcm(cipher) => cipher.finish(),
+            #[cfg(feature = "chacha20poly1305")]
+            Self::ChaCha20Poly1305(cipher) => cipher.finish(),
+        }
+    }
+}
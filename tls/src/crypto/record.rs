@@ -1,8 +1,9 @@
 use crate::{
-    crypto::Aes128Gcm,
+    crypto::Cipher,
     io::Buffer,
+    key_schedule::KeySchedule,
     record::{ContentType, RecordHeader},
-    AlertDescription, GCM_TAG_LEN,
+    AlertDescription, TAG_LEN,
 };
 use core::cmp::min;
 use subtle::ConstantTimeEq;
@@ -11,13 +12,10 @@ use w5500_hl::ll::{Registers, Sn, SocketCommand};
 pub fn decrypt_record_inplace<const N: usize, W5500: Registers>(
     w5500: &mut W5500,
     sn: Sn,
-    server_key: &[u8; 16],
-    server_nonce: &[u8; 12],
+    mut cipher: Cipher,
     header: &RecordHeader,
     buf: &mut Buffer<N>,
 ) -> Result<Result<ContentType, u8>, AlertDescription> {
-    let mut cipher = Aes128Gcm::new(server_key, server_nonce, header.as_bytes());
-
     let sn_rx_rsr: u16 = w5500
         .sn_rx_rsr(sn)
         .map_err(|_| AlertDescription::InternalError)?;
@@ -33,7 +31,7 @@ pub fn decrypt_record_inplace<const N: usize, W5500: Registers>(
         .sn_rx_rd(sn)
         .map_err(|_| AlertDescription::InternalError)?;
 
-    let mut remain: u16 = header.length().saturating_sub(GCM_TAG_LEN as u16);
+    let mut remain: u16 = header.length().saturating_sub(TAG_LEN as u16);
 
     if remain == 0 {
         error!("record is too short to contain ContentType");
@@ -90,8 +88,7 @@ pub fn decrypt_record_inplace<const N: usize, W5500: Registers>(
 pub fn encrypt_record_inplace<W5500: Registers>(
     w5500: &mut W5500,
     sn: Sn,
-    client_key: &[u8; 16],
-    client_nonce: &[u8; 12],
+    key_schedule: &KeySchedule,
     mut head: u16,
     tail: u16,
     content_type: ContentType,
@@ -103,7 +100,7 @@ pub fn encrypt_record_inplace<W5500: Registers>(
 
     let header: RecordHeader = RecordHeader::ser(
         ContentType::ApplicationData,
-        tail.wrapping_add(CONTENT_TYPE_LEN + (GCM_TAG_LEN as u16))
+        tail.wrapping_add(CONTENT_TYPE_LEN + (TAG_LEN as u16))
             .wrapping_sub(head),
     );
 
@@ -113,7 +110,7 @@ pub fn encrypt_record_inplace<W5500: Registers>(
         header.as_bytes(),
     )?;
 
-    let mut cipher = Aes128Gcm::new(client_key, client_nonce, header.as_bytes());
+    let mut cipher: Cipher = key_schedule.client_cipher(header.as_bytes()).unwrap();
 
     for _ in 0..(data_len / 16) {
         let mut block: [u8; 16] = [0; 16];
@@ -149,10 +146,10 @@ pub fn encrypt_record_inplace<W5500: Registers>(
     head = head.wrapping_add(remain);
 
     // write tag
-    let tag: [u8; GCM_TAG_LEN] = cipher.finish();
+    let tag: [u8; TAG_LEN] = cipher.finish();
     w5500.set_sn_tx_buf(sn, head, &tag)?;
 
-    w5500.set_sn_tx_wr(sn, head.wrapping_add(GCM_TAG_LEN as u16))?;
+    w5500.set_sn_tx_wr(sn, head.wrapping_add(TAG_LEN as u16))?;
     w5500.set_sn_cr(sn, SocketCommand::Send)?;
 
     Ok(())
@@ -0,0 +1,149 @@
+use aes::{
+    cipher::{BlockEncrypt, KeyInit, KeyIvInit, StreamCipher},
+    Aes128,
+};
+use ghash::{universal_hash::UniversalHash, GHash};
+
+type Aes128Ctr = ctr::Ctr32BE<Aes128>;
+
+/// Streaming AES-128-GCM using the RustCrypto [`aes`] crate.
+///
+/// This is a drop-in replacement for the AES-GCM implementation in this
+/// crate, selected with the `rustcrypto-aes` feature.
+///
+/// Data must be supplied in 16 byte blocks, only the last block may be
+/// shorter.
+pub struct Aes128Gcm {
+    cipher: Aes128Ctr,
+    ghash: GHash,
+
+    counter0_ct: [u8; 16],
+
+    data_len: usize,
+}
+
+const AAD_LEN: usize = crate::RecordHeader::LEN;
+
+impl Aes128Gcm {
+    pub fn new(key: &[u8; 16], nonce: &[u8; 12], aad: &[u8; AAD_LEN]) -> Self {
+        let aes: Aes128 = Aes128::new(key.into());
+
+        let mut hash_key: [u8; 16] = [0; 16];
+        aes.encrypt_block((&mut hash_key).into());
+        let mut ghash: GHash = GHash::new(&hash_key.into());
+        ghash.update_padded(aad);
+
+        // j0 aka counter0
+        let counter: [u8; 16] = {
+            let mut counter: [u8; 16] = [0; 16];
+            counter[..12].copy_from_slice(nonce);
+            counter[15] = 1;
+            counter
+        };
+
+        // the first block of keystream masks the tag,
+        // encryption starts at counter0 + 1
+        let mut cipher: Aes128Ctr = Aes128Ctr::new(key.into(), &counter.into());
+        let mut counter0_ct: [u8; 16] = [0; 16];
+        cipher.apply_keystream(&mut counter0_ct);
+
+        Self {
+            cipher,
+            ghash,
+            counter0_ct,
+            data_len: 0,
+        }
+    }
+
+    pub fn encrypt_block_inplace(&mut self, block: &mut [u8; 16]) {
+        self.cipher.apply_keystream(block);
+        self.ghash.update_padded(block);
+        self.data_len += block.len();
+    }
+
+    pub fn encrypt_remainder_inplace(&mut self, padded_block: &mut [u8; 16], len: usize) {
+        debug_assert!(len <= 16, "len should be less than 1 block not {}", len);
+        self.cipher.apply_keystream(&mut padded_block[..len]);
+
+        padded_block[len..].iter_mut().for_each(|b| *b = 0);
+        self.ghash.update_padded(&padded_block[..len]);
+
+        self.data_len += len;
+    }
+
+    pub fn decrypt_inplace(&mut self, data: &mut [u8]) {
+        self.ghash.update_padded(data);
+        self.cipher.apply_keystream(data);
+        self.data_len += data.len();
+    }
+
+    pub fn finish(mut self) -> [u8; 16] {
+        const ASSOCIATED_DATA_BITS: u64 = (AAD_LEN as u64) * 8;
+        let buffer_bits: u64 = (self.data_len as u64) * 8;
+
+        let mut block: [u8; 16] = [0; 16];
+        block[..8].copy_from_slice(&ASSOCIATED_DATA_BITS.to_be_bytes());
+        block[8..].copy_from_slice(&buffer_bits.to_be_bytes());
+        self.ghash.update_padded(&block);
+
+        let mut tag: [u8; 16] = self.ghash.finalize().into();
+        tag.iter_mut()
+            .zip(self.counter0_ct)
+            .for_each(|(a, b)| *a ^= b);
+
+        tag
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Aes128Gcm;
+
+    const KEY: [u8; 16] = [
+        0xFE, 0xFF, 0xE9, 0x92, 0x86, 0x65, 0x73, 0x1C, 0x6D, 0x6A, 0x8F, 0x94, 0x67, 0x30, 0x83,
+        0x08,
+    ];
+    const NONCE: [u8; 12] = [
+        0xCA, 0xFE, 0xBA, 0xBE, 0xFA, 0xCE, 0xDB, 0xAD, 0xDE, 0xCA, 0xF8, 0x88,
+    ];
+    const AAD: [u8; 5] = [0x17, 0x03, 0x03, 0x00, 0x35];
+
+    /// Cross check against the AES-GCM implementation in this crate.
+    #[test]
+    fn matches_builtin() {
+        for len in [0, 1, 15, 16, 17, 37, 64] {
+            let pt: Vec<u8> = (0..len).map(|n| n as u8).collect();
+
+            let mut builtin = crate::crypto::aes::Aes128Gcm::new(&KEY, &NONCE, &AAD);
+            let mut rustcrypto = Aes128Gcm::new(&KEY, &NONCE, &AAD);
+
+            let mut ct_builtin: Vec<u8> = Vec::new();
+            let mut ct_rustcrypto: Vec<u8> = Vec::new();
+            pt.chunks(16).for_each(|chunk| {
+                let mut block_builtin: [u8; 16] = [0; 16];
+                block_builtin[..chunk.len()].copy_from_slice(chunk);
+                let mut block_rustcrypto: [u8; 16] = block_builtin;
+                if chunk.len() == 16 {
+                    builtin.encrypt_block_inplace(&mut block_builtin);
+                    rustcrypto.encrypt_block_inplace(&mut block_rustcrypto);
+                } else {
+                    builtin.encrypt_remainder_inplace(&mut block_builtin, chunk.len());
+                    rustcrypto.encrypt_remainder_inplace(&mut block_rustcrypto, chunk.len());
+                }
+                ct_builtin.extend_from_slice(&block_builtin[..chunk.len()]);
+                ct_rustcrypto.extend_from_slice(&block_rustcrypto[..chunk.len()]);
+            });
+
+            assert_eq!(ct_builtin, ct_rustcrypto, "len={len}");
+            let tag: [u8; 16] = rustcrypto.finish();
+            assert_eq!(builtin.finish(), tag, "len={len}");
+
+            let mut data: Vec<u8> = ct_rustcrypto;
+            let mut cipher = Aes128Gcm::new(&KEY, &NONCE, &AAD);
+            data.chunks_mut(16)
+                .for_each(|chunk| cipher.decrypt_inplace(chunk));
+            assert_eq!(data, pt, "len={len}");
+            assert_eq!(cipher.finish(), tag, "len={len}");
+        }
+    }
+}
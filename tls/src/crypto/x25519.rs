@@ -0,0 +1,23 @@
+use super::p256::SharedSecret;
+use rand_core::{CryptoRng, RngCore};
+pub use x25519_dalek::{EphemeralSecret, PublicKey};
+
+pub fn public_key_from_bytes(bytes: [u8; 32]) -> PublicKey {
+    PublicKey::from(bytes)
+}
+
+pub fn keygen<R: RngCore + CryptoRng>(rng: &mut R) -> (EphemeralSecret, [u8; 32]) {
+    let private_key = EphemeralSecret::random_from_rng(rng);
+    let public_bytes: [u8; 32] = PublicKey::from(&private_key).to_bytes();
+    (private_key, public_bytes)
+}
+
+/// Returns `None` if the shared secret is all zeros.
+///
+/// # References
+///
+/// * [RFC 8446 Section 7.4.2](https://datatracker.ietf.org/doc/html/rfc8446#section-7.4.2)
+pub fn diffie_hellman(secret: EphemeralSecret, public: &PublicKey) -> Option<SharedSecret> {
+    let shared = secret.diffie_hellman(public);
+    shared.was_contributory().then(|| shared.to_bytes())
+}
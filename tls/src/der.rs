@@ -0,0 +1,73 @@
+//! Minimal DER decoding.
+//!
+//! This only implements what is required to find the subject public key of
+//! a certificate, and to decode ECDSA signatures.
+//!
+//! # References
+//!
+//! * [ITU-T X.690](https://www.itu.int/rec/T-REC-X.690)
+
+use crate::{io::CircleReader, AlertDescription};
+
+pub const INTEGER: u8 = 0x02;
+pub const SEQUENCE: u8 = 0x30;
+/// `[0] EXPLICIT`, used for the certificate version.
+pub const CONTEXT_SPECIFIC_0: u8 = 0xA0;
+
+/// Read a tag and length.
+///
+/// Lengths that require more than two bytes are rejected, these can never fit
+/// in the RX buffer.
+pub fn header(reader: &mut CircleReader) -> Result<(u8, u16), AlertDescription> {
+    let tag: u8 = reader.next_u8()?;
+    let len: u16 = match reader.next_u8()? {
+        len @ 0x00..=0x7F => len.into(),
+        0x81 => reader.next_u8()?.into(),
+        0x82 => reader.next_u16()?,
+        x => {
+            error!("unsupported DER length encoding {:#02X}", x);
+            return Err(AlertDescription::DecodeError);
+        }
+    };
+    Ok((tag, len))
+}
+
+/// Read a tag and length, returning the length if the tag matches.
+pub fn expect(reader: &mut CircleReader, tag: u8) -> Result<u16, AlertDescription> {
+    match header(reader)? {
+        (actual, len) if actual == tag => Ok(len),
+        (actual, _) => {
+            error!("expected DER tag {:#02X} got {:#02X}", tag, actual);
+            Err(AlertDescription::DecodeError)
+        }
+    }
+}
+
+/// Skip an element with the given tag.
+pub fn skip(reader: &mut CircleReader, tag: u8) -> Result<(), AlertDescription> {
+    let len: u16 = expect(reader, tag)?;
+    reader.skip_n(len)
+}
+
+/// Read a positive integer of up to 32 bytes as a big-endian array.
+pub fn uint32(reader: &mut CircleReader) -> Result<[u8; 32], AlertDescription> {
+    let mut len: u16 = expect(reader, INTEGER)?;
+
+    // positive integers with the MSB set have a leading zero
+    if len == 33 {
+        if reader.next_u8()? != 0 {
+            error!("DER integer exceeds 32 bytes");
+            return Err(AlertDescription::DecodeError);
+        }
+        len -= 1;
+    }
+
+    if len == 0 || len > 32 {
+        error!("invalid DER integer length {}", len);
+        return Err(AlertDescription::DecodeError);
+    }
+
+    let mut ret: [u8; 32] = [0; 32];
+    reader.read_exact(&mut ret[(32 - usize::from(len))..])?;
+    Ok(ret)
+}
@@ -87,3 +87,55 @@ impl TryFrom<u16> for ExtensionType {
         }
     }
 }
+
+/// Maximum fragment length.
+///
+/// Offered with the `max_fragment_length` extension to limit the plaintext
+/// length of records sent by the server, see
+/// [`Client::set_max_fragment_length`](crate::Client::set_max_fragment_length).
+///
+/// # References
+///
+/// * [RFC 6066 Section 4](https://datatracker.ietf.org/doc/html/rfc6066#section-4)
+///
+/// ```text
+/// enum {
+///     2^9(1), 2^10(2), 2^11(3), 2^12(4), (255)
+/// } MaxFragmentLength;
+/// ```
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MaxFragmentLength {
+    /// 512 bytes
+    Bytes512 = 1,
+    /// 1024 bytes
+    Bytes1024 = 2,
+    /// 2048 bytes
+    Bytes2048 = 3,
+    /// 4096 bytes
+    Bytes4096 = 4,
+}
+
+impl MaxFragmentLength {
+    /// Maximum fragment length in bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_tls::MaxFragmentLength;
+    ///
+    /// assert_eq!(MaxFragmentLength::Bytes512.bytes(), 512);
+    /// assert_eq!(MaxFragmentLength::Bytes4096.bytes(), 4096);
+    /// ```
+    pub const fn bytes(self) -> u16 {
+        1 << (8 + self as u16)
+    }
+}
+
+impl From<MaxFragmentLength> for u8 {
+    #[inline]
+    fn from(max_fragment_length: MaxFragmentLength) -> Self {
+        max_fragment_length as u8
+    }
+}
@@ -0,0 +1,224 @@
+use crate::{
+    crypto::ecdsa::{verifying_key_from_sec1_bytes, VerifyingKey},
+    der,
+    io::CircleReader,
+    AlertDescription,
+};
+use sha2::{Digest, Sha256};
+
+/// DER encoded SubjectPublicKeyInfo length for a `secp256r1` public key.
+const P256_SPKI_LEN: usize = 91;
+
+/// DER encoded SubjectPublicKeyInfo for a `secp256r1` public key, up to the
+/// uncompressed SEC1 point.
+///
+/// ```text
+/// SubjectPublicKeyInfo ::= SEQUENCE {
+///     algorithm AlgorithmIdentifier {
+///         algorithm id-ecPublicKey,
+///         parameters secp256r1 },
+///     subjectPublicKey BIT STRING }
+/// ```
+#[rustfmt::skip]
+const P256_SPKI_PREFIX: [u8; 26] = [
+    0x30, 0x59, // SEQUENCE
+    0x30, 0x13, // SEQUENCE
+    0x06, 0x07, 0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x02, 0x01, // id-ecPublicKey
+    0x06, 0x08, 0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x03, 0x01, 0x07, // secp256r1
+    0x03, 0x42, 0x00, // BIT STRING
+];
+
+fn next_u24(reader: &mut CircleReader) -> Result<u32, AlertDescription> {
+    let [a, b, c]: [u8; 3] = reader.next_n()?;
+    Ok(u32::from_be_bytes([0, a, b, c]))
+}
+
+/// Find the SubjectPublicKeyInfo of a DER encoded X.509 certificate.
+///
+/// # References
+///
+/// * [RFC 5280 Section 4.1](https://datatracker.ietf.org/doc/html/rfc5280#section-4.1)
+///
+/// ```text
+/// Certificate ::= SEQUENCE {
+///     tbsCertificate       TBSCertificate,
+///     signatureAlgorithm   AlgorithmIdentifier,
+///     signatureValue       BIT STRING }
+///
+/// TBSCertificate ::= SEQUENCE {
+///     version         [0]  EXPLICIT Version DEFAULT v1,
+///     serialNumber         CertificateSerialNumber,
+///     signature            AlgorithmIdentifier,
+///     issuer               Name,
+///     validity             Validity,
+///     subject              Name,
+///     subjectPublicKeyInfo SubjectPublicKeyInfo,
+///     ... }
+/// ```
+fn subject_public_key_info(
+    reader: &mut CircleReader,
+) -> Result<[u8; P256_SPKI_LEN], AlertDescription> {
+    // Certificate
+    der::expect(reader, der::SEQUENCE)?;
+    // TBSCertificate
+    der::expect(reader, der::SEQUENCE)?;
+
+    let (tag, len): (u8, u16) = der::header(reader)?;
+    reader.skip_n(len)?;
+    if tag == der::CONTEXT_SPECIFIC_0 {
+        // version is present, skip serialNumber
+        der::skip(reader, der::INTEGER)?;
+    } else if tag != der::INTEGER {
+        error!("expected certificate serialNumber got tag {:#02X}", tag);
+        return Err(AlertDescription::BadCertificate);
+    }
+
+    // signature, issuer, validity, subject
+    for _ in 0..4 {
+        der::skip(reader, der::SEQUENCE)?;
+    }
+
+    let mut spki: [u8; P256_SPKI_LEN] = [0; P256_SPKI_LEN];
+    reader.read_exact(&mut spki[..P256_SPKI_PREFIX.len()])?;
+    if spki[..P256_SPKI_PREFIX.len()] != P256_SPKI_PREFIX {
+        error!("certificate public key is not secp256r1");
+        return Err(AlertDescription::UnsupportedCertificate);
+    }
+    reader.read_exact(&mut spki[P256_SPKI_PREFIX.len()..])?;
+
+    Ok(spki)
+}
+
+/// Certificate message.
+///
+/// Only the end-entity certificate is used, the SHA-256 hash of its
+/// SubjectPublicKeyInfo must match `spki_pin`.
+/// The remainder of the certificate chain is ignored.
+///
+/// Returns the server public key used to verify the CertificateVerify
+/// message.
+///
+/// # References
+///
+/// * [RFC 8446 Section 4.4.2](https://datatracker.ietf.org/doc/html/rfc8446#section-4.4.2)
+///
+/// ```text
+/// struct {
+///     select (certificate_type) {
+///         case RawPublicKey:
+///           /* From RFC 7250 ASN.1_subjectPublicKeyInfo */
+///           opaque ASN1_subjectPublicKeyInfo<1..2^24-1>;
+///
+///         case X509:
+///           opaque cert_data<1..2^24-1>;
+///     };
+///     Extension extensions<0..2^16-1>;
+/// } CertificateEntry;
+///
+/// struct {
+///     opaque certificate_request_context<0..2^8-1>;
+///     CertificateEntry certificate_list<0..2^24-1>;
+/// } Certificate;
+/// ```
+pub(crate) fn recv_certificate(
+    reader: &mut CircleReader,
+    spki_pin: &[u8; 32],
+) -> Result<VerifyingKey, AlertDescription> {
+    // In the case of server authentication, this field SHALL be zero length.
+    let context_len: u8 = reader.next_u8()?;
+    if context_len != 0 {
+        error!(
+            "certificate_request_context length is not 0: {}",
+            context_len
+        );
+        return Err(AlertDescription::IllegalParameter);
+    }
+
+    // If the server supplies an empty Certificate message, the client MUST
+    // abort the handshake with a "decode_error" alert.
+    if next_u24(reader)? == 0 {
+        error!("server certificate_list is empty");
+        return Err(AlertDescription::DecodeError);
+    }
+
+    // The sender's certificate MUST come in the first CertificateEntry in
+    // the list.
+    let cert_data_len: u32 = next_u24(reader)?;
+    debug!("end-entity certificate length {}", cert_data_len);
+    let spki: [u8; P256_SPKI_LEN] = subject_public_key_info(reader)?;
+
+    let spki_hash: [u8; 32] = Sha256::digest(spki).into();
+    if spki_hash != *spki_pin {
+        error!("certificate SubjectPublicKeyInfo does not match the pinned hash");
+        return Err(AlertDescription::BadCertificate);
+    }
+
+    let mut sec1_bytes: [u8; 65] = [0; 65];
+    sec1_bytes.copy_from_slice(&spki[P256_SPKI_PREFIX.len()..]);
+    verifying_key_from_sec1_bytes(&sec1_bytes).ok_or_else(|| {
+        error!("certificate public key decode");
+        AlertDescription::BadCertificate
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::recv_certificate;
+    use crate::{io::CircleReader, AlertDescription};
+
+    /// Self-signed `secp256r1` certificate for `server.local`.
+    const CERTIFICATE: [u8; 295] = [
+        0x00, 0x00, 0x01, 0x23, 0x00, 0x01, 0x1E, 0x30, 0x82, 0x01, 0x1A, 0x30, 0x81, 0xC1, 0xA0,
+        0x03, 0x02, 0x01, 0x02, 0x02, 0x01, 0x01, 0x30, 0x0A, 0x06, 0x08, 0x2A, 0x86, 0x48, 0xCE,
+        0x3D, 0x04, 0x03, 0x02, 0x30, 0x17, 0x31, 0x15, 0x30, 0x13, 0x06, 0x03, 0x55, 0x04, 0x03,
+        0x0C, 0x0C, 0x73, 0x65, 0x72, 0x76, 0x65, 0x72, 0x2E, 0x6C, 0x6F, 0x63, 0x61, 0x6C, 0x30,
+        0x1E, 0x17, 0x0D, 0x32, 0x34, 0x30, 0x31, 0x30, 0x31, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30,
+        0x5A, 0x17, 0x0D, 0x33, 0x33, 0x31, 0x32, 0x32, 0x39, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30,
+        0x5A, 0x30, 0x17, 0x31, 0x15, 0x30, 0x13, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0C, 0x0C, 0x73,
+        0x65, 0x72, 0x76, 0x65, 0x72, 0x2E, 0x6C, 0x6F, 0x63, 0x61, 0x6C, 0x30, 0x59, 0x30, 0x13,
+        0x06, 0x07, 0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x02, 0x01, 0x06, 0x08, 0x2A, 0x86, 0x48, 0xCE,
+        0x3D, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00, 0x04, 0x9F, 0xAD, 0x84, 0xAE, 0xAE, 0x08, 0xBB,
+        0xEF, 0x7F, 0x01, 0x00, 0x14, 0xD8, 0x2C, 0xEF, 0x6A, 0x09, 0xDE, 0x2B, 0x0C, 0xF8, 0x71,
+        0xB5, 0xCE, 0x0C, 0x4F, 0x1D, 0x13, 0xA5, 0x9A, 0x59, 0x34, 0x07, 0xCB, 0x45, 0x76, 0x9F,
+        0x10, 0x70, 0xE2, 0xC2, 0x47, 0x0F, 0xE5, 0xB1, 0xBF, 0xE6, 0x31, 0x33, 0xC0, 0xB0, 0xCD,
+        0xC6, 0x4E, 0xA4, 0xBF, 0x37, 0x91, 0xA8, 0xEC, 0x2A, 0x07, 0xFD, 0x4F, 0x30, 0x0A, 0x06,
+        0x08, 0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x02, 0x03, 0x48, 0x00, 0x30, 0x45, 0x02,
+        0x20, 0x57, 0x81, 0x5F, 0x6C, 0x97, 0x62, 0x89, 0xA4, 0x61, 0xD4, 0x7A, 0xC6, 0xDD, 0x30,
+        0xE1, 0xE2, 0x27, 0x8B, 0xA2, 0x86, 0x6C, 0x18, 0x5C, 0x88, 0x49, 0xDB, 0x3C, 0x9B, 0xD5,
+        0xF5, 0x0C, 0xF6, 0x02, 0x21, 0x00, 0xF6, 0x88, 0xA3, 0x2B, 0xCE, 0x54, 0x3C, 0xE2, 0x93,
+        0x91, 0x84, 0x00, 0x11, 0xE8, 0x88, 0x3C, 0xB1, 0x79, 0xCA, 0xE3, 0xAB, 0xDE, 0xA3, 0x9B,
+        0x37, 0xEB, 0x35, 0x4D, 0xD9, 0x91, 0x88, 0xEC, 0x00, 0x00,
+    ];
+
+    const SPKI_PIN: [u8; 32] = [
+        0x2A, 0x22, 0xA9, 0x1F, 0xD7, 0x4A, 0xC9, 0xB9, 0xF4, 0xB2, 0xC1, 0xFB, 0x92, 0xF1, 0x10,
+        0x96, 0x9B, 0x00, 0x8D, 0x8A, 0x30, 0x4E, 0x13, 0x44, 0x2C, 0xAC, 0xFA, 0x15, 0x99, 0xD2,
+        0x10, 0x69,
+    ];
+
+    #[test]
+    fn pinned() {
+        let mut reader: CircleReader = CircleReader::new(&CERTIFICATE[..100], &CERTIFICATE[100..]);
+        assert!(recv_certificate(&mut reader, &SPKI_PIN).is_ok());
+    }
+
+    #[test]
+    fn pin_mismatch() {
+        let mut spki_pin: [u8; 32] = SPKI_PIN;
+        spki_pin[0] ^= 1;
+        let mut reader: CircleReader = CircleReader::new(&CERTIFICATE, &[]);
+        assert_eq!(
+            recv_certificate(&mut reader, &spki_pin).err(),
+            Some(AlertDescription::BadCertificate)
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let mut reader: CircleReader = CircleReader::new(&[0, 0, 0, 0], &[]);
+        assert_eq!(
+            recv_certificate(&mut reader, &SPKI_PIN).err(),
+            Some(AlertDescription::DecodeError)
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use super::client_hello::SignatureScheme;
+use crate::{der, io::CircleReader, key_schedule::KeySchedule, AlertDescription};
+
+/// Certificate verify message.
+///
+/// Only `ecdsa_secp256r1_sha256` signatures are supported.
+///
+/// # References
+///
+/// * [RFC 8446 Section 4.4.3](https://datatracker.ietf.org/doc/html/rfc8446#section-4.4.3)
+/// * [RFC 3279 Section 2.2.3](https://datatracker.ietf.org/doc/html/rfc3279#section-2.2.3)
+///
+/// ```text
+/// struct {
+///     SignatureScheme algorithm;
+///     opaque signature<0..2^16-1>;
+/// } CertificateVerify;
+///
+/// Ecdsa-Sig-Value  ::=  SEQUENCE  {
+///     r     INTEGER,
+///     s     INTEGER  }
+/// ```
+pub(crate) fn recv_certificate_verify(
+    reader: &mut CircleReader,
+    key_schedule: &KeySchedule,
+) -> Result<(), AlertDescription> {
+    match SignatureScheme::try_from(reader.next_u16()?) {
+        Ok(SignatureScheme::EcdsaSecp256r1Sha256) => (),
+        // the signature algorithm MUST be one offered in the client's
+        // "signature_algorithms" extension
+        scheme => {
+            error!("unsupported CertificateVerify algorithm {:?}", scheme);
+            return Err(AlertDescription::IllegalParameter);
+        }
+    }
+
+    let signature_len: u16 = reader.next_u16()?;
+    let signature_start: u16 = reader.stream_position();
+
+    der::expect(reader, der::SEQUENCE)?;
+    let r: [u8; 32] = der::uint32(reader)?;
+    let s: [u8; 32] = der::uint32(reader)?;
+
+    let n_read: u16 = reader.stream_position() - signature_start;
+    if n_read != signature_len {
+        error!(
+            "CertificateVerify signature length {} != n_read {}",
+            signature_len, n_read
+        );
+        return Err(AlertDescription::DecodeError);
+    }
+
+    key_schedule.verify_server_certificate_verify(&r, &s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::recv_certificate_verify;
+    use crate::{
+        crypto::ecdsa::verifying_key_from_sec1_bytes, io::CircleReader, key_schedule::KeySchedule,
+        AlertDescription,
+    };
+
+    const PUBLIC_KEY: [u8; 65] = [
+        0x04, 0x9F, 0xAD, 0x84, 0xAE, 0xAE, 0x08, 0xBB, 0xEF, 0x7F, 0x01, 0x00, 0x14, 0xD8, 0x2C,
+        0xEF, 0x6A, 0x09, 0xDE, 0x2B, 0x0C, 0xF8, 0x71, 0xB5, 0xCE, 0x0C, 0x4F, 0x1D, 0x13, 0xA5,
+        0x9A, 0x59, 0x34, 0x07, 0xCB, 0x45, 0x76, 0x9F, 0x10, 0x70, 0xE2, 0xC2, 0x47, 0x0F, 0xE5,
+        0xB1, 0xBF, 0xE6, 0x31, 0x33, 0xC0, 0xB0, 0xCD, 0xC6, 0x4E, 0xA4, 0xBF, 0x37, 0x91, 0xA8,
+        0xEC, 0x2A, 0x07, 0xFD, 0x4F,
+    ];
+
+    /// Signature over an empty transcript.
+    const CERTIFICATE_VERIFY: [u8; 75] = [
+        0x04, 0x03, 0x00, 0x47, 0x30, 0x45, 0x02, 0x21, 0x00, 0xEE, 0x77, 0x80, 0xD4, 0x9E, 0x94,
+        0xFE, 0x2C, 0xD4, 0x9E, 0xA2, 0xCA, 0x1A, 0xD9, 0x57, 0x0E, 0xCF, 0xA4, 0x6A, 0xF4, 0xB4,
+        0xB8, 0x15, 0xE3, 0x5A, 0xA7, 0xBD, 0xC8, 0xB1, 0x52, 0xAB, 0xC5, 0x02, 0x20, 0x19, 0x2C,
+        0xED, 0xE7, 0xF7, 0x10, 0xF2, 0x4F, 0x45, 0xF6, 0x45, 0x72, 0x1A, 0x58, 0xDB, 0xE6, 0xF6,
+        0x12, 0x64, 0xA8, 0xE0, 0x74, 0x27, 0xBE, 0x12, 0x21, 0xDC, 0x12, 0x2A, 0xF7, 0x8B, 0xE4,
+    ];
+
+    #[test]
+    fn verify() {
+        let mut key_schedule: KeySchedule = KeySchedule::default();
+        key_schedule
+            .set_server_certificate_key(verifying_key_from_sec1_bytes(&PUBLIC_KEY).unwrap());
+
+        let mut reader: CircleReader = CircleReader::new(&CERTIFICATE_VERIFY, &[]);
+        assert_eq!(recv_certificate_verify(&mut reader, &key_schedule), Ok(()));
+
+        key_schedule.update_transcript_hash(&[0]);
+        let mut reader: CircleReader = CircleReader::new(&CERTIFICATE_VERIFY, &[]);
+        assert_eq!(
+            recv_certificate_verify(&mut reader, &key_schedule),
+            Err(AlertDescription::DecryptError)
+        );
+    }
+
+    #[test]
+    fn unsupported_scheme() {
+        let mut certificate_verify: [u8; 75] = CERTIFICATE_VERIFY;
+        // rsa_pss_rsae_sha256
+        certificate_verify[..2].copy_from_slice(&[0x08, 0x04]);
+
+        let key_schedule: KeySchedule = KeySchedule::default();
+        let mut reader: CircleReader = CircleReader::new(&certificate_verify, &[]);
+        assert_eq!(
+            recv_certificate_verify(&mut reader, &key_schedule),
+            Err(AlertDescription::IllegalParameter)
+        );
+    }
+}
@@ -5,11 +5,14 @@ use hmac::digest::generic_array::{typenum::U32, GenericArray};
 use w5500_hl::Hostname;
 
 use crate::{
-    cipher_suites::CipherSuite, extension::ExtensionType, key_schedule::KeySchedule, ContentType,
-    TlsVersion,
+    cipher_suites::CipherSuite,
+    crypto::KeyShares,
+    extension::{ExtensionType, MaxFragmentLength},
+    key_schedule::{KeySchedule, PskType},
+    ContentType, TlsVersion,
 };
 use core::mem::size_of;
-use sha2::Sha256;
+use sha2::{Digest, Sha256};
 
 use super::HandshakeType;
 
@@ -422,6 +425,12 @@ pub const RECORD_HEADER_NO_LENGTH: [u8; CONTENT_TYPE.len() + TLS_VERSION.len()]
 
 const LEGACY_SESION_ID_LENGTH: [u8; 1] = [0];
 
+#[cfg(feature = "chacha20poly1305")]
+const CIPHER_SUITES: [CipherSuite; 2] = [
+    CipherSuite::TLS_CHACHA20_POLY1305_SHA256,
+    CipherSuite::TLS_AES_128_GCM_SHA256,
+];
+#[cfg(not(feature = "chacha20poly1305"))]
 const CIPHER_SUITES: [CipherSuite; 1] = [CipherSuite::TLS_AES_128_GCM_SHA256];
 const CIPHER_SUITES_LIST: [u8; CIPHER_SUITES.len() * size_of::<u16>() + size_of::<u16>()] =
     cipher_suites(CIPHER_SUITES);
@@ -446,23 +455,17 @@ const CLIENT_HELLO_SUPPORTED_VERSIONS_EXTENSION: [u8; CLIENT_HELLO_SUPPORTED_VER
     CLIENT_HELLO_SUPPORTED_VERSIONS,
 );
 
-const SIGNATURE_SCHEMES: [SignatureScheme; 9] = [
-    SignatureScheme::RsaPkcs1Sha256,
-    SignatureScheme::RsaPkcs1Sha384,
-    SignatureScheme::RsaPkcs1Sha512,
-    SignatureScheme::EcdsaSecp256r1Sha256,
-    SignatureScheme::EcdsaSecp384r1Sha384,
-    SignatureScheme::RsaPssRsaeSha256,
-    SignatureScheme::RsaPssRsaeSha384,
-    SignatureScheme::RsaPssRsaeSha512,
-    SignatureScheme::Ed25519,
-];
+// only signatures that can be verified are offered
+const SIGNATURE_SCHEMES: [SignatureScheme; 1] = [SignatureScheme::EcdsaSecp256r1Sha256];
 const SIGNATURE_SCHEME_LIST: [u8; SIGNATURE_SCHEMES.len() * size_of::<u16>() + size_of::<u16>()] =
     signature_scheme_list(SIGNATURE_SCHEMES);
 const SIGNATURE_ALGORITHMS_EXTENSION: [u8; SIGNATURE_SCHEME_LIST.len()
     + size_of::<u16>()
     + size_of::<u16>()] = extension(ExtensionType::SignatureAlgorithms, SIGNATURE_SCHEME_LIST);
 
+#[cfg(feature = "x25519")]
+const SUPPORTED_GROUPS: [NamedGroup; 2] = [NamedGroup::x25519, NamedGroup::secp256r1];
+#[cfg(not(feature = "x25519"))]
 const SUPPORTED_GROUPS: [NamedGroup; 1] = [NamedGroup::secp256r1];
 const NAMED_GROUP_LIST: [u8; SUPPORTED_GROUPS.len() * size_of::<u16>() + size_of::<u16>()] =
     named_group_list(SUPPORTED_GROUPS);
@@ -488,6 +491,14 @@ pub const CONST_EXTENSIONS: [u8; SUPPORTED_GROUPS_EXTENSION.len()
     ),
 );
 
+#[cfg(feature = "x25519")]
+const X25519_KEY_SIZE: u16 = 32;
+#[cfg(feature = "x25519")]
+const X25519_KEY_SHARE_ENTRY_LEN: u16 =
+    X25519_KEY_SIZE + (size_of::<u16>() as u16) + (size_of::<NamedGroup>() as u16);
+#[cfg(not(feature = "x25519"))]
+const X25519_KEY_SHARE_ENTRY_LEN: u16 = 0;
+
 struct ClientHelloWriter<'a> {
     buf: &'a mut [u8],
     len: usize,
@@ -511,23 +522,90 @@ impl ClientHelloWriter<'_> {
         self.len += 1;
     }
 
-    pub fn write_binder(&mut self, psk: &[u8], truncated_transcript_hash: Sha256) {
+    pub fn write_binder(
+        &mut self,
+        psk: &[u8],
+        psk_type: PskType,
+        truncated_transcript_hash: Sha256,
+    ) {
         let binder: GenericArray<u8, U32> =
-            self.key_schedule.binder(psk, truncated_transcript_hash);
+            self.key_schedule
+                .binder(psk, psk_type, &truncated_transcript_hash.finalize());
         self.copy_from_slice(&binder);
     }
 }
 
+fn protocol_name_list_length(alpn_protocols: &[&[u8]]) -> u16 {
+    alpn_protocols
+        .iter()
+        .map(|protocol| (protocol.len() as u16) + (size_of::<u8>() as u16))
+        .sum()
+}
+
+fn extensions_length(
+    hostname: &Hostname,
+    identity_len: u16,
+    max_fragment_length: Option<MaxFragmentLength>,
+    alpn_protocols: &[&[u8]],
+) -> u16 {
+    // PSK identities must be at least 1 byte, an empty identity indicates
+    // that no PSK is offered
+    let psk_extension_length: u16 = if identity_len == 0 {
+        0
+    } else {
+        47 + identity_len
+    };
+    let alpn_extension_length: u16 = if alpn_protocols.is_empty() {
+        0
+    } else {
+        6 + protocol_name_list_length(alpn_protocols)
+    };
+    let max_fragment_length_extension_length: u16 =
+        if max_fragment_length.is_some() { 5 } else { 0 };
+    90 + X25519_KEY_SHARE_ENTRY_LEN
+        + (CONST_EXTENSIONS.len() as u16)
+        + u16::from(hostname.len())
+        + max_fragment_length_extension_length
+        + alpn_extension_length
+        + psk_extension_length
+}
+
+fn handshake_length(extensions_length: u16) -> u16 {
+    36 + (LEGACY_THINGS_AND_CIPHER_SUITES.len() as u16) + extensions_length
+}
+
+/// Maximum length of a PSK identity that fits in a ClientHello serialized
+/// into a buffer of `buf_len` bytes.
+pub fn max_identity_len(
+    buf_len: usize,
+    hostname: &Hostname,
+    max_fragment_length: Option<MaxFragmentLength>,
+    alpn_protocols: &[&[u8]],
+) -> usize {
+    // ClientHello with a 1 byte identity, minus the identity
+    let extensions_length: u16 =
+        extensions_length(hostname, 1, max_fragment_length, alpn_protocols);
+    let record_length: usize = RECORD_HEADER_NO_LENGTH.len()
+        + size_of::<u16>()
+        + 4
+        + usize::from(handshake_length(extensions_length));
+    buf_len.saturating_sub(record_length - 1)
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn ser(
     buf: &mut [u8],
     random: &[u8; 32],
     hostname: &Hostname,
-    client_public_key: &[u8; 65],
+    key_shares: &KeyShares,
     key_schedule: &mut KeySchedule,
     psk: &[u8],
+    psk_type: PskType,
     identity: &[u8],
+    obfuscated_ticket_age: u32,
     record_size_limit: u16,
+    max_fragment_length: Option<MaxFragmentLength>,
+    alpn_protocols: &[&[u8]],
 ) -> usize {
     let mut writer: ClientHelloWriter = ClientHelloWriter {
         buf,
@@ -535,9 +613,14 @@ pub fn ser(
         key_schedule,
     };
 
-    let extensions_length: u16 =
-        137 + (CONST_EXTENSIONS.len() as u16) + u16::from(hostname.len()) + (identity.len() as u16);
-    let handshake_length: u16 = 43 + extensions_length;
+    let protocol_name_list_length: u16 = protocol_name_list_length(alpn_protocols);
+    let extensions_length: u16 = extensions_length(
+        hostname,
+        identity.len() as u16,
+        max_fragment_length,
+        alpn_protocols,
+    );
+    let handshake_length: u16 = handshake_length(extensions_length);
     let tls_plaintext_length: u16 = 4 + handshake_length;
 
     // the record header is not included in the transcript hash
@@ -577,25 +660,43 @@ pub fn ser(
     // https://datatracker.ietf.org/doc/html/rfc8446#section-4.2.8
     {
         const P256_UNCOMPRESSED_POINT_SIZE: u16 = 65;
-        const CLIENT_SHARES_LEN: u16 = P256_UNCOMPRESSED_POINT_SIZE
+        const P256_ENTRY_LEN: u16 = P256_UNCOMPRESSED_POINT_SIZE
             + (size_of::<u16>() as u16)
             + (size_of::<NamedGroup>() as u16);
+        const CLIENT_SHARES_LEN: u16 = P256_ENTRY_LEN + X25519_KEY_SHARE_ENTRY_LEN;
         const EXTENSION_LEN: u16 = CLIENT_SHARES_LEN + (size_of::<u16>() as u16);
 
-        const KEY_SHARE_EXTENSION_HEADER: [u8; 10] = [
+        const KEY_SHARE_EXTENSION_HEADER: [u8; 6] = [
             ExtensionType::KeyShare.msb(),
             ExtensionType::KeyShare.lsb(),
             (EXTENSION_LEN >> 8) as u8,
             EXTENSION_LEN as u8,
             (CLIENT_SHARES_LEN >> 8) as u8,
             CLIENT_SHARES_LEN as u8,
+        ];
+        writer.copy_from_slice(&KEY_SHARE_EXTENSION_HEADER);
+
+        // key shares are in order of preference
+        #[cfg(feature = "x25519")]
+        {
+            const X25519_ENTRY_HEADER: [u8; 4] = [
+                NamedGroup::x25519.msb(),
+                NamedGroup::x25519.lsb(),
+                (X25519_KEY_SIZE >> 8) as u8,
+                X25519_KEY_SIZE as u8,
+            ];
+            writer.copy_from_slice(&X25519_ENTRY_HEADER);
+            writer.copy_from_slice(&key_shares.x25519);
+        }
+
+        const P256_ENTRY_HEADER: [u8; 4] = [
             NamedGroup::secp256r1.msb(),
             NamedGroup::secp256r1.lsb(),
             (P256_UNCOMPRESSED_POINT_SIZE >> 8) as u8,
             P256_UNCOMPRESSED_POINT_SIZE as u8,
         ];
-        writer.copy_from_slice(&KEY_SHARE_EXTENSION_HEADER);
-        writer.copy_from_slice(client_public_key);
+        writer.copy_from_slice(&P256_ENTRY_HEADER);
+        writer.copy_from_slice(&key_shares.p256);
     }
 
     // record size limit
@@ -606,9 +707,33 @@ pub fn ser(
         writer.copy_from_slice(&record_size_limit.to_be_bytes());
     }
 
+    // maximum fragment length
+    // https://datatracker.ietf.org/doc/html/rfc6066#section-4
+    if let Some(max_fragment_length) = max_fragment_length {
+        writer.copy_from_slice(&u16::from(ExtensionType::MaxFragmentLength).to_be_bytes());
+        writer.copy_from_slice(&1_u16.to_be_bytes());
+        writer.push(max_fragment_length.into());
+    }
+
+    // application layer protocol negotiation
+    // https://datatracker.ietf.org/doc/html/rfc7301#section-3.1
+    if !alpn_protocols.is_empty() {
+        let extension_len: u16 = protocol_name_list_length + (size_of::<u16>() as u16);
+
+        writer.copy_from_slice(
+            &u16::from(ExtensionType::ApplicationLayerProtocolNegotiation).to_be_bytes(),
+        );
+        writer.copy_from_slice(&extension_len.to_be_bytes());
+        writer.copy_from_slice(&protocol_name_list_length.to_be_bytes());
+        for protocol in alpn_protocols {
+            writer.push(protocol.len() as u8);
+            writer.copy_from_slice(protocol);
+        }
+    }
+
     // pre-shared key
     // https://datatracker.ietf.org/doc/html/rfc8446#section-4.2.11
-    let len: usize = {
+    if !identity.is_empty() {
         let identity_len: u16 = identity.len() as u16;
         let identities_len: u16 =
             (identity_len + (size_of::<u32>() as u16)) + (size_of::<u16>() as u16);
@@ -617,22 +742,18 @@ pub fn ser(
         let extension_len: u16 =
             identities_len + BINDERS_LEN + (size_of::<u16>() as u16) + (size_of::<u16>() as u16);
 
-        // For identities established externally, an obfuscated_ticket_age of 0
-        // SHOULD be used.
-        const OBFUSCATED_TICKET_AGE: u32 = 0;
-
         writer.copy_from_slice(&u16::from(ExtensionType::PreSharedKey).to_be_bytes());
         writer.copy_from_slice(&extension_len.to_be_bytes());
         writer.copy_from_slice(&identities_len.to_be_bytes());
         writer.copy_from_slice(&identity_len.to_be_bytes());
         writer.copy_from_slice(identity);
-        writer.copy_from_slice(&OBFUSCATED_TICKET_AGE.to_be_bytes());
+        writer.copy_from_slice(&obfuscated_ticket_age.to_be_bytes());
         let truncated_transcript_hash: Sha256 = writer.key_schedule.transcript_hash();
         writer.copy_from_slice(&BINDERS_LEN.to_be_bytes());
         writer.copy_from_slice(&[BINDER_LEN]);
-        writer.write_binder(psk, truncated_transcript_hash);
-        writer.len
-    };
+        writer.write_binder(psk, psk_type, truncated_transcript_hash);
+    }
+    let len: usize = writer.len;
 
     let actual_extensions_length: u16 = (len - start_of_extensions) as u16;
     assert_eq!(actual_extensions_length, extensions_length);
@@ -645,3 +766,47 @@ pub fn ser(
 
     len
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{max_identity_len, ser};
+    use crate::{
+        extension::MaxFragmentLength,
+        key_schedule::{KeySchedule, PskType},
+    };
+    use w5500_hl::Hostname;
+
+    #[test]
+    fn max_identity_fills_buffer() {
+        const HOSTNAME: Hostname = Hostname::new_unwrapped("server.local");
+        const ALPN: [&[u8]; 2] = [b"mqtt", b"x-amzn-mqtt-ca"];
+
+        let mut key_schedule: KeySchedule = KeySchedule::default();
+        let key_shares = key_schedule.new_client_secret(&mut rand_core::OsRng);
+
+        let mut buf: [u8; 1024] = [0; 1024];
+        let max: usize = max_identity_len(
+            buf.len(),
+            &HOSTNAME,
+            Some(MaxFragmentLength::Bytes512),
+            &ALPN,
+        );
+        let identity: Vec<u8> = vec![0xAB; max];
+
+        let len: usize = ser(
+            &mut buf,
+            &[0; 32],
+            &HOSTNAME,
+            &key_shares,
+            &mut key_schedule,
+            &[0; 32],
+            PskType::Resumption,
+            &identity,
+            0,
+            16385,
+            Some(MaxFragmentLength::Bytes512),
+            &ALPN,
+        );
+        assert_eq!(len, buf.len());
+    }
+}
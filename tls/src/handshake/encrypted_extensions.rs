@@ -1,6 +1,16 @@
-use crate::{io::CircleReader, AlertDescription, ExtensionType};
+use crate::{extension::MaxFragmentLength, io::CircleReader, AlertDescription, ExtensionType};
 use core::cmp::min;
 
+/// Extensions accepted by the server.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ServerExtensions {
+    /// Index of the application protocol in `alpn_protocols` selected by the
+    /// server.
+    pub alpn_protocol: Option<usize>,
+    /// Maximum fragment length accepted by the server.
+    pub max_fragment_length: Option<MaxFragmentLength>,
+}
+
 /// Encrypted extensions message.
 ///
 /// # References
@@ -12,7 +22,13 @@ use core::cmp::min;
 ///     Extension extensions<0..2^16-1>;
 /// } EncryptedExtensions;
 /// ```
-pub(crate) fn recv_encrypted_extensions(reader: &mut CircleReader) -> Result<(), AlertDescription> {
+pub(crate) fn recv_encrypted_extensions(
+    reader: &mut CircleReader,
+    alpn_protocols: &[&[u8]],
+    max_fragment_length: Option<MaxFragmentLength>,
+) -> Result<ServerExtensions, AlertDescription> {
+    let mut server_extensions: ServerExtensions = ServerExtensions::default();
+
     let extensions_len: u16 = reader.next_u16()?;
     let extensions_end: u16 = match reader.stream_position().checked_add(extensions_len) {
         Some(end) => end,
@@ -58,10 +74,8 @@ pub(crate) fn recv_encrypted_extensions(reader: &mut CircleReader) -> Result<(),
                 }
             }
             ExtensionType::MaxFragmentLength => {
-                // server should not send this since we do not include
-                // it in the ClientHello
-                error!("Unexpected MaxFragmentLength");
-                return Err(AlertDescription::UnsupportedExtension);
+                server_extensions.max_fragment_length =
+                    Some(recv_max_fragment_length(reader, max_fragment_length)?);
             }
             ExtensionType::SupportedGroups => {
                 // Clients MUST NOT act upon any information
@@ -83,8 +97,7 @@ pub(crate) fn recv_encrypted_extensions(reader: &mut CircleReader) -> Result<(),
                 return Err(AlertDescription::UnsupportedExtension);
             }
             ExtensionType::ApplicationLayerProtocolNegotiation => {
-                warn!("application_layer_protocol_negotiation is unused");
-                reader.skip_n(extension_len)?;
+                server_extensions.alpn_protocol = Some(recv_alpn(reader, alpn_protocols)?);
             }
             ExtensionType::ClientCertificateType => {
                 // only used for DTLS
@@ -119,5 +132,179 @@ pub(crate) fn recv_encrypted_extensions(reader: &mut CircleReader) -> Result<(),
         }
     }
 
-    Ok(())
+    Ok(server_extensions)
+}
+
+/// Maximum fragment length extension.
+///
+/// The server response MUST contain the value offered by the client.
+///
+/// # References
+///
+/// * [RFC 6066 Section 4](https://datatracker.ietf.org/doc/html/rfc6066#section-4)
+fn recv_max_fragment_length(
+    reader: &mut CircleReader,
+    max_fragment_length: Option<MaxFragmentLength>,
+) -> Result<MaxFragmentLength, AlertDescription> {
+    let offered: MaxFragmentLength = match max_fragment_length {
+        Some(offered) => offered,
+        None => {
+            // server should not send this since we do not include it in the
+            // ClientHello
+            error!("Unexpected MaxFragmentLength");
+            return Err(AlertDescription::UnsupportedExtension);
+        }
+    };
+
+    let value: u8 = reader.next_u8()?;
+    if value != u8::from(offered) {
+        error!(
+            "MaxFragmentLength {} differs from the offered {:?}",
+            value, offered
+        );
+        Err(AlertDescription::IllegalParameter)
+    } else {
+        debug!("MaxFragmentLength {:?} accepted", offered);
+        Ok(offered)
+    }
+}
+
+/// Application layer protocol negotiation extension.
+///
+/// The server response MUST contain exactly one protocol name.
+///
+/// # References
+///
+/// * [RFC 7301 Section 3.1](https://datatracker.ietf.org/doc/html/rfc7301#section-3.1)
+///
+/// ```text
+/// opaque ProtocolName<1..2^8-1>;
+///
+/// struct {
+///     ProtocolName protocol_name_list<2..2^16-1>
+/// } ProtocolNameList;
+/// ```
+fn recv_alpn(
+    reader: &mut CircleReader,
+    alpn_protocols: &[&[u8]],
+) -> Result<usize, AlertDescription> {
+    if alpn_protocols.is_empty() {
+        // server should not send this since we do not include it in the
+        // ClientHello
+        error!("Unexpected application_layer_protocol_negotiation");
+        return Err(AlertDescription::UnsupportedExtension);
+    }
+
+    let protocol_name_list_len: u16 = reader.next_u16()?;
+    let protocol_name_len: u8 = reader.next_u8()?;
+    if protocol_name_list_len != u16::from(protocol_name_len) + 1 {
+        error!("ALPN ProtocolNameList must contain exactly one ProtocolName");
+        return Err(AlertDescription::DecodeError);
+    }
+
+    let mut buf: [u8; 255] = [0; 255];
+    let protocol_name: &mut [u8] = &mut buf[..protocol_name_len.into()];
+    reader.read_exact(protocol_name)?;
+
+    match alpn_protocols
+        .iter()
+        .position(|protocol| *protocol == protocol_name)
+    {
+        Some(idx) => {
+            debug!("ALPN selected protocol {}", idx);
+            Ok(idx)
+        }
+        None => {
+            error!("ALPN protocol was not offered");
+            Err(AlertDescription::NoApplicationProtocol)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{recv_encrypted_extensions, ServerExtensions};
+    use crate::{extension::MaxFragmentLength, io::CircleReader, AlertDescription};
+
+    const ALPN_PROTOCOLS: [&[u8]; 2] = [b"h2", b"http/1.1"];
+
+    #[rustfmt::skip]
+    const ALPN_H2: [u8; 11] = [
+        0x00, 0x09, // extensions length
+        0x00, 0x10, // application_layer_protocol_negotiation
+        0x00, 0x05, // extension length
+        0x00, 0x03, // protocol_name_list length
+        0x02, b'h', b'2',
+    ];
+
+    #[rustfmt::skip]
+    const MAX_FRAGMENT_LENGTH_1024: [u8; 7] = [
+        0x00, 0x05, // extensions length
+        0x00, 0x01, // max_fragment_length
+        0x00, 0x01, // extension length
+        0x02, // 2^10
+    ];
+
+    #[test]
+    fn no_extensions() {
+        let mut reader: CircleReader = CircleReader::new(&[0, 0], &[]);
+        assert_eq!(
+            recv_encrypted_extensions(&mut reader, &ALPN_PROTOCOLS, None),
+            Ok(ServerExtensions::default())
+        );
+    }
+
+    #[test]
+    fn alpn() {
+        let mut reader: CircleReader = CircleReader::new(&ALPN_H2, &[]);
+        assert_eq!(
+            recv_encrypted_extensions(&mut reader, &ALPN_PROTOCOLS, None),
+            Ok(ServerExtensions {
+                alpn_protocol: Some(0),
+                max_fragment_length: None,
+            })
+        );
+    }
+
+    #[test]
+    fn alpn_not_offered() {
+        let mut reader: CircleReader = CircleReader::new(&ALPN_H2, &[]);
+        assert_eq!(
+            recv_encrypted_extensions(&mut reader, &[b"http/1.1"], None),
+            Err(AlertDescription::NoApplicationProtocol)
+        );
+
+        let mut reader: CircleReader = CircleReader::new(&ALPN_H2, &[]);
+        assert_eq!(
+            recv_encrypted_extensions(&mut reader, &[], None),
+            Err(AlertDescription::UnsupportedExtension)
+        );
+    }
+
+    #[test]
+    fn max_fragment_length() {
+        let mut reader: CircleReader = CircleReader::new(&MAX_FRAGMENT_LENGTH_1024, &[]);
+        assert_eq!(
+            recv_encrypted_extensions(&mut reader, &[], Some(MaxFragmentLength::Bytes1024)),
+            Ok(ServerExtensions {
+                alpn_protocol: None,
+                max_fragment_length: Some(MaxFragmentLength::Bytes1024),
+            })
+        );
+    }
+
+    #[test]
+    fn max_fragment_length_mismatch() {
+        let mut reader: CircleReader = CircleReader::new(&MAX_FRAGMENT_LENGTH_1024, &[]);
+        assert_eq!(
+            recv_encrypted_extensions(&mut reader, &[], Some(MaxFragmentLength::Bytes512)),
+            Err(AlertDescription::IllegalParameter)
+        );
+
+        let mut reader: CircleReader = CircleReader::new(&MAX_FRAGMENT_LENGTH_1024, &[]);
+        assert_eq!(
+            recv_encrypted_extensions(&mut reader, &[], None),
+            Err(AlertDescription::UnsupportedExtension)
+        );
+    }
 }
@@ -1,12 +1,19 @@
+mod certificate;
+mod certificate_verify;
 pub mod client_hello;
 mod encrypted_extensions;
 mod finished;
 mod key_update;
+mod new_session_ticket;
 mod server_hello;
 
-pub(crate) use encrypted_extensions::recv_encrypted_extensions;
+pub(crate) use certificate::recv_certificate;
+pub(crate) use certificate_verify::recv_certificate_verify;
+pub(crate) use encrypted_extensions::{recv_encrypted_extensions, ServerExtensions};
 pub use finished::client_finished;
 pub use key_update::KeyUpdateRequest;
+pub(crate) use new_session_ticket::recv_new_session_ticket;
+pub use new_session_ticket::SessionTicket;
 pub(crate) use server_hello::recv_server_hello;
 
 /// Handshake Type.
@@ -36,15 +43,25 @@ pub(crate) use server_hello::recv_server_hello;
 #[non_exhaustive]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum HandshakeType {
+    /// `client_hello`
     ClientHello = 1,
+    /// `server_hello`
     ServerHello = 2,
+    /// `new_session_ticket`
     NewSessionTicket = 4,
+    /// `end_of_early_data`
     EndOfEarlyData = 5,
+    /// `encrypted_extensions`
     EncryptedExtensions = 8,
+    /// `certificate`
     Certificate = 11,
+    /// `certificate_request`
     CertificateRequest = 13,
+    /// `certificate_verify`
     CertificateVerify = 15,
+    /// `finished`
     Finished = 20,
+    /// `key_update`
     KeyUpdate = 24,
     // this is a synthetic handshake message and will not be TX'd or RX'd
     // https://datatracker.ietf.org/doc/html/rfc8446#section-4.4.1
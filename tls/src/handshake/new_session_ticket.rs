@@ -0,0 +1,262 @@
+use crate::{io::CircleReader, key_schedule::KeySchedule, AlertDescription};
+use core::cmp::min;
+
+/// Clients MUST NOT cache tickets for longer than 7 days.
+const MAX_TICKET_LIFETIME_SECS: u32 = 604800;
+
+/// Session ticket storage, used for session resumption.
+///
+/// The client stores the most recent ticket issued by the server here, and
+/// offers it in the next ClientHello instead of the external pre-shared key.
+///
+/// The buffer holds the ticket sent by the server, which is opaque to the
+/// client.
+/// Tickets that do not fit in the buffer are discarded.
+/// The ClientHello is written to the client RX buffer, tickets that would not
+/// fit in the ClientHello are also discarded, a ticket buffer larger than
+/// the RX buffer is unused.
+///
+/// See [`Client::resume_with`](crate::Client::resume_with).
+///
+/// # References
+///
+/// * [RFC 8446 Section 2.2](https://datatracker.ietf.org/doc/html/rfc8446#section-2.2)
+pub struct SessionTicket<'t> {
+    buf: &'t mut [u8],
+    len: u16,
+    psk: [u8; 32],
+    lifetime: u32,
+    age_add: u32,
+    received: u32,
+}
+
+impl<'t> SessionTicket<'t> {
+    /// Create new, empty, session ticket storage.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use w5500_tls::SessionTicket;
+    ///
+    /// let mut buf: [u8; 256] = [0; 256];
+    /// let ticket: SessionTicket = SessionTicket::new(&mut buf);
+    /// assert!(ticket.is_empty());
+    /// ```
+    pub fn new(buf: &'t mut [u8]) -> Self {
+        Self {
+            buf,
+            len: 0,
+            psk: [0; 32],
+            lifetime: 0,
+            age_add: 0,
+            received: 0,
+        }
+    }
+
+    /// Returns `true` if no ticket is stored.
+    ///
+    /// Tickets are used at most once, this will return `true` after the
+    /// ticket has been offered to the server.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Discard the stored ticket.
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.psk = [0; 32];
+    }
+
+    pub(crate) fn identity(&self) -> &[u8] {
+        &self.buf[..self.len.into()]
+    }
+
+    pub(crate) fn psk(&self) -> &[u8] {
+        &self.psk
+    }
+
+    /// Returns the obfuscated ticket age in milliseconds, or `None` if there
+    /// is no ticket, or the ticket has expired.
+    ///
+    /// # References
+    ///
+    /// * [RFC 8446 Section 4.2.11.1](https://datatracker.ietf.org/doc/html/rfc8446#section-4.2.11.1)
+    pub(crate) fn obfuscated_age(&self, monotonic_secs: u32) -> Option<u32> {
+        let age_secs: u32 = monotonic_secs.saturating_sub(self.received);
+        if self.is_empty() || age_secs >= self.lifetime {
+            None
+        } else {
+            Some(age_secs.saturating_mul(1000).wrapping_add(self.age_add))
+        }
+    }
+}
+
+impl ::core::fmt::Debug for SessionTicket<'_> {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> Result<(), ::core::fmt::Error> {
+        f.debug_struct("SessionTicket")
+            .field("len", &self.len)
+            .field("lifetime", &self.lifetime)
+            .field("received", &self.received)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl ::defmt::Format for SessionTicket<'_> {
+    fn format(&self, fmt: ::defmt::Formatter) {
+        ::defmt::write!(
+            fmt,
+            "SessionTicket {{ len: {}, lifetime: {}, received: {}, .. }}",
+            self.len,
+            self.lifetime,
+            self.received
+        );
+    }
+}
+
+/// New session ticket message.
+///
+/// # References
+///
+/// * [RFC 8446 Section 4.6.1](https://datatracker.ietf.org/doc/html/rfc8446#section-4.6.1)
+///
+/// ```text
+/// struct {
+///     uint32 ticket_lifetime;
+///     uint32 ticket_age_add;
+///     opaque ticket_nonce<0..255>;
+///     opaque ticket<1..2^16-1>;
+///     Extension extensions<0..2^16-2>;
+/// } NewSessionTicket;
+/// ```
+pub(crate) fn recv_new_session_ticket(
+    reader: &mut CircleReader,
+    key_schedule: &KeySchedule,
+    ticket: &mut SessionTicket,
+    max_identity_len: usize,
+    monotonic_secs: u32,
+) -> Result<(), AlertDescription> {
+    ticket.clear();
+
+    let lifetime: u32 = u32::from_be_bytes(reader.next_n()?);
+    let age_add: u32 = u32::from_be_bytes(reader.next_n()?);
+
+    let nonce_len: u8 = reader.next_u8()?;
+    let mut nonce: [u8; 255] = [0; 255];
+    let nonce: &mut [u8] = &mut nonce[..nonce_len.into()];
+    reader.read_exact(nonce)?;
+
+    let ticket_len: u16 = reader.next_u16()?;
+    if ticket_len == 0 {
+        error!("NewSessionTicket ticket is empty");
+        return Err(AlertDescription::DecodeError);
+    }
+
+    // A value of zero indicates that the ticket should be discarded
+    // immediately.
+    if lifetime == 0 {
+        info!("NewSessionTicket has a lifetime of 0");
+        return Ok(());
+    }
+
+    if usize::from(ticket_len) > ticket.buf.len() {
+        warn!(
+            "NewSessionTicket ticket length {} exceeds buffer length {}",
+            ticket_len,
+            ticket.buf.len()
+        );
+        return Ok(());
+    }
+
+    if usize::from(ticket_len) > max_identity_len {
+        warn!(
+            "NewSessionTicket ticket length {} exceeds ClientHello space {}",
+            ticket_len, max_identity_len
+        );
+        return Ok(());
+    }
+
+    reader.read_exact(&mut ticket.buf[..ticket_len.into()])?;
+
+    // the only extension defined for NewSessionTicket is early_data,
+    // which is not supported
+    let extensions_len: u16 = reader.next_u16()?;
+    reader.skip_n(extensions_len)?;
+
+    ticket.psk = key_schedule
+        .resumption_psk(nonce)
+        .ok_or(AlertDescription::InternalError)?
+        .into();
+    ticket.lifetime = min(lifetime, MAX_TICKET_LIFETIME_SECS);
+    ticket.age_add = age_add;
+    ticket.received = monotonic_secs;
+    ticket.len = ticket_len;
+
+    debug!("stored session ticket with lifetime {}", ticket.lifetime);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{recv_new_session_ticket, SessionTicket};
+    use crate::{io::CircleReader, key_schedule::KeySchedule};
+
+    #[rustfmt::skip]
+    const NEW_SESSION_TICKET: [u8; 20] = [
+        0x00, 0x00, 0x1C, 0x20, // ticket_lifetime
+        0x00, 0x00, 0x03, 0xE8, // ticket_age_add
+        0x01, 0xAA, // ticket_nonce
+        0x00, 0x04, 0xDE, 0xAD, 0xBE, 0xEF, // ticket
+        0x00, 0x02, 0x00, 0x00, // extensions
+    ];
+
+    #[test]
+    fn store() {
+        let mut key_schedule: KeySchedule = KeySchedule::default();
+        key_schedule.initialize_resumption_master_secret(&[]);
+
+        let mut buf: [u8; 8] = [0; 8];
+        let mut ticket: SessionTicket = SessionTicket::new(&mut buf);
+        let mut reader: CircleReader = CircleReader::new(&NEW_SESSION_TICKET, &[]);
+        recv_new_session_ticket(&mut reader, &key_schedule, &mut ticket, 64, 100).unwrap();
+
+        assert!(!ticket.is_empty());
+        assert_eq!(ticket.identity(), &[0xDE, 0xAD, 0xBE, 0xEF]);
+        assert_eq!(
+            ticket.psk(),
+            key_schedule.resumption_psk(&[0xAA]).unwrap().as_slice()
+        );
+        assert_eq!(ticket.obfuscated_age(100), Some(1000));
+        assert_eq!(ticket.obfuscated_age(102), Some(3000));
+        assert_eq!(ticket.obfuscated_age(100 + 7200), None);
+
+        ticket.clear();
+        assert!(ticket.is_empty());
+        assert_eq!(ticket.obfuscated_age(100), None);
+    }
+
+    #[test]
+    fn buffer_too_small() {
+        let key_schedule: KeySchedule = KeySchedule::default();
+
+        let mut buf: [u8; 3] = [0; 3];
+        let mut ticket: SessionTicket = SessionTicket::new(&mut buf);
+        let mut reader: CircleReader = CircleReader::new(&NEW_SESSION_TICKET, &[]);
+        recv_new_session_ticket(&mut reader, &key_schedule, &mut ticket, 64, 100).unwrap();
+
+        assert!(ticket.is_empty());
+    }
+
+    #[test]
+    fn client_hello_too_small() {
+        let key_schedule: KeySchedule = KeySchedule::default();
+
+        let mut buf: [u8; 8] = [0; 8];
+        let mut ticket: SessionTicket = SessionTicket::new(&mut buf);
+        let mut reader: CircleReader = CircleReader::new(&NEW_SESSION_TICKET, &[]);
+        recv_new_session_ticket(&mut reader, &key_schedule, &mut ticket, 3, 100).unwrap();
+
+        assert!(ticket.is_empty());
+    }
+}
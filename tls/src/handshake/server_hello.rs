@@ -1,13 +1,18 @@
 use crate::{
     cipher_suites::CipherSuite,
-    crypto::p256::{public_key_from_sec1_bytes, PublicKey},
+    crypto::{p256::public_key_from_sec1_bytes, PublicKey},
     io::CircleReader,
     AlertDescription, ExtensionType, NamedGroup, TlsVersion,
 };
 const P256_KEY_LEN: usize = 65;
+#[cfg(feature = "x25519")]
+const X25519_KEY_LEN: usize = 32;
 
 /// Server Hello key exchange message.
 ///
+/// `psk_offered` indicates if the ClientHello contained a pre-shared key.
+/// If a pre-shared key was offered the server must select it.
+///
 /// # References
 ///
 /// * [RFC 8446 Appendix B.3.1](https://datatracker.ietf.org/doc/html/rfc8446#appendix-B.3.1)
@@ -22,7 +27,10 @@ const P256_KEY_LEN: usize = 65;
 ///     Extension extensions<6..2^16-1>;
 /// } ServerHello;
 /// ```
-pub(crate) fn recv_server_hello(reader: &mut CircleReader) -> Result<PublicKey, AlertDescription> {
+pub(crate) fn recv_server_hello(
+    reader: &mut CircleReader,
+    psk_offered: bool,
+) -> Result<(CipherSuite, PublicKey), AlertDescription> {
     let legacy_version: u16 = reader.next_u16()?;
     const EXPECTED_LEGACY_VERSION: u16 = TlsVersion::V1_2 as u16;
     if legacy_version != EXPECTED_LEGACY_VERSION {
@@ -46,9 +54,14 @@ pub(crate) fn recv_server_hello(reader: &mut CircleReader) -> Result<PublicKey,
     let cipher_suite: CipherSuite = cipher_suite
         .try_into()
         .map_err(|_| AlertDescription::IllegalParameter)?;
-    if cipher_suite != CipherSuite::TLS_AES_128_GCM_SHA256 {
-        error!("unsupported cipher suite: {:?}", cipher_suite);
-        return Err(AlertDescription::HandshakeFailure);
+    match cipher_suite {
+        CipherSuite::TLS_AES_128_GCM_SHA256 => (),
+        #[cfg(feature = "chacha20poly1305")]
+        CipherSuite::TLS_CHACHA20_POLY1305_SHA256 => (),
+        _ => {
+            error!("unsupported cipher suite: {:?}", cipher_suite);
+            return Err(AlertDescription::HandshakeFailure);
+        }
     }
 
     let compression_method: u8 = reader.next_u8()?;
@@ -72,6 +85,7 @@ pub(crate) fn recv_server_hello(reader: &mut CircleReader) -> Result<PublicKey,
     let mut done_pre_shared_key: bool = false;
 
     let mut key_buf: [u8; 65] = [0; 65];
+    let mut group: NamedGroup = NamedGroup::secp256r1;
 
     while extensions_end > reader.stream_position() {
         let extension_type: ExtensionType = match ExtensionType::try_from(reader.next_u16()?) {
@@ -95,25 +109,35 @@ pub(crate) fn recv_server_hello(reader: &mut CircleReader) -> Result<PublicKey,
                     return Err(AlertDescription::IllegalParameter);
                 }
 
-                let group: Result<NamedGroup, u16> = reader.next_u16()?.try_into();
-                if group != Ok(NamedGroup::secp256r1) {
-                    // should never occur because we inform the server
-                    // that we only support for secp256r1
-                    error!("unsupported KeyShareEntry.group={:?}", group);
-                    return Err(AlertDescription::IllegalParameter);
-                }
+                // should never receive a group we did not send a key share
+                // for, the server would send a HelloRetryRequest instead
+                let key_len: usize = match reader.next_u16()?.try_into() {
+                    Ok(NamedGroup::secp256r1) => {
+                        group = NamedGroup::secp256r1;
+                        P256_KEY_LEN
+                    }
+                    #[cfg(feature = "x25519")]
+                    Ok(NamedGroup::x25519) => {
+                        group = NamedGroup::x25519;
+                        X25519_KEY_LEN
+                    }
+                    unsupported => {
+                        error!("unsupported KeyShareEntry.group={:?}", unsupported);
+                        return Err(AlertDescription::IllegalParameter);
+                    }
+                };
 
                 let key_exchange_len: u16 = reader.next_u16()?;
 
-                if usize::from(key_exchange_len) != P256_KEY_LEN {
+                if usize::from(key_exchange_len) != key_len {
                     error!(
-                        "expected P256 key length {} got {}",
-                        P256_KEY_LEN, key_exchange_len
+                        "expected {:?} key length {} got {}",
+                        group, key_len, key_exchange_len
                     );
                     return Err(AlertDescription::DecodeError);
                 }
 
-                reader.read_exact(&mut key_buf)?;
+                reader.read_exact(&mut key_buf[..key_len])?;
 
                 done_key_share = true;
             }
@@ -139,6 +163,11 @@ pub(crate) fn recv_server_hello(reader: &mut CircleReader) -> Result<PublicKey,
                 done_supported_versions = true;
             }
             ExtensionType::PreSharedKey => {
+                if !psk_offered {
+                    error!("PreSharedKey was not offered");
+                    return Err(AlertDescription::UnsupportedExtension);
+                }
+
                 const EXPECTED_LEN: u16 = 2;
                 if extension_len != EXPECTED_LEN {
                     error!(
@@ -186,15 +215,26 @@ pub(crate) fn recv_server_hello(reader: &mut CircleReader) -> Result<PublicKey,
         return Err(AlertDescription::MissingExtension);
     }
 
-    if !done_pre_shared_key {
+    if psk_offered && !done_pre_shared_key {
         error!("missing pre-shared key extension");
         return Err(AlertDescription::MissingExtension);
     }
 
-    if let Some(key) = public_key_from_sec1_bytes(&key_buf) {
-        Ok(key)
-    } else {
-        error!("P256 public key decode");
-        Err(AlertDescription::DecodeError)
-    }
+    let public_key: PublicKey = match group {
+        #[cfg(feature = "x25519")]
+        NamedGroup::x25519 => {
+            let mut bytes: [u8; X25519_KEY_LEN] = [0; X25519_KEY_LEN];
+            bytes.copy_from_slice(&key_buf[..X25519_KEY_LEN]);
+            PublicKey::X25519(crate::crypto::x25519::public_key_from_bytes(bytes))
+        }
+        _ => match public_key_from_sec1_bytes(&key_buf) {
+            Some(key) => PublicKey::P256(key),
+            None => {
+                error!("P256 public key decode");
+                return Err(AlertDescription::DecodeError);
+            }
+        },
+    };
+
+    Ok((cipher_suite, public_key))
 }
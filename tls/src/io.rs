@@ -235,12 +235,10 @@ impl<W5500: Registers> Write<W5500::Error> for TlsWriter<'_, '_, W5500> {
     }
 
     fn send(self) -> Result<(), W5500::Error> {
-        let (key, nonce): ([u8; 16], [u8; 12]) = self.key_schedule.client_key_and_nonce().unwrap();
         encrypt_record_inplace(
             self.w5500,
             self.sn,
-            &key,
-            &nonce,
+            self.key_schedule,
             self.head_ptr,
             self.ptr,
             ContentType::ApplicationData,
@@ -8,8 +8,13 @@
 //! [RFC 5869]: https://datatracker.ietf.org/doc/html/rfc5869
 
 use crate::{
-    crypto::p256::{EphemeralSecret, PublicKey, SharedSecret},
-    AlertDescription,
+    cipher_suites::CipherSuite,
+    crypto::{
+        ecdsa::{self, VerifyingKey},
+        p256::{EphemeralSecret, SharedSecret},
+        Aes128Gcm, Cipher, KeyShares, PublicKey,
+    },
+    AlertDescription, RecordHeader,
 };
 use core::mem::size_of;
 use hkdf::Hkdf;
@@ -18,7 +23,7 @@ use rand_core::{CryptoRng, RngCore};
 use sha2::{
     digest::{
         crypto_common::generic_array::{ArrayLength, GenericArray},
-        typenum::{Unsigned, U12, U32},
+        typenum::{Unsigned, U12, U16, U32},
         OutputSizeUser,
     },
     Digest, Sha256,
@@ -46,7 +51,7 @@ const ZEROS_OF_HASH_LEN: [u8; SHA256_LEN] = [0; SHA256_LEN];
 ///     opaque context<0..255> = Context;
 /// } HkdfLabel;
 /// ```
-const HKDF_LABEL_LEN_MAX: usize = size_of::<u16>() + 255 + 255;
+const HKDF_LABEL_LEN_MAX: usize = size_of::<u16>() + 1 + 255 + 1 + 255;
 fn hkdf_label(len: u16, label: &[u8], context: &[u8]) -> heapless::Vec<u8, HKDF_LABEL_LEN_MAX> {
     let mut hkdf_label: heapless::Vec<u8, HKDF_LABEL_LEN_MAX> = heapless::Vec::new();
     hkdf_label.extend_from_slice(&len.to_be_bytes()).unwrap();
@@ -86,6 +91,62 @@ pub(crate) fn hkdf_expand_label<N: ArrayLength<u8>>(
     okm
 }
 
+/// TLS 1.3 `HKDF-Expand-Label` function with SHA-256, filling `out`.
+///
+/// `secret` is a pseudorandom key, such as a traffic secret, and the length
+/// of the output keying material is the length of `out`.
+/// The `"tls13 "` prefix is added to `label` by this function.
+///
+/// This is the same function used by the TLS key schedule, for protocols that
+/// derive their own keys in the same way.
+///
+/// # Panics
+///
+/// * `secret` is shorter than 32 bytes.
+/// * `label` is longer than 249 bytes.
+/// * `context` is longer than 255 bytes.
+/// * `out` is longer than 8160 bytes (255 times the SHA-256 length).
+///
+/// # Example
+///
+/// Derive the server handshake traffic key and IV from [RFC 8448 Section 3].
+///
+/// ```
+/// use w5500_tls::hkdf_expand_label;
+///
+/// const SERVER_HS_TRAFFIC_SECRET: [u8; 32] = [
+///     0xb6, 0x7b, 0x7d, 0x69, 0x0c, 0xc1, 0x6c, 0x4e, 0x75, 0xe5, 0x42, 0x13, 0xcb, 0x2d, 0x37, 0xb4,
+///     0xe9, 0xc9, 0x12, 0xbc, 0xde, 0xd9, 0x10, 0x5d, 0x42, 0xbe, 0xfd, 0x59, 0xd3, 0x91, 0xad, 0x38,
+/// ];
+///
+/// let mut key: [u8; 16] = [0; 16];
+/// hkdf_expand_label(&SERVER_HS_TRAFFIC_SECRET, b"key", &[], &mut key);
+/// assert_eq!(
+///     key,
+///     [
+///         0x3f, 0xce, 0x51, 0x60, 0x09, 0xc2, 0x17, 0x27, 0xd0, 0xf2, 0xe4, 0xe8, 0x6e, 0xe4, 0x03,
+///         0xbc
+///     ]
+/// );
+///
+/// let mut iv: [u8; 12] = [0; 12];
+/// hkdf_expand_label(&SERVER_HS_TRAFFIC_SECRET, b"iv", &[], &mut iv);
+/// assert_eq!(
+///     iv,
+///     [0x5d, 0x31, 0x3e, 0xb2, 0x67, 0x12, 0x76, 0xee, 0x13, 0x00, 0x0b, 0x30]
+/// );
+/// ```
+///
+/// [RFC 8448 Section 3]: https://datatracker.ietf.org/doc/html/rfc8448#section-3
+#[cfg(feature = "hazmat")]
+pub fn expand_label(secret: &[u8], label: &[u8], context: &[u8], out: &mut [u8]) {
+    let secret: Hkdf<Sha256> =
+        Hkdf::<Sha256>::from_prk(secret).expect("secret must be at least 32 bytes");
+    let len: u16 = u16::try_from(out.len()).expect("out is too long");
+    let label: heapless::Vec<u8, HKDF_LABEL_LEN_MAX> = hkdf_label(len, label, context);
+    secret.expand(&label, out).expect("out is too long");
+}
+
 /// TLS `Derive-Secret` function.
 ///
 /// # References
@@ -113,9 +174,29 @@ pub(crate) fn derive_secret(
     okm
 }
 
+/// Origin of a pre-shared key.
+///
+/// The binder key is derived with a different label for each.
+///
+/// # References
+///
+/// * [RFC 8446 Section 7.1](https://datatracker.ietf.org/doc/html/rfc8446#section-7.1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PskType {
+    /// PSK provisioned out of band, binder key label `"ext binder"`.
+    External,
+    /// PSK from a `NewSessionTicket`, binder key label `"res binder"`.
+    Resumption,
+}
+
 pub struct KeySchedule {
+    cipher_suite: CipherSuite,
+
     client_secret: Option<EphemeralSecret>,
+    #[cfg(feature = "x25519")]
+    client_x25519_secret: Option<crate::crypto::x25519::EphemeralSecret>,
     server_public: Option<PublicKey>,
+    server_certificate_key: Option<VerifyingKey>,
 
     // https://datatracker.ietf.org/doc/html/rfc8446#section-4.4.1
     // Many of the cryptographic computations in TLS make use of a
@@ -140,6 +221,9 @@ pub struct KeySchedule {
 
     client_traffic_secret: Option<Hkdf<Sha256>>,
     server_traffic_secret: Option<Hkdf<Sha256>>,
+
+    resumption_master_secret: Option<Hkdf<Sha256>>,
+    exporter_master_secret: Option<Hkdf<Sha256>>,
 }
 
 impl Default for KeySchedule {
@@ -149,8 +233,12 @@ impl Default for KeySchedule {
         let secret: GenericArray<u8, _> = derive_secret(&hkdf, b"derived", &EMPTY_HASH);
 
         Self {
+            cipher_suite: CipherSuite::TLS_AES_128_GCM_SHA256,
             client_secret: None,
+            #[cfg(feature = "x25519")]
+            client_x25519_secret: None,
             server_public: None,
+            server_certificate_key: None,
             transcript_hash: sha2::Sha256::new(),
             read_record_sequence_number: 0,
             write_record_sequence_number: 0,
@@ -158,6 +246,8 @@ impl Default for KeySchedule {
             secret,
             client_traffic_secret: None,
             server_traffic_secret: None,
+            resumption_master_secret: None,
+            exporter_master_secret: None,
         }
     }
 }
@@ -186,12 +276,30 @@ impl KeySchedule {
         *self = Self::default();
     }
 
-    /// Create a new ephemeral client secret, and return the public key bytes
-    /// as an uncompressed SEC1 encoded point.
-    pub fn new_client_secret<R: RngCore + CryptoRng>(&mut self, rng: &mut R) -> [u8; 65] {
-        let (private, public) = crate::crypto::p256::keygen(rng);
+    pub fn set_cipher_suite(&mut self, cipher_suite: CipherSuite) {
+        self.cipher_suite = cipher_suite;
+    }
+
+    /// Create new ephemeral client secrets for each supported group, and
+    /// return the public keys.
+    ///
+    /// The P256 public key is an uncompressed SEC1 encoded point.
+    pub fn new_client_secret<R: RngCore + CryptoRng>(&mut self, rng: &mut R) -> KeyShares {
+        let (private, p256) = crate::crypto::p256::keygen(rng);
         self.client_secret.replace(private);
-        public
+
+        #[cfg(feature = "x25519")]
+        let x25519: [u8; 32] = {
+            let (private, public) = crate::crypto::x25519::keygen(rng);
+            self.client_x25519_secret.replace(private);
+            public
+        };
+
+        KeyShares {
+            p256,
+            #[cfg(feature = "x25519")]
+            x25519,
+        }
     }
 
     pub fn update_transcript_hash(&mut self, data: &[u8]) {
@@ -214,26 +322,51 @@ impl KeySchedule {
         self.server_public.replace(key);
     }
 
-    fn shared_secret(&self) -> Option<SharedSecret> {
-        Some(crate::crypto::p256::diffie_hellman(
-            self.client_secret.as_ref()?,
-            self.server_public.as_ref()?,
-        ))
+    pub fn set_server_certificate_key(&mut self, key: VerifyingKey) {
+        self.server_certificate_key.replace(key);
+    }
+
+    fn shared_secret(&mut self) -> Result<SharedSecret, AlertDescription> {
+        match self.server_public.as_ref() {
+            Some(PublicKey::P256(public)) => {
+                let secret: &EphemeralSecret = self
+                    .client_secret
+                    .as_ref()
+                    .ok_or(AlertDescription::InternalError)?;
+                Ok(crate::crypto::p256::diffie_hellman(secret, public))
+            }
+            #[cfg(feature = "x25519")]
+            Some(PublicKey::X25519(public)) => {
+                let secret = self
+                    .client_x25519_secret
+                    .take()
+                    .ok_or(AlertDescription::InternalError)?;
+                crate::crypto::x25519::diffie_hellman(secret, public).ok_or_else(|| {
+                    error!("X25519 shared secret is all zeros");
+                    AlertDescription::IllegalParameter
+                })
+            }
+            None => Err(AlertDescription::InternalError),
+        }
     }
 
-    fn binder_key(&mut self, psk: &[u8]) -> Hkdf<Sha256> {
+    fn binder_key(&mut self, psk: &[u8], psk_type: PskType) -> Hkdf<Sha256> {
         (self.secret, self.hkdf) = Hkdf::<Sha256>::extract(Some(&ZEROS_OF_HASH_LEN), psk);
-        let binder_key: GenericArray<u8, U32> =
-            derive_secret(&self.hkdf, b"ext binder", &EMPTY_HASH);
+        let label: &[u8] = match psk_type {
+            PskType::External => b"ext binder",
+            PskType::Resumption => b"res binder",
+        };
+        let binder_key: GenericArray<u8, U32> = derive_secret(&self.hkdf, label, &EMPTY_HASH);
         Hkdf::<Sha256>::from_prk(&binder_key).unwrap()
     }
 
     pub fn binder(
         &mut self,
         psk: &[u8],
-        truncated_transcript_hash: Sha256,
+        psk_type: PskType,
+        truncated_transcript_hash: &GenericArray<u8, U32>,
     ) -> GenericArray<u8, U32> {
-        let binder_key: Hkdf<Sha256> = self.binder_key(psk);
+        let binder_key: Hkdf<Sha256> = self.binder_key(psk, psk_type);
 
         // The PskBinderEntry is computed in the same way as the Finished
         // message (Section 4.4.4) but with the BaseKey being the binder_key
@@ -244,7 +377,7 @@ impl KeySchedule {
         let key: GenericArray<u8, U32> = hkdf_expand_label(&binder_key, b"finished", &[]);
 
         let mut hmac = hmac::Hmac::<Sha256>::new_from_slice(&key).unwrap();
-        hmac.update(&truncated_transcript_hash.finalize());
+        hmac.update(truncated_transcript_hash);
         hmac.finalize().into_bytes()
     }
 
@@ -263,8 +396,8 @@ impl KeySchedule {
         self.write_record_sequence_number = 0;
     }
 
-    pub fn initialize_handshake_secret(&mut self) {
-        let shared_secret = self.shared_secret().unwrap();
+    pub fn initialize_handshake_secret(&mut self) -> Result<(), AlertDescription> {
+        let shared_secret: SharedSecret = self.shared_secret()?;
         (self.secret, self.hkdf) = Hkdf::<Sha256>::extract(Some(&self.secret), &shared_secret);
 
         let transcript_hash_bytes: GenericArray<u8, _> = self.transcript_hash_bytes();
@@ -282,6 +415,8 @@ impl KeySchedule {
 
         self.read_record_sequence_number = 0;
         self.write_record_sequence_number = 0;
+
+        Ok(())
     }
 
     pub fn initialize_master_secret(&mut self) {
@@ -298,12 +433,91 @@ impl KeySchedule {
         self.server_traffic_secret
             .replace(Hkdf::<Sha256>::from_prk(&server_secret).unwrap());
 
+        let exporter_master_secret: GenericArray<u8, _> =
+            derive_secret(&self.hkdf, b"exp master", &transcript_hash_bytes);
+        self.exporter_master_secret
+            .replace(Hkdf::<Sha256>::from_prk(&exporter_master_secret).unwrap());
+
         self.secret = derive_secret(&self.hkdf, b"derived", &EMPTY_HASH);
 
         self.read_record_sequence_number = 0;
         self.write_record_sequence_number = 0;
     }
 
+    /// Initialize the resumption master secret.
+    ///
+    /// This must be called after [`initialize_master_secret`], the transcript
+    /// for this secret includes the client Finished message.
+    ///
+    /// # References
+    ///
+    /// * [RFC 8446 Section 7.1](https://datatracker.ietf.org/doc/html/rfc8446#section-7.1)
+    ///
+    /// ```text
+    /// Derive-Secret(., "res master",
+    ///               ClientHello...client Finished)
+    ///               = resumption_master_secret
+    /// ```
+    ///
+    /// [`initialize_master_secret`]: Self::initialize_master_secret
+    pub fn initialize_resumption_master_secret(&mut self, client_finished: &[u8]) {
+        let mut transcript_hash: Sha256 = self.transcript_hash();
+        transcript_hash.update(client_finished);
+        let resumption_master_secret: GenericArray<u8, _> =
+            derive_secret(&self.hkdf, b"res master", &transcript_hash.finalize());
+        self.resumption_master_secret
+            .replace(Hkdf::<Sha256>::from_prk(&resumption_master_secret).unwrap());
+    }
+
+    /// Derive the PSK associated with a session ticket.
+    ///
+    /// # References
+    ///
+    /// * [RFC 8446 Section 4.6.1](https://datatracker.ietf.org/doc/html/rfc8446#section-4.6.1)
+    ///
+    /// ```text
+    /// HKDF-Expand-Label(resumption_master_secret,
+    ///                   "resumption", ticket_nonce, Hash.length)
+    /// ```
+    pub fn resumption_psk(&self, ticket_nonce: &[u8]) -> Option<GenericArray<u8, U32>> {
+        Some(hkdf_expand_label(
+            self.resumption_master_secret.as_ref()?,
+            b"resumption",
+            ticket_nonce,
+        ))
+    }
+
+    /// Export keying material into `out`.
+    ///
+    /// Returns `None` if the exporter master secret has not been initialized.
+    ///
+    /// # References
+    ///
+    /// * [RFC 8446 Section 7.5](https://datatracker.ietf.org/doc/html/rfc8446#section-7.5)
+    ///
+    /// ```text
+    /// TLS-Exporter(label, context_value, key_length) =
+    ///     HKDF-Expand-Label(Derive-Secret(Secret, label, ""),
+    ///                       "exporter", Hash(context_value), key_length)
+    /// ```
+    pub fn export_keying_material(
+        &self,
+        label: &[u8],
+        context: &[u8],
+        out: &mut [u8],
+    ) -> Option<()> {
+        let secret: GenericArray<u8, U32> =
+            derive_secret(self.exporter_master_secret.as_ref()?, label, &EMPTY_HASH);
+        let secret: Hkdf<Sha256> = Hkdf::<Sha256>::from_prk(&secret).unwrap();
+
+        let context_hash: GenericArray<u8, U32> = Sha256::digest(context);
+        let len: u16 = u16::try_from(out.len()).unwrap();
+        let label: heapless::Vec<u8, HKDF_LABEL_LEN_MAX> =
+            hkdf_label(len, b"exporter", &context_hash);
+        secret.expand(&label, out).unwrap();
+        Some(())
+    }
+
     /// Update traffic secrets.
     ///
     /// # References
@@ -339,39 +553,54 @@ impl KeySchedule {
         self.server_traffic_secret.is_some()
     }
 
-    pub fn client_key_and_nonce(&self) -> Option<([u8; 16], [u8; 12])> {
-        let traffic_secret = self.client_traffic_secret.as_ref()?;
-
-        let key: [u8; 16] = hkdf_expand_label(traffic_secret, b"key", &[]).into();
-        let mut iv: GenericArray<u8, U12> = hkdf_expand_label(traffic_secret, b"iv", &[]);
-        self.write_record_sequence_number
-            .to_be_bytes()
-            .iter()
-            .enumerate()
-            .for_each(|(idx, byte)| iv[idx + 4] ^= byte);
-        Some((key, iv.into()))
-    }
-
-    /// Get the server key and nonce.
+    /// Create a record cipher from a traffic secret.
     ///
     /// # References
     ///
-    /// * [RFC 8446 Section 7.3](https://datatracker.ietf.org/doc/html/rfc8446#ref-sender)
+    /// * [RFC 8446 Section 5.3](https://datatracker.ietf.org/doc/html/rfc8446#section-5.3)
+    /// * [RFC 8446 Section 7.3](https://datatracker.ietf.org/doc/html/rfc8446#section-7.3)
     ///
     /// ```text
     /// [sender]_write_key = HKDF-Expand-Label(Secret, "key", "", key_length)
+    /// [sender]_write_iv  = HKDF-Expand-Label(Secret, "iv", "", iv_length)
     /// ```
-    pub fn server_key_and_nonce(&self) -> Option<([u8; 16], [u8; 12])> {
-        let traffic_secret = self.server_traffic_secret.as_ref()?;
-
-        let key: [u8; 16] = hkdf_expand_label(traffic_secret, b"key", &[]).into();
+    fn cipher(
+        &self,
+        traffic_secret: &Hkdf<Sha256>,
+        record_sequence_number: u64,
+        aad: &[u8; RecordHeader::LEN],
+    ) -> Cipher {
         let mut iv: GenericArray<u8, U12> = hkdf_expand_label(traffic_secret, b"iv", &[]);
-        self.read_record_sequence_number
+        record_sequence_number
             .to_be_bytes()
             .iter()
             .enumerate()
             .for_each(|(idx, byte)| iv[idx + 4] ^= byte);
-        Some((key, iv.into()))
+        let nonce: [u8; 12] = iv.into();
+
+        match self.cipher_suite {
+            #[cfg(feature = "chacha20poly1305")]
+            CipherSuite::TLS_CHACHA20_POLY1305_SHA256 => {
+                let key: [u8; 32] = hkdf_expand_label::<U32>(traffic_secret, b"key", &[]).into();
+                Cipher::ChaCha20Poly1305(crate::crypto::ChaCha20Poly1305::new(&key, &nonce, aad))
+            }
+            _ => {
+                let key: [u8; 16] = hkdf_expand_label::<U16>(traffic_secret, b"key", &[]).into();
+                Cipher::Aes128Gcm(Aes128Gcm::new(&key, &nonce, aad))
+            }
+        }
+    }
+
+    /// Get the client record cipher.
+    pub fn client_cipher(&self, aad: &[u8; RecordHeader::LEN]) -> Option<Cipher> {
+        let traffic_secret = self.client_traffic_secret.as_ref()?;
+        Some(self.cipher(traffic_secret, self.write_record_sequence_number, aad))
+    }
+
+    /// Get the server record cipher.
+    pub fn server_cipher(&self, aad: &[u8; RecordHeader::LEN]) -> Option<Cipher> {
+        let traffic_secret = self.server_traffic_secret.as_ref()?;
+        Some(self.cipher(traffic_secret, self.read_record_sequence_number, aad))
     }
 
     /// # References
@@ -408,6 +637,47 @@ impl KeySchedule {
             .map_err(|_| AlertDescription::DecryptError)
     }
 
+    /// Verify the server CertificateVerify signature.
+    ///
+    /// # References
+    ///
+    /// * [RFC 8446 Section 4.4.3](https://datatracker.ietf.org/doc/html/rfc8446#section-4.4.3)
+    ///
+    /// ```text
+    /// The digital signature is then computed over the concatenation of:
+    /// -  A string that consists of octet 32 (0x20) repeated 64 times
+    /// -  The context string
+    /// -  A single 0 byte which serves as the separator
+    /// -  The content to be signed
+    /// ```
+    pub fn verify_server_certificate_verify(
+        &self,
+        r: &[u8; 32],
+        s: &[u8; 32],
+    ) -> Result<(), AlertDescription> {
+        let key: &VerifyingKey = self
+            .server_certificate_key
+            .as_ref()
+            .ok_or(AlertDescription::InternalError)?;
+
+        const CONTEXT: &[u8] = b"TLS 1.3, server CertificateVerify";
+        let mut hash: Sha256 = Sha256::new();
+        hash.update([0x20; 64]);
+        hash.update(CONTEXT);
+        hash.update([0]);
+        hash.update(self.transcript_hash_bytes());
+        let digest: [u8; 32] = hash.finalize().into();
+
+        // If the verification fails, the receiver MUST terminate the
+        // handshake with a "decrypt_error" alert.
+        if ecdsa::verify_prehash(key, &digest, r, s) {
+            Ok(())
+        } else {
+            error!("CertificateVerify signature is invalid");
+            Err(AlertDescription::DecryptError)
+        }
+    }
+
     pub fn client_finished_verify_data(&self) -> GenericArray<u8, U32> {
         let key: GenericArray<u8, U32> = hkdf_expand_label(
             self.client_traffic_secret.as_ref().unwrap(),
@@ -433,3 +703,228 @@ impl ::defmt::Format for KeySchedule {
         ::defmt::write!(fmt, "KeySchedule {{ ... }}");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        derive_secret, hkdf_expand_label, hkdf_label, KeySchedule, PskType, EMPTY_HASH,
+        HKDF_LABEL_LEN_MAX, ZEROS_OF_HASH_LEN,
+    };
+    use hkdf::Hkdf;
+    use sha2::{
+        digest::{
+            generic_array::GenericArray,
+            typenum::{U12, U16, U32},
+        },
+        Sha256,
+    };
+
+    // Test vectors from RFC 8448 Section 3, "Simple 1-RTT Handshake"
+    // https://datatracker.ietf.org/doc/html/rfc8448#section-3
+
+    const EARLY_SECRET: [u8; 32] = [
+        0x33, 0xad, 0x0a, 0x1c, 0x60, 0x7e, 0xc0, 0x3b, 0x09, 0xe6, 0xcd, 0x98, 0x93, 0x68, 0x0c,
+        0xe2, 0x10, 0xad, 0xf3, 0x00, 0xaa, 0x1f, 0x26, 0x60, 0xe1, 0xb2, 0x2e, 0x10, 0xf1, 0x70,
+        0xf9, 0x2a,
+    ];
+
+    const DERIVED_SECRET: [u8; 32] = [
+        0x6f, 0x26, 0x15, 0xa1, 0x08, 0xc7, 0x02, 0xc5, 0x67, 0x8f, 0x54, 0xfc, 0x9d, 0xba, 0xb6,
+        0x97, 0x16, 0xc0, 0x76, 0x18, 0x9c, 0x48, 0x25, 0x0c, 0xeb, 0xea, 0xc3, 0x57, 0x6c, 0x36,
+        0x11, 0xba,
+    ];
+
+    const SHARED_SECRET: [u8; 32] = [
+        0x8b, 0xd4, 0x05, 0x4f, 0xb5, 0x5b, 0x9d, 0x63, 0xfd, 0xfb, 0xac, 0xf9, 0xf0, 0x4b, 0x9f,
+        0x0d, 0x35, 0xe6, 0xd6, 0x3f, 0x53, 0x75, 0x63, 0xef, 0xd4, 0x62, 0x72, 0x90, 0x0f, 0x89,
+        0x49, 0x2d,
+    ];
+
+    const HANDSHAKE_SECRET: [u8; 32] = [
+        0x1d, 0xc8, 0x26, 0xe9, 0x36, 0x06, 0xaa, 0x6f, 0xdc, 0x0a, 0xad, 0xc1, 0x2f, 0x74, 0x1b,
+        0x01, 0x04, 0x6a, 0xa6, 0xb9, 0x9f, 0x69, 0x1e, 0xd2, 0x21, 0xa9, 0xf0, 0xca, 0x04, 0x3f,
+        0xbe, 0xac,
+    ];
+
+    // Transcript-Hash(ClientHello, ServerHello)
+    const HELLO_HASH: [u8; 32] = [
+        0x86, 0x0c, 0x06, 0xed, 0xc0, 0x78, 0x58, 0xee, 0x8e, 0x78, 0xf0, 0xe7, 0x42, 0x8c, 0x58,
+        0xed, 0xd6, 0xb4, 0x3f, 0x2c, 0xa3, 0xe6, 0xe9, 0x5f, 0x02, 0xed, 0x06, 0x3c, 0xf0, 0xe1,
+        0xca, 0xd8,
+    ];
+
+    const CLIENT_HS_TRAFFIC_SECRET: [u8; 32] = [
+        0xb3, 0xed, 0xdb, 0x12, 0x6e, 0x06, 0x7f, 0x35, 0xa7, 0x80, 0xb3, 0xab, 0xf4, 0x5e, 0x2d,
+        0x8f, 0x3b, 0x1a, 0x95, 0x07, 0x38, 0xf5, 0x2e, 0x96, 0x00, 0x74, 0x6a, 0x0e, 0x27, 0xa5,
+        0x5a, 0x21,
+    ];
+
+    const SERVER_HS_TRAFFIC_SECRET: [u8; 32] = [
+        0xb6, 0x7b, 0x7d, 0x69, 0x0c, 0xc1, 0x6c, 0x4e, 0x75, 0xe5, 0x42, 0x13, 0xcb, 0x2d, 0x37,
+        0xb4, 0xe9, 0xc9, 0x12, 0xbc, 0xde, 0xd9, 0x10, 0x5d, 0x42, 0xbe, 0xfd, 0x59, 0xd3, 0x91,
+        0xad, 0x38,
+    ];
+
+    #[test]
+    fn hkdf_label_max_len() {
+        let label = hkdf_label(32, &[b'a'; 249], &[0; 255]);
+        assert_eq!(label.len(), HKDF_LABEL_LEN_MAX);
+        assert_eq!(label[..3], [0, 32, 255]);
+        assert_eq!(label[2 + 1 + 255], 255);
+    }
+
+    #[test]
+    fn rfc8448_handshake_secrets() {
+        let (early_secret, early) = Hkdf::<Sha256>::extract(None, &ZEROS_OF_HASH_LEN);
+        assert_eq!(early_secret.as_slice(), EARLY_SECRET);
+
+        let derived = derive_secret(&early, b"derived", &EMPTY_HASH);
+        assert_eq!(derived.as_slice(), DERIVED_SECRET);
+
+        let (handshake_secret, handshake) = Hkdf::<Sha256>::extract(Some(&derived), &SHARED_SECRET);
+        assert_eq!(handshake_secret.as_slice(), HANDSHAKE_SECRET);
+
+        let client = derive_secret(&handshake, b"c hs traffic", &HELLO_HASH);
+        assert_eq!(client.as_slice(), CLIENT_HS_TRAFFIC_SECRET);
+
+        let server = derive_secret(&handshake, b"s hs traffic", &HELLO_HASH);
+        assert_eq!(server.as_slice(), SERVER_HS_TRAFFIC_SECRET);
+    }
+
+    // Test vectors from RFC 8448 Section 4, "Resumed 0-RTT Handshake"
+    // https://datatracker.ietf.org/doc/html/rfc8448#section-4
+
+    const RESUMPTION_PSK: [u8; 32] = [
+        0x4e, 0xcd, 0x0e, 0xb6, 0xec, 0x3b, 0x4d, 0x87, 0xf5, 0xd6, 0x02, 0x8f, 0x92, 0x2c, 0xa4,
+        0xc5, 0x85, 0x1a, 0x27, 0x7f, 0xd4, 0x13, 0x11, 0xc9, 0xe6, 0x2d, 0x2c, 0x94, 0x92, 0xe1,
+        0xc4, 0xf3,
+    ];
+
+    // Transcript-Hash(Truncate(ClientHello))
+    const TRUNCATED_HELLO_HASH: [u8; 32] = [
+        0x63, 0x22, 0x4b, 0x2e, 0x45, 0x73, 0xf2, 0xd3, 0x45, 0x4c, 0xa8, 0x4b, 0x9d, 0x00, 0x9a,
+        0x04, 0xf6, 0xbe, 0x9e, 0x05, 0x71, 0x1a, 0x83, 0x96, 0x47, 0x3a, 0xef, 0xa0, 0x1e, 0x92,
+        0x4a, 0x14,
+    ];
+
+    const RESUMPTION_BINDER: [u8; 32] = [
+        0x3a, 0xdd, 0x4f, 0xb2, 0xd8, 0xfd, 0xf8, 0x22, 0xa0, 0xca, 0x3c, 0xf7, 0x67, 0x8e, 0xf5,
+        0xe8, 0x8d, 0xae, 0x99, 0x01, 0x41, 0xc5, 0x92, 0x4d, 0x57, 0xbb, 0x6f, 0xa3, 0x1b, 0x9e,
+        0x5f, 0x9d,
+    ];
+
+    #[test]
+    fn rfc8448_resumption_binder() {
+        let mut key_schedule = KeySchedule::default();
+        let hash: GenericArray<u8, U32> = TRUNCATED_HELLO_HASH.into();
+
+        let binder = key_schedule.binder(&RESUMPTION_PSK, PskType::Resumption, &hash);
+        assert_eq!(binder.as_slice(), RESUMPTION_BINDER);
+
+        // the external binder label produces a different binder
+        let binder = key_schedule.binder(&RESUMPTION_PSK, PskType::External, &hash);
+        assert_ne!(binder.as_slice(), RESUMPTION_BINDER);
+    }
+
+    #[test]
+    fn rfc8448_handshake_traffic_keys() {
+        let client = Hkdf::<Sha256>::from_prk(&CLIENT_HS_TRAFFIC_SECRET).unwrap();
+        let key: GenericArray<u8, U16> = hkdf_expand_label(&client, b"key", &[]);
+        assert_eq!(
+            key.as_slice(),
+            [
+                0xdb, 0xfa, 0xa6, 0x93, 0xd1, 0x76, 0x2c, 0x5b, 0x66, 0x6a, 0xf5, 0xd9, 0x50, 0x25,
+                0x8d, 0x01
+            ]
+        );
+        let iv: GenericArray<u8, U12> = hkdf_expand_label(&client, b"iv", &[]);
+        assert_eq!(
+            iv.as_slice(),
+            [0x5b, 0xd3, 0xc7, 0x1b, 0x83, 0x6e, 0x0b, 0x76, 0xbb, 0x73, 0x26, 0x5f]
+        );
+
+        let server = Hkdf::<Sha256>::from_prk(&SERVER_HS_TRAFFIC_SECRET).unwrap();
+        let key: GenericArray<u8, U16> = hkdf_expand_label(&server, b"key", &[]);
+        assert_eq!(
+            key.as_slice(),
+            [
+                0x3f, 0xce, 0x51, 0x60, 0x09, 0xc2, 0x17, 0x27, 0xd0, 0xf2, 0xe4, 0xe8, 0x6e, 0xe4,
+                0x03, 0xbc
+            ]
+        );
+        let iv: GenericArray<u8, U12> = hkdf_expand_label(&server, b"iv", &[]);
+        assert_eq!(
+            iv.as_slice(),
+            [0x5d, 0x31, 0x3e, 0xb2, 0x67, 0x12, 0x76, 0xee, 0x13, 0x00, 0x0b, 0x30]
+        );
+    }
+
+    // RFC 8448 does not include exporter outputs, the values below continue
+    // from the RFC 8448 handshake secret and were computed with an independent
+    // HKDF implementation.
+
+    const MASTER_SECRET: [u8; 32] = [
+        0x18, 0xdf, 0x06, 0x84, 0x3d, 0x13, 0xa0, 0x8b, 0xf2, 0xa4, 0x49, 0x84, 0x4c, 0x5f, 0x8a,
+        0x47, 0x80, 0x01, 0xbc, 0x4d, 0x4c, 0x62, 0x79, 0x84, 0xd5, 0xa4, 0x1d, 0xa8, 0xd0, 0x40,
+        0x29, 0x19,
+    ];
+
+    const EXPORTER_MASTER_SECRET: [u8; 32] = [
+        0x2f, 0xa2, 0xf8, 0x9b, 0x75, 0x11, 0xc3, 0x8a, 0x11, 0xe0, 0x0c, 0xdc, 0xaa, 0x0d, 0x31,
+        0x4e, 0xad, 0xba, 0xff, 0x3a, 0x11, 0xbb, 0x26, 0xc2, 0x78, 0x84, 0x13, 0x15, 0x81, 0x21,
+        0x45, 0xf8,
+    ];
+
+    const EXPORTED_KEYING_MATERIAL: [u8; 32] = [
+        0xa0, 0x1d, 0xc7, 0x5a, 0x8a, 0xad, 0x86, 0x13, 0x16, 0x92, 0x98, 0x03, 0x27, 0xe8, 0xe1,
+        0x0a, 0x07, 0x17, 0x90, 0xf5, 0x79, 0x3d, 0x40, 0x83, 0xf5, 0x34, 0x03, 0x95, 0x6f, 0x34,
+        0xae, 0x5a,
+    ];
+
+    #[test]
+    fn export_keying_material() {
+        let handshake = Hkdf::<Sha256>::from_prk(&HANDSHAKE_SECRET).unwrap();
+
+        let derived = derive_secret(&handshake, b"derived", &EMPTY_HASH);
+        let (master_secret, _) = Hkdf::<Sha256>::extract(Some(&derived), &ZEROS_OF_HASH_LEN);
+        assert_eq!(master_secret.as_slice(), MASTER_SECRET);
+
+        let mut key_schedule = KeySchedule {
+            secret: derived,
+            ..Default::default()
+        };
+        key_schedule.update_transcript_hash(b"ClientHello...server Finished");
+
+        let mut out: [u8; 32] = [0; 32];
+        assert_eq!(
+            key_schedule.export_keying_material(b"EXPORTER-w5500-tls", b"context", &mut out),
+            None
+        );
+
+        key_schedule.initialize_master_secret();
+
+        let exporter_master_secret = derive_secret(
+            &key_schedule.hkdf,
+            b"exp master",
+            &key_schedule.transcript_hash_bytes(),
+        );
+        assert_eq!(exporter_master_secret.as_slice(), EXPORTER_MASTER_SECRET);
+
+        assert_eq!(
+            key_schedule.export_keying_material(b"EXPORTER-w5500-tls", b"context", &mut out),
+            Some(())
+        );
+        assert_eq!(out, EXPORTED_KEYING_MATERIAL);
+    }
+
+    #[cfg(feature = "hazmat")]
+    #[test]
+    fn expand_label_matches_key_schedule() {
+        let server = Hkdf::<Sha256>::from_prk(&SERVER_HS_TRAFFIC_SECRET).unwrap();
+        let expected: GenericArray<u8, U16> = hkdf_expand_label(&server, b"key", &[]);
+
+        let mut key: [u8; 16] = [0; 16];
+        super::expand_label(&SERVER_HS_TRAFFIC_SECRET, b"key", &[], &mut key);
+        assert_eq!(key, expected.as_slice());
+    }
+}
@@ -19,19 +19,35 @@
 //! * To facilitate the ill-advised encryption in-place in the socket buffers
 //!   there is a hacky AES implementation that has little thought put towards
 //!   constant-time evaluation.
+//!   The `rustcrypto-aes` feature replaces it with the RustCrypto
+//!   implementations, which are constant-time, at the cost of code size.
 //!
 //! # Limitations
 //!
-//! At the moment this only supports pre-shared keys.
+//! This supports pre-shared keys, and server certificates authenticated by
+//! pinning the SubjectPublicKeyInfo hash, see [`Client::new_pinned`].
 //! This will not work for majority of web (HTTPS) applications.
 //!
+//! Session resumption is supported with session tickets, see
+//! [`Client::resume_with`].
+//!
 //! * Requires a local buffer equal to the socket buffer size.
 //!   * TLS record fragmentation makes implementing socket buffer streaming
 //!     impractical.
+//!   * Application data is not limited to the buffer size, the
+//!     `record_size_limit` extension limits each record to fit in the buffer,
+//!     larger payloads arrive as multiple records.
+//!   * Servers that do not support `record_size_limit` may support the
+//!     `max_fragment_length` extension instead, see
+//!     [`Client::set_max_fragment_length`].
+//!   * Records are not decrypted directly into the application, plaintext
+//!     is only released after the authentication tag has been verified.
 //! * Limited cryptography support
-//!   * Cipher: `TLS_AES_128_GCM_SHA256`
-//!   * Key Exchange: `secp256r1`
-//! * Does not support certificate validation
+//!   * Cipher: `TLS_AES_128_GCM_SHA256`, and `TLS_CHACHA20_POLY1305_SHA256`
+//!     with the `chacha20poly1305` feature
+//!   * Key Exchange: `secp256r1`, and `x25519` with the `x25519` feature
+//! * Does not support certificate chain validation
+//!   * Server certificates must use a `secp256r1` key
 //! * Does not support client certificates (mutual TLS)
 //! * Does not support early data
 //! * Does not support serving TLS
@@ -46,9 +62,22 @@
 //! * `log`: Enable logging with `log`.
 //! * `p256-cm4`: Use [`p256-cm4`], a P256 implementation optimized for the
 //!   Cortex-M4 CPU.
+//! * `x25519`: Enable the `x25519` key exchange with [`x25519-dalek`].
+//! * `chacha20poly1305`: Enable the `TLS_CHACHA20_POLY1305_SHA256` cipher suite
+//!   with [`chacha20`] and [`poly1305`].
+//! * `rustcrypto-aes`: Use [`aes`], [`ctr`], and [`ghash`] for
+//!   `TLS_AES_128_GCM_SHA256` instead of the AES implementation in this crate.
+//! * `hazmat`: Expose `hkdf_expand_label`, the TLS 1.3 `HKDF-Expand-Label`
+//!   function used by the key schedule.
 //!
 //! [`w5500-hl`]: https://crates.io/crates/w5500-hl
+//! [`aes`]: https://crates.io/crates/aes
+//! [`chacha20`]: https://crates.io/crates/chacha20
+//! [`ctr`]: https://crates.io/crates/ctr
+//! [`ghash`]: https://crates.io/crates/ghash
 //! [`p256-cm4`]: https://crates.io/crates/p256-cm4
+//! [`poly1305`]: https://crates.io/crates/poly1305
+//! [`x25519-dalek`]: https://crates.io/crates/x25519-dalek
 //! [Wiznet W5500]: https://docs.wiznet.io/Product/iEthernet/W5500/overview
 #![cfg_attr(docsrs, feature(doc_cfg), feature(doc_auto_cfg))]
 #![cfg_attr(not(test), no_std)]
@@ -61,20 +90,24 @@ pub(crate) mod fmt;
 mod alert;
 mod cipher_suites;
 mod crypto;
+mod der;
 mod extension;
 mod handshake;
 mod io;
 mod key_schedule;
 mod record;
 
-use crate::crypto::p256::PublicKey;
+use crate::crypto::{ecdsa::VerifyingKey, Cipher, KeyShares, PublicKey};
 pub use alert::{Alert, AlertDescription, AlertLevel};
+use cipher_suites::CipherSuite;
 use core::{cmp::min, convert::Infallible};
 use extension::ExtensionType;
+pub use extension::MaxFragmentLength;
 use handshake::{
     client_hello::{self, NamedGroup},
-    HandshakeType,
+    ServerExtensions,
 };
+pub use handshake::{HandshakeType, SessionTicket};
 use hl::{
     io::{Read, Seek, Write},
     ll::{BufferSize, Registers, Sn, SocketInterrupt, SocketInterruptMask},
@@ -83,7 +116,9 @@ use hl::{
 };
 use io::Buffer;
 pub use io::{TlsReader, TlsWriter};
-use key_schedule::KeySchedule;
+#[cfg(feature = "hazmat")]
+pub use key_schedule::expand_label as hkdf_expand_label;
+use key_schedule::{KeySchedule, PskType};
 pub use rand_core;
 use rand_core::{CryptoRng, RngCore};
 use record::{ContentType, RecordHeader};
@@ -94,7 +129,7 @@ use sha2::{
 pub use w5500_hl as hl;
 pub use w5500_hl::ll;
 
-const GCM_TAG_LEN: usize = 16;
+const TAG_LEN: usize = 16;
 
 #[repr(u16)]
 enum TlsVersion {
@@ -139,12 +174,12 @@ pub enum Error {
     /// A timeout occurred while waiting for the client to transition from this
     /// state.
     StateTimeout(State),
-    /// Tried to write with [`Client::writer`] or [`Client::write_all`] before
-    /// the handshake has completed.
+    /// Tried to write with [`Client::writer`] or [`Client::write_all`], or
+    /// export keying material before the handshake has completed.
     NotConnected,
 }
 
-/// Duration in seconds to wait for the TLS server to send a response.
+/// Default duration in seconds to wait for the TLS server to send a response.
 const TIMEOUT_SECS: u32 = 10;
 
 /// Internal TLS client states.
@@ -160,7 +195,14 @@ pub enum State {
     WaitServerHello,
     /// Received ServerHello, waiting for EncryptedExtensions.
     WaitEncryptedExtensions,
-    /// Received EncryptedExtensions, waiting for ServerFinished.
+    /// Received EncryptedExtensions, waiting for Certificate.
+    ///
+    /// This state only occurs with certificate authentication.
+    WaitCertificate,
+    /// Received Certificate, waiting for CertificateVerify.
+    WaitCertificateVerify,
+    /// Received EncryptedExtensions or CertificateVerify, waiting for
+    /// ServerFinished.
     WaitFinished,
     /// Client will send ClientFinished on the next call to [`Client::process`].
     SendFinished,
@@ -184,6 +226,7 @@ pub enum State {
 /// This is returned by [`Client::process`].
 #[derive(Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
 pub enum Event {
     /// A hint to call [`Client::process`] after this many seconds have elapsed.
     ///
@@ -203,6 +246,12 @@ pub enum Event {
     HandshakeFinished,
     /// Expected disconnection.
     Disconnect,
+    /// No data has been sent or received for the interval set with
+    /// [`Client::set_idle_interval_secs`].
+    ///
+    /// This can be used to send application layer keep-alive messages,
+    /// such as a MQTT PINGREQ.
+    Idle,
     /// No event occurred, the client ready and idle.
     None,
 }
@@ -218,36 +267,69 @@ pub enum Event {
 /// The socket RX buffer size will be set to match N.
 /// When using pre-shared keys the default value of `N=2048` is typically
 /// sufficient.
+/// When using certificate authentication the entire certificate chain sent by
+/// the server must fit, this typically requires 8192 or 16384 bytes.
 ///
 /// This buffer is necessary because handshakes may be fragmented across
 /// multiple records, and due to the gaps left by the headers and footers is is
 /// not feasible to reassemble fragments within the socket buffers.
+///
+/// # Multiple Clients
+///
+/// Clients do not share any state, multiple clients can run on the same W5500
+/// by giving each client a different socket and RX buffer, and calling
+/// [`process`](Self::process) for each client with the same device.
+///
+/// The socket RX buffer sizes of all clients must fit within the 16 KiB of
+/// W5500 RX memory.
 pub struct Client<'hn, 'psk, 'b, const N: usize> {
     sn: Sn,
     src_port: u16,
     hostname: Hostname<'hn>,
+    alpn_protocols: &'hn [&'hn [u8]],
+    alpn_protocol: Option<&'hn [u8]>,
+    /// Maximum fragment length offered to the server
+    offer_max_fragment_length: Option<MaxFragmentLength>,
+    /// Maximum fragment length accepted by the server
+    max_fragment_length: Option<MaxFragmentLength>,
     dst: SocketAddrV4,
     state: State,
 
     /// Timeout for TLS server responses
     timeout: Option<u32>,
+    timeout_secs: u32,
     key_schedule: KeySchedule,
 
+    /// Interval for idle events
+    idle_interval_secs: Option<u32>,
+    /// Time of the last activity in the connected state
+    idle_since: u32,
+
+    /// Initial and maximum reconnect backoff
+    reconnect_backoff: Option<(u32, u32)>,
+    /// Backoff after the next failed connection
+    backoff_secs: u32,
+    /// Time to reconnect after a failed connection
+    reconnect_at: Option<u32>,
+
     identity: &'psk [u8],
     psk: &'psk [u8],
+    ticket: Option<SessionTicket<'psk>>,
+    spki_pin: Option<[u8; 32]>,
+
+    /// Called for each received handshake message
+    on_handshake_message: Option<fn(Result<HandshakeType, u8>, u32)>,
 
     // RX buffer
     rx: Buffer<'b, N>,
 }
 
 const fn size_to_buffersize(size: usize) -> BufferSize {
-    match size {
-        1024 => BufferSize::KB1,
-        2048 => BufferSize::KB2,
-        4096 => BufferSize::KB4,
-        8192 => BufferSize::KB8,
-        16384 => BufferSize::KB16,
-        _ => ::core::panic!("valid buffer sizes are 1024, 2048, 4096, 8192, or 16384"),
+    match BufferSize::from_bytes(size) {
+        Ok(BufferSize::KB0) | Err(_) => {
+            ::core::panic!("valid buffer sizes are 1024, 2048, 4096, 8192, or 16384")
+        }
+        Ok(buffer_size) => buffer_size,
     }
 }
 
@@ -258,8 +340,7 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
     // https://www.rfc-editor.org/rfc/rfc8449
     // minus 1 because the local memory circular buffer implementation
     // does not use full/empty flags
-    const RECORD_SIZE_LIMIT: u16 =
-        (N as u16) - (GCM_TAG_LEN as u16) - (RecordHeader::LEN as u16) - 1;
+    const RECORD_SIZE_LIMIT: u16 = (N as u16) - (TAG_LEN as u16) - (RecordHeader::LEN as u16) - 1;
 
     /// Create a new TLS client.
     ///
@@ -309,27 +390,538 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
     /// );
     /// ```
     ///
-    /// [`Ipv4Addr`]: w5500_hl::ll::net::Ipv4Addr
-    pub fn new(
-        sn: Sn,
-        src_port: u16,
-        hostname: Hostname<'hn>,
-        dst: SocketAddrV4,
-        identity: &'psk [u8],
-        psk: &'psk [u8],
-        rx: &'b mut [u8; N],
-    ) -> Self {
-        Self {
-            sn,
-            src_port,
-            hostname,
-            dst,
-            state: State::Reset,
-            timeout: None,
-            key_schedule: KeySchedule::default(),
-            identity,
-            psk,
-            rx: Buffer::from(rx),
+    /// [`Ipv4Addr`]: w5500_hl::ll::net::Ipv4Addr
+    pub fn new(
+        sn: Sn,
+        src_port: u16,
+        hostname: Hostname<'hn>,
+        dst: SocketAddrV4,
+        identity: &'psk [u8],
+        psk: &'psk [u8],
+        rx: &'b mut [u8; N],
+    ) -> Self {
+        Self {
+            sn,
+            src_port,
+            hostname,
+            alpn_protocols: &[],
+            alpn_protocol: None,
+            offer_max_fragment_length: None,
+            max_fragment_length: None,
+            dst,
+            state: State::Reset,
+            timeout: None,
+            timeout_secs: TIMEOUT_SECS,
+            key_schedule: KeySchedule::default(),
+            idle_interval_secs: None,
+            idle_since: 0,
+            reconnect_backoff: None,
+            backoff_secs: 0,
+            reconnect_at: None,
+            identity,
+            psk,
+            ticket: None,
+            spki_pin: None,
+            on_handshake_message: None,
+            rx: Buffer::from(rx),
+        }
+    }
+
+    /// Create a new TLS client with session resumption.
+    ///
+    /// This is the same as [`new`](Self::new), except session tickets sent by
+    /// the server are stored in `ticket`.
+    ///
+    /// If `ticket` contains an unexpired ticket it will be offered to the
+    /// server in place of the external pre-shared key on the next connection.
+    /// Tickets are only offered once, if the server rejects the ticket the
+    /// following connection will use the external pre-shared key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # const MY_KEY: [u8; 1] = [0];
+    /// use w5500_tls::{
+    ///     Client, SessionTicket,
+    ///     {
+    ///         hl::Hostname,
+    ///         ll::{
+    ///             net::{Ipv4Addr, SocketAddrV4},
+    ///             Sn,
+    ///         },
+    ///     },
+    /// };
+    ///
+    /// static mut RX: [u8; 2048] = [0; 2048];
+    /// static mut TICKET: [u8; 256] = [0; 256];
+    ///
+    /// const DST: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 168, 0, 4), 8883);
+    /// const HOSTNAME: Hostname = Hostname::new_unwrapped("server.local");
+    /// const SRC_PORT: u16 = 1234;
+    /// const TLS_SN: Sn = Sn::Sn4;
+    ///
+    /// let tls_client: Client<2048> = Client::resume_with(
+    ///     TLS_SN,
+    ///     SRC_PORT,
+    ///     HOSTNAME,
+    ///     DST,
+    ///     b"mykeyidentity",
+    ///     &MY_KEY,
+    ///     SessionTicket::new(unsafe { &mut TICKET }),
+    ///     unsafe { &mut RX },
+    /// );
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn resume_with(
+        sn: Sn,
+        src_port: u16,
+        hostname: Hostname<'hn>,
+        dst: SocketAddrV4,
+        identity: &'psk [u8],
+        psk: &'psk [u8],
+        ticket: SessionTicket<'psk>,
+        rx: &'b mut [u8; N],
+    ) -> Self {
+        Self {
+            ticket: Some(ticket),
+            ..Self::new(sn, src_port, hostname, dst, identity, psk, rx)
+        }
+    }
+
+    /// Create a new TLS client that authenticates the server with a
+    /// certificate.
+    ///
+    /// This is the same as [`new`](Self::new), except a pre-shared key is not
+    /// offered, and the server must authenticate with a certificate.
+    ///
+    /// The certificate chain is not validated, instead the SubjectPublicKeyInfo
+    /// of the server certificate is pinned.
+    /// The SHA-256 hash of the DER encoded SubjectPublicKeyInfo must match
+    /// `spki_sha256`.
+    /// Only `secp256r1` (P-256) certificates are supported.
+    ///
+    /// The pin can be created from the server certificate with `openssl`:
+    ///
+    /// ```bash
+    /// openssl x509 -in server.crt -pubkey -noout \
+    ///     | openssl pkey -pubin -outform der \
+    ///     | openssl dgst -sha256
+    /// ```
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # const SPKI_SHA256: [u8; 32] = [0; 32];
+    /// use w5500_tls::{
+    ///     Client,
+    ///     {
+    ///         hl::Hostname,
+    ///         ll::{
+    ///             net::{Ipv4Addr, SocketAddrV4},
+    ///             Sn,
+    ///         },
+    ///     },
+    /// };
+    ///
+    /// static mut RX: [u8; 8192] = [0; 8192];
+    ///
+    /// const DST: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 168, 0, 4), 8883);
+    /// const HOSTNAME: Hostname = Hostname::new_unwrapped("server.local");
+    /// const SRC_PORT: u16 = 1234;
+    /// const TLS_SN: Sn = Sn::Sn4;
+    ///
+    /// let tls_client: Client<8192> = Client::new_pinned(
+    ///     TLS_SN,
+    ///     SRC_PORT,
+    ///     HOSTNAME,
+    ///     DST,
+    ///     SPKI_SHA256,
+    ///     unsafe { &mut RX },
+    /// );
+    /// ```
+    pub fn new_pinned(
+        sn: Sn,
+        src_port: u16,
+        hostname: Hostname<'hn>,
+        dst: SocketAddrV4,
+        spki_sha256: [u8; 32],
+        rx: &'b mut [u8; N],
+    ) -> Self {
+        Self {
+            spki_pin: Some(spki_sha256),
+            ..Self::new(sn, src_port, hostname, dst, &[], &[], rx)
+        }
+    }
+
+    /// Session ticket storage.
+    ///
+    /// This is `None` if the client was not created with
+    /// [`resume_with`](Self::resume_with).
+    pub fn session_ticket(&self) -> Option<&SessionTicket<'psk>> {
+        self.ticket.as_ref()
+    }
+
+    /// Set the TLS server response timeout duration in seconds.
+    ///
+    /// This is the duration to wait for the TLS server to respond before
+    /// returning [`Error::StateTimeout`].
+    ///
+    /// The default is 10 seconds.
+    ///
+    /// # Example
+    ///
+    /// Set a 30 second timeout.
+    ///
+    /// ```
+    /// # const MY_KEY: [u8; 1] = [0];
+    /// use w5500_tls::{
+    ///     Client,
+    ///     {
+    ///         hl::Hostname,
+    ///         ll::{
+    ///             net::{Ipv4Addr, SocketAddrV4},
+    ///             Sn,
+    ///         },
+    ///     },
+    /// };
+    ///
+    /// static mut RX: [u8; 2048] = [0; 2048];
+    ///
+    /// const DST: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 168, 0, 4), 8883);
+    /// const HOSTNAME: Hostname = Hostname::new_unwrapped("server.local");
+    /// const SRC_PORT: u16 = 1234;
+    /// const TLS_SN: Sn = Sn::Sn4;
+    ///
+    /// let mut tls_client: Client<2048> = Client::new(
+    ///     TLS_SN,
+    ///     SRC_PORT,
+    ///     HOSTNAME,
+    ///     DST,
+    ///     b"mykeyidentity",
+    ///     &MY_KEY,
+    ///     unsafe { &mut RX },
+    /// );
+    /// tls_client.set_timeout_secs(30);
+    /// ```
+    pub fn set_timeout_secs(&mut self, secs: u32) {
+        self.timeout_secs = secs;
+    }
+
+    /// Set the interval for [`Event::Idle`].
+    ///
+    /// When set [`process`](Self::process) will return [`Event::Idle`] after
+    /// this many seconds have elapsed in the connected state without the
+    /// socket sending or receiving data.
+    /// The interval restarts after each [`Event::Idle`].
+    ///
+    /// Activity is detected with the RECV and SENDOK socket interrupts,
+    /// [`process`](Self::process) must be called when there is a pending
+    /// socket interrupt, and at least once per interval.
+    ///
+    /// `None` disables idle events, this is the default.
+    ///
+    /// # Example
+    ///
+    /// Send a MQTT PINGREQ after 60 seconds of inactivity.
+    ///
+    /// ```
+    /// # const MY_KEY: [u8; 1] = [0];
+    /// use w5500_tls::{
+    ///     Client,
+    ///     {
+    ///         hl::Hostname,
+    ///         ll::{
+    ///             net::{Ipv4Addr, SocketAddrV4},
+    ///             Sn,
+    ///         },
+    ///     },
+    /// };
+    ///
+    /// static mut RX: [u8; 2048] = [0; 2048];
+    ///
+    /// const DST: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 168, 0, 4), 8883);
+    /// const HOSTNAME: Hostname = Hostname::new_unwrapped("server.local");
+    /// const SRC_PORT: u16 = 1234;
+    /// const TLS_SN: Sn = Sn::Sn4;
+    ///
+    /// let mut tls_client: Client<2048> = Client::new(
+    ///     TLS_SN,
+    ///     SRC_PORT,
+    ///     HOSTNAME,
+    ///     DST,
+    ///     b"mykeyidentity",
+    ///     &MY_KEY,
+    ///     unsafe { &mut RX },
+    /// );
+    /// tls_client.set_idle_interval_secs(Some(60));
+    /// ```
+    pub fn set_idle_interval_secs(&mut self, secs: Option<u32>) {
+        self.idle_interval_secs = secs;
+    }
+
+    /// Set the exponential backoff for reconnecting after a failed TCP
+    /// connection.
+    ///
+    /// After the TCP connection is refused or times out
+    /// [`process`](Self::process) returns [`Event::CallAfter`] until the
+    /// backoff elapses instead of reconnecting immediately.
+    /// The backoff starts at `initial_secs`, and doubles after each
+    /// consecutive failure up to `max_secs`.
+    /// The backoff returns to `initial_secs` after a successful connection.
+    ///
+    /// `initial_secs` is clamped to a minimum of 1 second, and `max_secs` is
+    /// clamped to a minimum of `initial_secs`.
+    ///
+    /// `None` disables the backoff, this is the default.
+    ///
+    /// # Example
+    ///
+    /// Reconnect after 1, 2, 4, 8, ..., 300 seconds.
+    ///
+    /// ```
+    /// # const MY_KEY: [u8; 1] = [0];
+    /// use w5500_tls::{
+    ///     Client,
+    ///     {
+    ///         hl::Hostname,
+    ///         ll::{
+    ///             net::{Ipv4Addr, SocketAddrV4},
+    ///             Sn,
+    ///         },
+    ///     },
+    /// };
+    ///
+    /// static mut RX: [u8; 2048] = [0; 2048];
+    ///
+    /// const DST: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 168, 0, 4), 8883);
+    /// const HOSTNAME: Hostname = Hostname::new_unwrapped("server.local");
+    /// const SRC_PORT: u16 = 1234;
+    /// const TLS_SN: Sn = Sn::Sn4;
+    ///
+    /// let mut tls_client: Client<2048> = Client::new(
+    ///     TLS_SN,
+    ///     SRC_PORT,
+    ///     HOSTNAME,
+    ///     DST,
+    ///     b"mykeyidentity",
+    ///     &MY_KEY,
+    ///     unsafe { &mut RX },
+    /// );
+    /// tls_client.set_reconnect_backoff(Some((1, 300)));
+    /// ```
+    pub fn set_reconnect_backoff(&mut self, backoff: Option<(u32, u32)>) {
+        let backoff: Option<(u32, u32)> = backoff.map(|(initial, max)| {
+            let initial: u32 = initial.max(1);
+            (initial, max.max(initial))
+        });
+        self.reconnect_backoff = backoff;
+        self.backoff_secs = backoff.map(|(initial, _)| initial).unwrap_or(0);
+        self.reconnect_at = None;
+    }
+
+    fn connect_failed(&mut self, monotonic_secs: u32) {
+        if let Some((_, max)) = self.reconnect_backoff {
+            debug!("reconnecting in {}s", self.backoff_secs);
+            self.reconnect_at = Some(monotonic_secs.saturating_add(self.backoff_secs));
+            self.backoff_secs = self.backoff_secs.saturating_mul(2).min(max);
+        }
+    }
+
+    fn idle_event(&mut self, monotonic_secs: u32) -> Option<Event> {
+        let interval: u32 = self.idle_interval_secs?;
+        if self.connected() && monotonic_secs.saturating_sub(self.idle_since) >= interval {
+            self.idle_since = monotonic_secs;
+            Some(Event::Idle)
+        } else {
+            None
+        }
+    }
+
+    /// Set the application layer protocols offered to the server with
+    /// application layer protocol negotiation (ALPN), in order of preference.
+    ///
+    /// This takes effect on the next connection.
+    ///
+    /// # Panics
+    ///
+    /// * Protocols must be between 1 and 255 bytes in length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # const MY_KEY: [u8; 1] = [0];
+    /// use w5500_tls::{
+    ///     Client,
+    ///     {
+    ///         hl::Hostname,
+    ///         ll::{
+    ///             net::{Ipv4Addr, SocketAddrV4},
+    ///             Sn,
+    ///         },
+    ///     },
+    /// };
+    ///
+    /// static mut RX: [u8; 2048] = [0; 2048];
+    ///
+    /// const DST: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 168, 0, 4), 8883);
+    /// const HOSTNAME: Hostname = Hostname::new_unwrapped("server.local");
+    /// const SRC_PORT: u16 = 1234;
+    /// const TLS_SN: Sn = Sn::Sn4;
+    ///
+    /// let mut tls_client: Client<2048> = Client::new(
+    ///     TLS_SN,
+    ///     SRC_PORT,
+    ///     HOSTNAME,
+    ///     DST,
+    ///     b"mykeyidentity",
+    ///     &MY_KEY,
+    ///     unsafe { &mut RX },
+    /// );
+    /// tls_client.set_alpn_protocols(&[b"mqtt"]);
+    /// ```
+    pub fn set_alpn_protocols(&mut self, protocols: &'hn [&'hn [u8]]) {
+        protocols.iter().for_each(|protocol| {
+            assert!(
+                (1..=255).contains(&protocol.len()),
+                "ALPN protocols must be 1 to 255 bytes"
+            )
+        });
+        self.alpn_protocols = protocols;
+    }
+
+    /// Application layer protocol selected by the server.
+    ///
+    /// Returns `None` if the server did not select a protocol, or if the
+    /// handshake has not yet progressed far enough to know.
+    pub fn alpn_protocol(&self) -> Option<&'hn [u8]> {
+        self.alpn_protocol
+    }
+
+    /// Set the maximum fragment length offered to the server.
+    ///
+    /// This adds the `max_fragment_length` extension to the ClientHello,
+    /// asking the server to limit the plaintext length of each record.
+    /// The client always offers the `record_size_limit` extension to fit
+    /// records in the RX buffer, this is for servers that only support
+    /// `max_fragment_length`.
+    ///
+    /// If the server accepts the extension records that exceed the agreed
+    /// length are rejected with a `record_overflow` alert.
+    ///
+    /// The default is `None`, which does not offer the extension.
+    /// This takes effect on the next connection.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # const MY_KEY: [u8; 1] = [0];
+    /// use w5500_tls::{
+    ///     Client, MaxFragmentLength,
+    ///     {
+    ///         hl::Hostname,
+    ///         ll::{
+    ///             net::{Ipv4Addr, SocketAddrV4},
+    ///             Sn,
+    ///         },
+    ///     },
+    /// };
+    ///
+    /// static mut RX: [u8; 1024] = [0; 1024];
+    ///
+    /// const DST: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 168, 0, 4), 8883);
+    /// const HOSTNAME: Hostname = Hostname::new_unwrapped("server.local");
+    /// const SRC_PORT: u16 = 1234;
+    /// const TLS_SN: Sn = Sn::Sn4;
+    ///
+    /// let mut tls_client: Client<1024> = Client::new(
+    ///     TLS_SN,
+    ///     SRC_PORT,
+    ///     HOSTNAME,
+    ///     DST,
+    ///     b"mykeyidentity",
+    ///     &MY_KEY,
+    ///     unsafe { &mut RX },
+    /// );
+    /// tls_client.set_max_fragment_length(Some(MaxFragmentLength::Bytes512));
+    /// ```
+    pub fn set_max_fragment_length(&mut self, max_fragment_length: Option<MaxFragmentLength>) {
+        self.offer_max_fragment_length = max_fragment_length;
+    }
+
+    /// Maximum fragment length accepted by the server.
+    ///
+    /// Returns `None` if the server did not accept the `max_fragment_length`
+    /// extension, or if the handshake has not yet progressed far enough to
+    /// know.
+    pub fn max_fragment_length(&self) -> Option<MaxFragmentLength> {
+        self.max_fragment_length
+    }
+
+    /// Set a callback for each handshake message received from the server.
+    ///
+    /// The callback is called with the message type and the length of the
+    /// message body as each message is processed, before the message is
+    /// validated.
+    /// Message types that are not recognized are passed as `Err(u8)`.
+    ///
+    /// This is intended for debugging handshake failures, for example by
+    /// comparing the sequence of messages with a working session from
+    /// `openssl s_client`.
+    ///
+    /// The default is `None`, which does not call anything.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # const MY_KEY: [u8; 1] = [0];
+    /// use w5500_tls::{
+    ///     Client, HandshakeType,
+    ///     {
+    ///         hl::Hostname,
+    ///         ll::{
+    ///             net::{Ipv4Addr, SocketAddrV4},
+    ///             Sn,
+    ///         },
+    ///     },
+    /// };
+    ///
+    /// static mut RX: [u8; 1024] = [0; 1024];
+    ///
+    /// const DST: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 168, 0, 4), 8883);
+    /// const HOSTNAME: Hostname = Hostname::new_unwrapped("server.local");
+    /// const SRC_PORT: u16 = 1234;
+    /// const TLS_SN: Sn = Sn::Sn4;
+    ///
+    /// let mut tls_client: Client<1024> = Client::new(
+    ///     TLS_SN,
+    ///     SRC_PORT,
+    ///     HOSTNAME,
+    ///     DST,
+    ///     b"mykeyidentity",
+    ///     &MY_KEY,
+    ///     unsafe { &mut RX },
+    /// );
+    ///
+    /// fn trace(msg_type: Result<HandshakeType, u8>, length: u32) {
+    ///     println!("<- {msg_type:?} ({length} bytes)");
+    /// }
+    ///
+    /// tls_client.on_handshake_message(Some(trace));
+    /// ```
+    pub fn on_handshake_message(&mut self, callback: Option<fn(Result<HandshakeType, u8>, u32)>) {
+        self.on_handshake_message = callback;
+    }
+
+    /// Maximum length of a received record.
+    fn record_length_limit(&self) -> u16 {
+        match self.max_fragment_length {
+            // the fragment length limits the plaintext, the encrypted record
+            // also contains the content type and authentication tag
+            Some(max_fragment_length) => min(
+                Self::RECORD_SIZE_LIMIT,
+                max_fragment_length.bytes() + 1 + (TAG_LEN as u16),
+            ),
+            None => Self::RECORD_SIZE_LIMIT,
         }
     }
 
@@ -344,7 +936,7 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
         );
         self.state = state;
         self.timeout = Some(monotonic_secs);
-        TIMEOUT_SECS
+        self.timeout_secs
     }
 
     fn set_state(&mut self, state: State) {
@@ -402,6 +994,9 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
 
             if sn_ir.con_raised() {
                 info!("CONN interrupt");
+                if let Some((initial, _)) = self.reconnect_backoff {
+                    self.backoff_secs = initial;
+                }
                 if let Err(e) = self.send_client_hello(w5500, rng, monotonic_secs) {
                     return Err(self.send_fatal_alert(w5500, e, monotonic_secs));
                 }
@@ -409,11 +1004,15 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
             if sn_ir.discon_raised() {
                 info!("DISCON interrupt");
                 // TODO: try to get discon reason from server
-                if self.state != State::WaitDiscon {
-                    warn!("Unexpected TCP disconnect");
+                if self.state == State::WaitDiscon {
                     self.reset();
                     return Ok(Event::Disconnect);
                 } else {
+                    warn!("Unexpected TCP disconnect");
+                    if self.state == State::WaitConInt {
+                        self.connect_failed(monotonic_secs);
+                    }
+                    self.reset();
                     return Err(Error::UnexpectedDisconnect);
                 }
             }
@@ -422,9 +1021,15 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
             }
             if sn_ir.timeout_raised() {
                 info!("TIMEOUT interrupt");
+                if self.state == State::WaitConInt {
+                    self.connect_failed(monotonic_secs);
+                }
                 self.reset();
                 return Err(Error::TcpTimeout);
             }
+            if sn_ir.recv_raised() || sn_ir.sendok_raised() {
+                self.idle_since = monotonic_secs;
+            }
             if sn_ir.sendok_raised() {
                 info!("SENDOK interrupt");
                 if self.state == State::WaitAlertSendOk {
@@ -435,6 +1040,12 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
 
         match self.state {
             State::Reset => {
+                if let Some(reconnect_at) = self.reconnect_at {
+                    if monotonic_secs < reconnect_at {
+                        return Ok(Event::CallAfter(reconnect_at - monotonic_secs));
+                    }
+                    self.reconnect_at = None;
+                }
                 match self.tcp_connect(w5500, monotonic_secs) {
                     Ok(after) => return Ok(Event::CallAfter(after)),
                     Err(e) => return Err(self.send_fatal_alert(w5500, e, monotonic_secs)),
@@ -476,17 +1087,21 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
                 if let Err(e) = self.send_client_finished(w5500) {
                     return Err(self.send_fatal_alert(w5500, e, monotonic_secs));
                 }
+                self.idle_since = monotonic_secs;
                 return Ok(Event::HandshakeFinished);
             }
         }
 
         if let Some(elapsed_secs) = self.timeout_elapsed_secs(monotonic_secs) {
-            if elapsed_secs > TIMEOUT_SECS {
+            if elapsed_secs > self.timeout_secs {
                 info!(
                     "timeout waiting for state to transition from {:?}",
                     self.state
                 );
                 let ret = Err(Error::StateTimeout(self.state));
+                if matches!(self.state, State::WaitConInt) {
+                    self.connect_failed(monotonic_secs);
+                }
                 if matches!(self.state, State::WaitDiscon) {
                     self.reset()
                 } else {
@@ -494,11 +1109,11 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
                 }
                 ret
             } else {
-                let call_after: u32 = TIMEOUT_SECS.saturating_sub(elapsed_secs);
+                let call_after: u32 = self.timeout_secs.saturating_sub(elapsed_secs);
                 Ok(Event::CallAfter(call_after))
             }
         } else {
-            Ok(Event::None)
+            Ok(self.idle_event(monotonic_secs).unwrap_or(Event::None))
         }
     }
 
@@ -544,23 +1159,59 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
         monotonic_secs: u32,
     ) -> Result<(), AlertDescription> {
         self.rx.reset();
+        self.alpn_protocol = None;
+        self.max_fragment_length = None;
 
         let mut random: [u8; 32] = [0; 32];
         rng.fill_bytes(&mut random);
 
-        let client_public_key = self.key_schedule.new_client_secret(rng);
+        let key_shares: KeyShares = self.key_schedule.new_client_secret(rng);
+
+        // settings changed after the ticket was received may leave no room
+        // for the ticket in the ClientHello
+        let max_identity_len: usize = client_hello::max_identity_len(
+            N,
+            &self.hostname,
+            self.offer_max_fragment_length,
+            self.alpn_protocols,
+        );
+        let (psk, psk_type, identity, obfuscated_ticket_age): (&[u8], PskType, &[u8], u32) =
+            match self
+                .ticket
+                .as_ref()
+                .filter(|ticket| ticket.identity().len() <= max_identity_len)
+                .and_then(|ticket| Some((ticket, ticket.obfuscated_age(monotonic_secs)?)))
+            {
+                Some((ticket, age)) => {
+                    info!("offering session ticket");
+                    (ticket.psk(), PskType::Resumption, ticket.identity(), age)
+                }
+                // For identities established externally, an obfuscated_ticket_age
+                // of 0 SHOULD be used.
+                None => (self.psk, PskType::External, self.identity, 0),
+            };
 
         // using fragment buffer for TX since it is unused at this point
         let len: usize = client_hello::ser(
             self.rx.as_mut_buf(),
             &random,
             &self.hostname,
-            &client_public_key,
+            &key_shares,
             &mut self.key_schedule,
-            self.psk,
-            self.identity,
+            psk,
+            psk_type,
+            identity,
+            obfuscated_ticket_age,
             Self::RECORD_SIZE_LIMIT,
+            self.offer_max_fragment_length,
+            self.alpn_protocols,
         );
+
+        // clients SHOULD NOT reuse a ticket for multiple connections
+        // https://datatracker.ietf.org/doc/html/rfc8446#appendix-C.4
+        if let Some(ticket) = self.ticket.as_mut() {
+            ticket.clear();
+        }
         let buf: &[u8] = &self.rx.as_buf()[..len];
 
         let mut writer: TcpWriter<W5500> = w5500
@@ -604,7 +1255,7 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
                 self.send_encrypted_record(
                     w5500,
                     ContentType::Alert,
-                    &[level.into(), description.into()],
+                    &[&[level.into(), description.into()]],
                 )
                 .map_err(AlertDescription::map_w5500)?;
             } else {
@@ -706,7 +1357,7 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
         //
         // We use the record size limit extension, so we can limit this to
         // our RX buffer size
-        if header.length() > Self::RECORD_SIZE_LIMIT {
+        if header.length() > self.record_length_limit() {
             Err(AlertDescription::RecordOverflow)
         } else if header.length().saturating_add(RecordHeader::LEN as u16) > reader.stream_len() {
             Ok(None)
@@ -759,27 +1410,20 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
             if matches!(header.content_type(), ContentType::ApplicationData) {
                 debug!("decrypting record");
 
-                let (key, nonce): ([u8; 16], [u8; 12]) =
-                    match self.key_schedule.server_key_and_nonce() {
-                        Some(x) => x,
-                        None => {
-                            error!("received ApplicationData before establishing keys");
-                            return Err(self.send_fatal_alert(
-                                w5500,
-                                AlertDescription::UnexpectedMessage,
-                                monotonic_secs,
-                            ));
-                        }
-                    };
+                let cipher: Cipher = match self.key_schedule.server_cipher(header.as_bytes()) {
+                    Some(cipher) => cipher,
+                    None => {
+                        error!("received ApplicationData before establishing keys");
+                        return Err(self.send_fatal_alert(
+                            w5500,
+                            AlertDescription::UnexpectedMessage,
+                            monotonic_secs,
+                        ));
+                    }
+                };
 
-                match crypto::decrypt_record_inplace(
-                    w5500,
-                    self.sn,
-                    &key,
-                    &nonce,
-                    &header,
-                    &mut self.rx,
-                ) {
+                match crypto::decrypt_record_inplace(w5500, self.sn, cipher, &header, &mut self.rx)
+                {
                     Ok(Ok(content_type)) => content_type,
                     Ok(Err(x)) => {
                         error!("ContentType {:02X}", x);
@@ -802,10 +1446,7 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
 
         if matches!(actual_content_type, ContentType::ApplicationData) {
             self.rx.increment_application_data_tail(
-                header
-                    .length()
-                    .saturating_sub((GCM_TAG_LEN + 1) as u16)
-                    .into(),
+                header.length().saturating_sub((TAG_LEN + 1) as u16).into(),
             );
         }
 
@@ -921,65 +1562,105 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
         let verify_data: GenericArray<u8, U32> = self.key_schedule.client_finished_verify_data();
         let data: [u8; 36] = handshake::client_finished(&verify_data);
 
-        self.send_encrypted_record(w5500, ContentType::Handshake, &data)
+        self.send_encrypted_record(w5500, ContentType::Handshake, &[&data])
             .map_err(AlertDescription::map_w5500)?;
         self.set_state(State::Connected);
 
         // master secrets are only ClientHello..server Finished
         // no need to update the key schedule for this.
         self.key_schedule.initialize_master_secret();
+        if self.ticket.is_some() {
+            self.key_schedule.initialize_resumption_master_secret(&data);
+        }
 
         Ok(())
     }
 
-    // helper to send an encrypted record without a round-trip to the socket
+    /// Maximum length of the plaintext in a sent record.
+    fn fragment_length_limit(&self) -> u16 {
+        const MAX_FRAGMENT_LENGTH: u16 = 1 << 14;
+        self.max_fragment_length
+            .map_or(MAX_FRAGMENT_LENGTH, MaxFragmentLength::bytes)
+    }
+
+    // helper to send encrypted records without a round-trip to the socket
     // buffers
+    //
+    // the record data is the concatenation of `bufs`, split into multiple
+    // records if it exceeds the fragment length limit
     fn send_encrypted_record<W5500: Registers>(
         &mut self,
         w5500: &mut W5500,
         content_type: ContentType,
-        data: &[u8],
+        bufs: &[&[u8]],
     ) -> Result<(), HlError<W5500::Error>> {
         const CONTENT_TYPE_LEN: usize = 1;
-        let data_len: u16 = unwrap!((data.len() + GCM_TAG_LEN + CONTENT_TYPE_LEN).try_into());
-
-        let header: [u8; 5] = [
-            ContentType::ApplicationData.into(),
-            TlsVersion::V1_2.msb(),
-            TlsVersion::V1_2.lsb(),
-            (data_len >> 8) as u8,
-            data_len as u8,
-        ];
+        let fragment_length_limit: usize = self.fragment_length_limit().into();
+        let mut remain: usize = bufs.iter().map(|buf| buf.len()).sum();
 
         let mut writer: TcpWriter<W5500> = w5500.tcp_writer(self.sn)?;
 
-        // write the record header
-        writer.write_all(&header)?;
+        let mut bufs = bufs.iter().copied();
+        let mut buf: &[u8] = &[];
+        loop {
+            let fragment_len: usize = min(remain, fragment_length_limit);
+            remain -= fragment_len;
+            let data_len: u16 = unwrap!((fragment_len + TAG_LEN + CONTENT_TYPE_LEN).try_into());
+
+            let header: [u8; 5] = [
+                ContentType::ApplicationData.into(),
+                TlsVersion::V1_2.msb(),
+                TlsVersion::V1_2.lsb(),
+                (data_len >> 8) as u8,
+                data_len as u8,
+            ];
+
+            // write the record header
+            writer.write_all(&header)?;
+
+            let mut cipher: Cipher = self.key_schedule.client_cipher(&header).unwrap();
+
+            // write the record data in 128-bit chunks
+            let mut block: [u8; 16] = [0; 16];
+            let mut block_len: usize = 0;
+            let mut fragment_remain: usize = fragment_len;
+            while fragment_remain != 0 {
+                if buf.is_empty() {
+                    buf = unwrap!(bufs.next());
+                    continue;
+                }
+                let n: usize = min(block.len() - block_len, min(buf.len(), fragment_remain));
+                block[block_len..(block_len + n)].copy_from_slice(&buf[..n]);
+                block_len += n;
+                buf = &buf[n..];
+                fragment_remain -= n;
+
+                if block_len == block.len() {
+                    cipher.encrypt_block_inplace(&mut block);
+                    writer.write_all(&block)?;
+                    block_len = 0;
+                }
+            }
+
+            // write the remaining data
+            block[block_len..].fill(0);
+            // append the content type
+            block[block_len] = content_type as u8;
+            let remainder_len: usize = block_len + CONTENT_TYPE_LEN;
+            cipher.encrypt_remainder_inplace(&mut block, remainder_len);
+            writer.write_all(&block[..remainder_len])?;
 
-        let (key, nonce): ([u8; 16], [u8; 12]) = self.key_schedule.client_key_and_nonce().unwrap();
-        let mut cipher = crate::crypto::Aes128Gcm::new(&key, &nonce, &header);
+            // write the AES-GCM authentication tag
+            let tag: [u8; TAG_LEN] = cipher.finish();
+            writer.write_all(&tag)?;
 
-        // write the record data in 128-bit chunks
-        let mut chunks = data.chunks_exact(16);
-        for chunk in &mut chunks {
-            let mut mut_chunck: [u8; 16] = chunk.try_into().unwrap();
-            cipher.encrypt_block_inplace(&mut mut_chunck);
-            writer.write_all(&mut_chunck)?;
+            self.key_schedule.increment_write_record_sequence_number();
+
+            if remain == 0 {
+                break;
+            }
         }
 
-        // write the remaining data
-        let rem = chunks.remainder();
-        let mut padded_block: [u8; 16] = [0; 16];
-        padded_block[..rem.len()].copy_from_slice(rem);
-        // append the content type
-        padded_block[rem.len()] = content_type as u8;
-        let remainder_len: usize = rem.len() + CONTENT_TYPE_LEN;
-        cipher.encrypt_remainder_inplace(&mut padded_block, remainder_len);
-        writer.write_all(&padded_block[..remainder_len])?;
-
-        // write the AES-GCM authentication tag
-        let tag: [u8; GCM_TAG_LEN] = cipher.finish();
-        writer.write_all(&tag)?;
         writer.send()?;
 
         Ok(())
@@ -994,6 +1675,10 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
                 Some(s) => s,
             };
 
+            if let Some(callback) = self.on_handshake_message {
+                callback(header.msg_type(), header.length());
+            }
+
             match header.msg_type() {
                 Ok(HandshakeType::ClientHello) => {
                     error!("unexpected ClientHello");
@@ -1004,11 +1689,15 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
                         error!("unexpected ServerHello in state {:?}", self.state);
                         return Err(AlertDescription::UnexpectedMessage);
                     } else {
-                        let public_key: PublicKey = handshake::recv_server_hello(&mut reader)?;
+                        // pinned clients do not offer a pre-shared key
+                        let psk_offered: bool = self.spki_pin.is_none();
+                        let (cipher_suite, public_key): (CipherSuite, PublicKey) =
+                            handshake::recv_server_hello(&mut reader, psk_offered)?;
 
+                        self.key_schedule.set_cipher_suite(cipher_suite);
                         self.key_schedule.set_server_public_key(public_key);
                         self.key_schedule.set_transcript_hash(hash.clone());
-                        self.key_schedule.initialize_handshake_secret();
+                        self.key_schedule.initialize_handshake_secret()?;
                         self.set_state_with_timeout(State::WaitEncryptedExtensions, monotonic_secs);
                     }
                 }
@@ -1022,7 +1711,21 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
                         // message, it MAY send a NewSessionTicket message.
                         // The client MAY use this PSK for future handshakes by including the
                         // ticket value in the "pre_shared_key" extension in its ClientHello
-                        info!("NewSessionTicket is unused");
+                        match self.ticket.as_mut() {
+                            Some(ticket) => handshake::recv_new_session_ticket(
+                                &mut reader,
+                                &self.key_schedule,
+                                ticket,
+                                client_hello::max_identity_len(
+                                    N,
+                                    &self.hostname,
+                                    self.offer_max_fragment_length,
+                                    self.alpn_protocols,
+                                ),
+                                monotonic_secs,
+                            )?,
+                            None => info!("NewSessionTicket is unused"),
+                        }
                     }
                 }
                 Ok(HandshakeType::EndOfEarlyData) => {
@@ -1037,18 +1740,46 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
                         return Err(AlertDescription::UnexpectedMessage);
                     }
 
-                    handshake::recv_encrypted_extensions(&mut reader)?;
+                    let server_extensions: ServerExtensions = handshake::recv_encrypted_extensions(
+                        &mut reader,
+                        self.alpn_protocols,
+                        self.offer_max_fragment_length,
+                    )?;
+                    self.alpn_protocol = server_extensions
+                        .alpn_protocol
+                        .map(|idx| self.alpn_protocols[idx]);
+                    self.max_fragment_length = server_extensions.max_fragment_length;
+                    let next_state: State = if self.spki_pin.is_some() {
+                        State::WaitCertificate
+                    } else {
+                        State::WaitFinished
+                    };
+                    self.set_state_with_timeout(next_state, monotonic_secs);
+                }
+                Ok(HandshakeType::Certificate) => {
+                    let spki_pin: &[u8; 32] = match self.spki_pin.as_ref() {
+                        Some(spki_pin) if self.state == State::WaitCertificate => spki_pin,
+                        _ => {
+                            error!("unexpected Certificate in state {:?}", self.state);
+                            return Err(AlertDescription::UnexpectedMessage);
+                        }
+                    };
+
+                    let key: VerifyingKey = handshake::recv_certificate(&mut reader, spki_pin)?;
+                    self.key_schedule.set_server_certificate_key(key);
+                    self.set_state_with_timeout(State::WaitCertificateVerify, monotonic_secs);
+                }
+                Ok(HandshakeType::CertificateVerify) => {
+                    if self.state != State::WaitCertificateVerify {
+                        error!("unexpected CertificateVerify in state {:?}", self.state);
+                        return Err(AlertDescription::UnexpectedMessage);
+                    }
+
+                    handshake::recv_certificate_verify(&mut reader, &self.key_schedule)?;
                     self.set_state_with_timeout(State::WaitFinished, monotonic_secs);
                 }
-                Ok(
-                    hs_type @ (HandshakeType::Certificate
-                    | HandshakeType::CertificateRequest
-                    | HandshakeType::CertificateVerify),
-                ) => {
-                    error!(
-                        "unexpected extension {:?} certificate authentication not supported",
-                        hs_type
-                    );
+                Ok(HandshakeType::CertificateRequest) => {
+                    error!("CertificateRequest client certificates are not supported");
                     return Err(AlertDescription::UnexpectedMessage);
                 }
                 Ok(HandshakeType::Finished) => {
@@ -1158,6 +1889,135 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
         self.state == State::Connected
     }
 
+    /// Current state of the client.
+    ///
+    /// This is intended for diagnostics, such as logging the handshake
+    /// progress.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # const MY_KEY: [u8; 1] = [0];
+    /// use w5500_tls::{
+    ///     Client, State,
+    ///     {
+    ///         hl::Hostname,
+    ///         ll::{
+    ///             net::{Ipv4Addr, SocketAddrV4},
+    ///             Sn,
+    ///         },
+    ///     },
+    /// };
+    ///
+    /// static mut RX: [u8; 2048] = [0; 2048];
+    ///
+    /// const DST: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 168, 0, 4), 8883);
+    /// const HOSTNAME: Hostname = Hostname::new_unwrapped("server.local");
+    /// const SRC_PORT: u16 = 1234;
+    /// const TLS_SN: Sn = Sn::Sn4;
+    ///
+    /// let tls_client: Client<2048> = Client::new(
+    ///     TLS_SN,
+    ///     SRC_PORT,
+    ///     HOSTNAME,
+    ///     DST,
+    ///     b"mykeyidentity",
+    ///     &MY_KEY,
+    ///     unsafe { &mut RX },
+    /// );
+    /// assert_eq!(tls_client.state(), State::Reset);
+    /// assert_eq!(tls_client.seconds_until_timeout(0), None);
+    /// ```
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// Seconds remaining until the current state times out.
+    ///
+    /// Returns `None` if the current state does not have a timeout.
+    /// Once this reaches `0` [`process`](Self::process) will return
+    /// [`Error::StateTimeout`] when the next second elapses.
+    /// This is the same value as [`Event::CallAfter`].
+    ///
+    /// `monotonic_secs` is the same monotonic time passed to
+    /// [`process`](Self::process).
+    pub fn seconds_until_timeout(&self, monotonic_secs: u32) -> Option<u32> {
+        self.timeout_elapsed_secs(monotonic_secs)
+            .map(|elapsed_secs| self.timeout_secs.saturating_sub(elapsed_secs))
+    }
+
+    /// Export keying material from the connection.
+    ///
+    /// This implements the TLS 1.3 exporter, filling `out` with keying
+    /// material derived from the connection secrets, `label`, and `context`.
+    /// Both peers derive the same keying material, which is useful for
+    /// binding application layer authentication to the TLS connection.
+    ///
+    /// # Errors
+    ///
+    /// This method can only return:
+    ///
+    /// * [`Error::NotConnected`]
+    ///
+    /// # Panics
+    ///
+    /// * `label` is longer than 249 bytes.
+    /// * `out` is longer than 8160 bytes.
+    ///
+    /// # References
+    ///
+    /// * [RFC 8446 Section 7.5](https://datatracker.ietf.org/doc/html/rfc8446#section-7.5)
+    /// * [RFC 5705](https://datatracker.ietf.org/doc/html/rfc5705)
+    pub fn export_keying_material(
+        &self,
+        label: &[u8],
+        context: &[u8],
+        out: &mut [u8],
+    ) -> Result<(), Error> {
+        if !self.connected() {
+            return Err(Error::NotConnected);
+        }
+        self.key_schedule
+            .export_keying_material(label, context, out)
+            .ok_or(Error::NotConnected)
+    }
+
+    /// Start an orderly shutdown of the connection.
+    ///
+    /// This sends a `close_notify` alert to the server, subsequent calls to
+    /// [`process`](Self::process) will disconnect the TCP stream, and
+    /// return [`Event::Disconnect`] when the disconnection is complete.
+    ///
+    /// [`process`](Self::process) will start a new connection if it is
+    /// called after returning [`Event::Disconnect`].
+    ///
+    /// # Errors
+    ///
+    /// This method can only return:
+    ///
+    /// * [`Error::NotConnected`]
+    ///
+    /// # References
+    ///
+    /// * [RFC 8446 Section 6.1](https://datatracker.ietf.org/doc/html/rfc8446#section-6.1)
+    pub fn close<W5500: Registers>(
+        &mut self,
+        w5500: &mut W5500,
+        monotonic_secs: u32,
+    ) -> Result<(), Error> {
+        if !self.connected() {
+            Err(Error::NotConnected)
+        } else {
+            self.send_alert(
+                w5500,
+                AlertLevel::Warning,
+                AlertDescription::CloseNotify,
+                monotonic_secs,
+            );
+            Ok(())
+        }
+    }
+
     /// Create a TLS writer.
     ///
     /// This returns a [`TlsWriter`] structure, which contains functions to
@@ -1170,6 +2030,9 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
     /// write all your data, read it back, encrypt it, then write it back
     /// before sending.  This is useful for low-memory applications.
     ///
+    /// The writer sends a single record, the data written is limited to the
+    /// negotiated [`max_fragment_length`](Self::max_fragment_length).
+    ///
     /// # Errors
     ///
     /// This method can only return:
@@ -1189,8 +2052,7 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
     {
         const TRAILING_CONTENT_TYPE_LEN: u16 = 1;
         const RECORD_HEADER_LEN: u16 = RecordHeader::LEN as u16;
-        const TLS_OVERHEAD: u16 =
-            RECORD_HEADER_LEN + (GCM_TAG_LEN as u16) + TRAILING_CONTENT_TYPE_LEN;
+        const TLS_OVERHEAD: u16 = RECORD_HEADER_LEN + (TAG_LEN as u16) + TRAILING_CONTENT_TYPE_LEN;
 
         if !self.connected() {
             return Err(Error::NotConnected);
@@ -1202,6 +2064,8 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
             .map_err(|_| Error::Client(Alert::new_warning(AlertDescription::InternalError)))?
             .checked_sub(TLS_OVERHEAD)
             .ok_or_else(|| Error::Client(Alert::new_warning(AlertDescription::InternalError)))?;
+        // a single record may not exceed the fragment length limit
+        let sn_tx_fsr: u16 = min(sn_tx_fsr, self.fragment_length_limit());
 
         // advance write pointer by 5 to leave room for the record header
         let sn_tx_wr: u16 = w5500
@@ -1228,6 +2092,10 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
     /// This should only be used when the handshake has completed, otherwise
     /// the server will send an `unexpected_message` alert.
     ///
+    /// Data exceeding the negotiated
+    /// [`max_fragment_length`](Self::max_fragment_length) is split into
+    /// multiple records.
+    ///
     /// # Errors
     ///
     /// This method can only return:
@@ -1242,7 +2110,34 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
         if !self.connected() {
             Err(Error::NotConnected)
         } else {
-            self.send_encrypted_record(w5500, ContentType::ApplicationData, data)
+            self.send_encrypted_record(w5500, ContentType::ApplicationData, &[data])
+                .map_err(|_| Error::Client(Alert::new_warning(AlertDescription::InternalError)))
+        }
+    }
+
+    /// Send the concatenation of multiple buffers to the remote host.
+    ///
+    /// This is the same as [`write_all`](Self::write_all), except the data
+    /// is gathered from multiple buffers.
+    /// The buffers are split into records as if they were a single contiguous
+    /// buffer, avoiding the overhead of sending a record per buffer, or
+    /// copying the buffers into a contiguous buffer.
+    ///
+    /// # Errors
+    ///
+    /// This method can only return:
+    ///
+    /// * [`Error::Client`] with [`AlertDescription::InternalError`]
+    /// * [`Error::NotConnected`]
+    pub fn write_vectored<W5500: Registers>(
+        &mut self,
+        w5500: &mut W5500,
+        bufs: &[&[u8]],
+    ) -> Result<(), Error> {
+        if !self.connected() {
+            Err(Error::NotConnected)
+        } else {
+            self.send_encrypted_record(w5500, ContentType::ApplicationData, bufs)
                 .map_err(|_| Error::Client(Alert::new_warning(AlertDescription::InternalError)))
         }
     }
@@ -1263,3 +2158,310 @@ impl<'hn, 'psk, 'b, const N: usize> Client<'hn, 'psk, 'b, N> {
         self.rx.app_data_reader()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Client, Error, Event, State};
+    use w5500_hl::{
+        ll::{
+            net::{Ipv4Addr, SocketAddrV4},
+            Registers, Sn, SocketStatus,
+        },
+        Hostname, Tcp,
+    };
+
+    fn client<'b>(rx: &'b mut [u8; 2048], dst: SocketAddrV4) -> Client<'static, 'static, 'b, 2048> {
+        Client::new(
+            Sn::Sn0,
+            1234,
+            Hostname::new_unwrapped("server.local"),
+            dst,
+            b"identity",
+            &[0],
+            rx,
+        )
+    }
+
+    #[test]
+    fn idle_event() {
+        let mut rx: [u8; 2048] = [0; 2048];
+        let mut client = client(&mut rx, SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8883));
+
+        // disabled by default
+        client.state = State::Connected;
+        assert_eq!(client.idle_event(1000), None);
+
+        client.set_idle_interval_secs(Some(60));
+        client.idle_since = 100;
+        assert_eq!(client.idle_event(159), None);
+        assert_eq!(client.idle_event(160), Some(Event::Idle));
+        assert_eq!(client.idle_event(219), None);
+        assert_eq!(client.idle_event(220), Some(Event::Idle));
+
+        // only when connected
+        client.state = State::WaitServerHello;
+        assert_eq!(client.idle_event(1000), None);
+    }
+
+    #[test]
+    fn close() {
+        use std::{io::Read, net::TcpListener};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let dst = SocketAddrV4::new(Ipv4Addr::LOCALHOST, listener.local_addr().unwrap().port());
+
+        let mut rx: [u8; 2048] = [0; 2048];
+        let mut client = client(&mut rx, dst);
+        let mut w5500 = w5500_regsim::W5500::default();
+
+        assert_eq!(client.close(&mut w5500, 0), Err(Error::NotConnected));
+
+        w5500.tcp_connect(Sn::Sn0, 1234, &dst).unwrap();
+        let (mut server, _) = listener.accept().unwrap();
+        while w5500.sn_sr(Sn::Sn0).unwrap() != Ok(SocketStatus::Established) {}
+
+        client.state = State::Connected;
+        client.close(&mut w5500, 0).unwrap();
+        assert_eq!(client.state, State::WaitAlertSendOk);
+
+        let mut alert: [u8; 7] = [0; 7];
+        server.read_exact(&mut alert).unwrap();
+        // unencrypted warning close_notify alert
+        assert_eq!(alert, [0x15, 0x03, 0x03, 0x00, 0x02, 0x01, 0x00]);
+    }
+
+    #[test]
+    fn write_all_max_fragment_length() {
+        use super::MaxFragmentLength;
+        use std::{io::Read, net::TcpListener};
+        use w5500_hl::io::Seek;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let dst = SocketAddrV4::new(Ipv4Addr::LOCALHOST, listener.local_addr().unwrap().port());
+
+        let mut rx: [u8; 2048] = [0; 2048];
+        let mut client = client(&mut rx, dst);
+        let mut w5500 = w5500_regsim::W5500::default();
+
+        w5500.tcp_connect(Sn::Sn0, 1234, &dst).unwrap();
+        let (mut server, _) = listener.accept().unwrap();
+        while w5500.sn_sr(Sn::Sn0).unwrap() != Ok(SocketStatus::Established) {}
+
+        client.state = State::Connected;
+        client.key_schedule.initialize_master_secret();
+        client.max_fragment_length = Some(MaxFragmentLength::Bytes512);
+
+        assert_eq!(client.writer(&mut w5500).unwrap().stream_len(), 512);
+
+        client.write_all(&mut w5500, &[0xAA; 1024]).unwrap();
+
+        // two records, each with 512 bytes of data, the content type,
+        // and the authentication tag
+        let mut records: [[u8; 0x211]; 2] = [[0; 0x211]; 2];
+        for record in records.iter_mut() {
+            let mut header: [u8; 5] = [0; 5];
+            server.read_exact(&mut header).unwrap();
+            assert_eq!(header, [0x17, 0x03, 0x03, 0x02, 0x11]);
+            server.read_exact(record).unwrap();
+        }
+        // the same plaintext is encrypted with a different nonce
+        assert_ne!(records[0], records[1]);
+    }
+
+    #[test]
+    fn write_vectored_max_fragment_length() {
+        use super::MaxFragmentLength;
+        use std::{io::Read, net::TcpListener};
+
+        // the first buffer straddles the record boundary
+        let data: [u8; 1024] = core::array::from_fn(|n| n as u8);
+        let (a, b) = data.split_at(600);
+
+        let mut records: [[u8; 2 * (5 + 0x211)]; 2] = [[0; 2 * (5 + 0x211)]; 2];
+        for (vectored, records) in [false, true].into_iter().zip(records.iter_mut()) {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let dst = SocketAddrV4::new(Ipv4Addr::LOCALHOST, listener.local_addr().unwrap().port());
+
+            let mut rx: [u8; 2048] = [0; 2048];
+            let mut client = client(&mut rx, dst);
+            let mut w5500 = w5500_regsim::W5500::default();
+
+            w5500.tcp_connect(Sn::Sn0, 1234, &dst).unwrap();
+            let (mut server, _) = listener.accept().unwrap();
+            while w5500.sn_sr(Sn::Sn0).unwrap() != Ok(SocketStatus::Established) {}
+
+            client.state = State::Connected;
+            client.key_schedule.initialize_master_secret();
+            client.max_fragment_length = Some(MaxFragmentLength::Bytes512);
+
+            if vectored {
+                client.write_vectored(&mut w5500, &[a, b]).unwrap();
+            } else {
+                client.write_all(&mut w5500, &data).unwrap();
+            }
+
+            server.read_exact(records).unwrap();
+        }
+
+        assert_eq!(records[0], records[1]);
+    }
+
+    #[test]
+    fn recv_max_fragment_length() {
+        use super::{AlertDescription, MaxFragmentLength};
+        use std::{io::Write, net::TcpListener};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let dst = SocketAddrV4::new(Ipv4Addr::LOCALHOST, listener.local_addr().unwrap().port());
+
+        let mut rx: [u8; 2048] = [0; 2048];
+        let mut client = client(&mut rx, dst);
+        let mut w5500 = w5500_regsim::W5500::default();
+
+        w5500.tcp_connect(Sn::Sn0, 1234, &dst).unwrap();
+        let (mut server, _) = listener.accept().unwrap();
+        while w5500.sn_sr(Sn::Sn0).unwrap() != Ok(SocketStatus::Established) {}
+
+        client.max_fragment_length = Some(MaxFragmentLength::Bytes512);
+        // 512 bytes of data, the content type, and the authentication tag
+        let limit: u16 = client.record_length_limit();
+        assert_eq!(limit, 512 + 1 + 16);
+
+        let mut recv_header = |len: u16| {
+            let [msb, lsb] = len.to_be_bytes();
+            server.write_all(&[0x17, 0x03, 0x03, msb, lsb]).unwrap();
+            while w5500.sn_rx_rsr(Sn::Sn0).unwrap() < 5 {}
+            let result = client.recv_header(&mut w5500);
+            // discard the header
+            w5500.tcp_read(Sn::Sn0, &mut [0; 5]).unwrap();
+            result
+        };
+
+        // the record body has not been received
+        assert!(matches!(recv_header(limit), Ok(None)));
+        assert!(matches!(
+            recv_header(limit + 1),
+            Err(AlertDescription::RecordOverflow)
+        ));
+    }
+
+    #[test]
+    fn reconnect_backoff() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let dst = SocketAddrV4::new(Ipv4Addr::LOCALHOST, listener.local_addr().unwrap().port());
+        // connections to dst are refused
+        drop(listener);
+
+        let mut rx: [u8; 2048] = [0; 2048];
+        let mut client = client(&mut rx, dst);
+        client.set_reconnect_backoff(Some((1, 4)));
+        let mut w5500 = w5500_regsim::W5500::default();
+        let mut rng = rand_core::OsRng;
+
+        let mut connect = |client: &mut Client<2048>, monotonic_secs: u32| {
+            assert_eq!(
+                client.process(&mut w5500, &mut rng, monotonic_secs),
+                Ok(Event::CallAfter(client.timeout_secs))
+            );
+            // the simulator connects in the background
+            let result = loop {
+                match client.process(&mut w5500, &mut rng, monotonic_secs) {
+                    Ok(Event::CallAfter(secs)) if secs == client.timeout_secs => (),
+                    result => break result,
+                }
+            };
+            assert_eq!(result, Err(Error::UnexpectedDisconnect));
+            client.process(&mut w5500, &mut rng, monotonic_secs)
+        };
+
+        assert_eq!(connect(&mut client, 0), Ok(Event::CallAfter(1)));
+        assert_eq!(connect(&mut client, 1), Ok(Event::CallAfter(2)));
+        assert_eq!(connect(&mut client, 3), Ok(Event::CallAfter(4)));
+        assert_eq!(connect(&mut client, 7), Ok(Event::CallAfter(4)));
+    }
+
+    #[test]
+    fn reconnect_backoff_clamped() {
+        let mut rx: [u8; 2048] = [0; 2048];
+        let mut client = client(&mut rx, SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8883));
+
+        let delays = |client: &mut Client<2048>| -> [u32; 4] {
+            core::array::from_fn(|_| {
+                client.connect_failed(0);
+                client.reconnect_at.unwrap()
+            })
+        };
+
+        // an initial backoff of zero would never back off
+        client.set_reconnect_backoff(Some((0, 4)));
+        assert_eq!(delays(&mut client), [1, 2, 4, 4]);
+
+        // the maximum is never exceeded
+        client.set_reconnect_backoff(Some((8, 2)));
+        assert_eq!(delays(&mut client), [8, 8, 8, 8]);
+    }
+
+    #[test]
+    fn on_handshake_message() {
+        use super::{AlertDescription, HandshakeType};
+        use std::sync::Mutex;
+
+        static MESSAGES: Mutex<Vec<(Result<HandshakeType, u8>, u32)>> = Mutex::new(Vec::new());
+
+        fn record(msg_type: Result<HandshakeType, u8>, length: u32) {
+            MESSAGES.lock().unwrap().push((msg_type, length));
+        }
+
+        let mut rx: [u8; 2048] = [0; 2048];
+        let mut client = client(&mut rx, SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8883));
+        client.on_handshake_message(Some(record));
+        client.state = State::WaitServerHello;
+
+        // unknown message types are reported before they are rejected
+        client
+            .rx
+            .extend_from_slice(&[0xFF, 0x00, 0x00, 0x02, 0xAB, 0xCD])
+            .unwrap();
+        assert_eq!(
+            client.recv_handshake(0),
+            Err(AlertDescription::UnexpectedMessage)
+        );
+        assert_eq!(MESSAGES.lock().unwrap().as_slice(), &[(Err(0xFF), 2)]);
+
+        client.rx.reset();
+        client
+            .rx
+            .extend_from_slice(&[0x14, 0x00, 0x00, 0x00])
+            .unwrap();
+        assert_eq!(
+            client.recv_handshake(0),
+            Err(AlertDescription::UnexpectedMessage)
+        );
+        assert_eq!(
+            MESSAGES.lock().unwrap().as_slice(),
+            &[(Err(0xFF), 2), (Ok(HandshakeType::Finished), 0)]
+        );
+    }
+
+    #[test]
+    fn alpn_protocol_outlives_protocols() {
+        let mut rx: [u8; 2048] = [0; 2048];
+        let mut client = client(&mut rx, SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8883));
+        client.set_alpn_protocols(&[b"h2", b"mqtt"]);
+        client.alpn_protocol = Some(client.alpn_protocols[1]);
+
+        client.set_alpn_protocols(&[b"h2"]);
+        assert_eq!(client.alpn_protocol(), Some(b"mqtt".as_slice()));
+    }
+
+    #[test]
+    fn export_keying_material_not_connected() {
+        let mut rx: [u8; 2048] = [0; 2048];
+        let client = client(&mut rx, SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8883));
+        let mut out: [u8; 32] = [0; 32];
+        assert_eq!(
+            client.export_keying_material(b"EXPORTER-test", &[], &mut out),
+            Err(Error::NotConnected)
+        );
+    }
+}
@@ -0,0 +1,103 @@
+//! Multiple TLS clients sharing one W5500.
+
+use std::{
+    io::Read,
+    net::{TcpListener, TcpStream},
+    time::{Duration, Instant},
+};
+use w5500_regsim::W5500;
+use w5500_tls::{
+    hl::Hostname,
+    ll::{
+        net::{Ipv4Addr, SocketAddrV4},
+        Sn,
+    },
+    Client, Event, State,
+};
+
+const KEY: [u8; 1] = [0];
+
+fn listen() -> (TcpListener, SocketAddrV4) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port: u16 = listener.local_addr().unwrap().port();
+    (listener, SocketAddrV4::new(Ipv4Addr::LOCALHOST, port))
+}
+
+/// Read a TLS record, returning the content type and fragment.
+fn read_record(stream: &mut TcpStream) -> (u8, Vec<u8>) {
+    let mut header: [u8; 5] = [0; 5];
+    stream.read_exact(&mut header).unwrap();
+    let len: u16 = u16::from_be_bytes([header[3], header[4]]);
+    let mut fragment: Vec<u8> = vec![0; len.into()];
+    stream.read_exact(&mut fragment).unwrap();
+    (header[0], fragment)
+}
+
+#[test]
+fn two_clients() {
+    let (listener_a, dst_a) = listen();
+    let (listener_b, dst_b) = listen();
+
+    let mut rx_a: [u8; 2048] = [0; 2048];
+    let mut rx_b: [u8; 4096] = [0; 4096];
+
+    let mut client_a: Client<2048> = Client::new(
+        Sn::Sn0,
+        50140,
+        Hostname::new_unwrapped("a.local"),
+        dst_a,
+        b"a",
+        &KEY,
+        &mut rx_a,
+    );
+    let mut client_b: Client<4096> = Client::new(
+        Sn::Sn1,
+        50141,
+        Hostname::new_unwrapped("b.local"),
+        dst_b,
+        b"b",
+        &KEY,
+        &mut rx_b,
+    );
+
+    let mut w5500 = W5500::default();
+    let mut rng = rand_core::OsRng;
+
+    // both clients are driven in one loop with the same device, until both
+    // have connected and sent a ClientHello
+    let start: Instant = Instant::now();
+    while client_a.state() != State::WaitServerHello || client_b.state() != State::WaitServerHello {
+        let event_a: Event = client_a.process(&mut w5500, &mut rng, 0).unwrap();
+        let event_b: Event = client_b.process(&mut w5500, &mut rng, 0).unwrap();
+        assert!(matches!(event_a, Event::CallAfter(_)));
+        assert!(matches!(event_b, Event::CallAfter(_)));
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "timeout waiting for ClientHello"
+        );
+    }
+
+    let (mut server_a, _) = listener_a.accept().unwrap();
+    let (mut server_b, _) = listener_b.accept().unwrap();
+
+    let (content_type_a, hello_a) = read_record(&mut server_a);
+    let (content_type_b, hello_b) = read_record(&mut server_b);
+
+    // handshake records containing a ClientHello
+    assert_eq!(content_type_a, 22);
+    assert_eq!(content_type_b, 22);
+    assert_eq!(hello_a[0], 1);
+    assert_eq!(hello_b[0], 1);
+
+    // each client uses its own hostname and random
+    let contains = |haystack: &[u8], needle: &[u8]| {
+        haystack
+            .windows(needle.len())
+            .any(|window| window == needle)
+    };
+    assert!(contains(&hello_a, b"a.local"));
+    assert!(contains(&hello_b, b"b.local"));
+    assert!(!contains(&hello_a, b"b.local"));
+    assert!(!contains(&hello_b, b"a.local"));
+    assert_ne!(hello_a[6..38], hello_b[6..38]);
+}